@@ -0,0 +1,82 @@
+//! Throughput of offline save link signing
+//!
+//! Run with `cargo bench --bench save_links`. `benches/test_key.pem` is a
+//! throwaway key generated for these benchmarks; it is not registered with
+//! any service account.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use porter::google::{
+    EventTicketObject, GoogleWalletClient, GoogleWalletConfig, JwtObjectPayload, JwtPayload, State,
+};
+use std::borrow::Cow;
+
+const BATCH: usize = 1_000;
+
+fn config() -> GoogleWalletConfig {
+    GoogleWalletConfig {
+        issuer_id: "3388000000022222228".to_string(),
+        service_account_email: "wallet@example.iam.gserviceaccount.com".to_string(),
+        private_key: include_str!("test_key.pem").to_string(),
+    }
+}
+
+fn payloads() -> Vec<JwtObjectPayload> {
+    (0..BATCH)
+        .map(|i| JwtObjectPayload {
+            event_ticket_objects: Some(vec![EventTicketObject {
+                id: format!("3388000000022222228.ticket_{}", i),
+                class_id: "3388000000022222228.concert".to_string(),
+                state: Some(State::Active),
+                ticket_holder_name: Some("Ada Lovelace".to_string()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn save_links(c: &mut Criterion) {
+    let client = GoogleWalletClient::new(config());
+    let payloads = payloads();
+
+    let mut group = c.benchmark_group("save_links");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.sample_size(10);
+
+    group.bench_function("sign_save_urls", |b| {
+        b.iter(|| client.sign_save_urls(black_box(&payloads)).unwrap())
+    });
+
+    // Parsing the key and cloning each payload per link, as a baseline
+    group.bench_function("jsonwebtoken_per_link", |b| {
+        let config = config();
+        b.iter_batched(
+            || payloads.clone(),
+            |payloads| {
+                payloads
+                    .into_iter()
+                    .map(|payload| {
+                        let claims = JwtPayload {
+                            iss: config.service_account_email.clone().into(),
+                            aud: "google".into(),
+                            typ: "savetowallet".into(),
+                            iat: 1_800_000_000,
+                            exp: None,
+                            origins: None,
+                            payload: Cow::Owned(payload),
+                        };
+                        let key = EncodingKey::from_rsa_pem(config.private_key.as_bytes()).unwrap();
+                        encode(&Header::new(Algorithm::RS256), &claims, &key).unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, save_links);
+criterion_main!(benches);
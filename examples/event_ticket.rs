@@ -1,7 +1,7 @@
 use porter::error::Result;
 use porter::google::{
-    Barcode, EventSeat, EventTicketObject, GenericObject, GoogleWalletClient, GoogleWalletConfig,
-    LocalizedString, TranslatedString,
+    Barcode, EventSeat, EventTicketObject, GoogleWalletClient, GoogleWalletConfig,
+    JwtObjectPayload, LocalizedString, State, TranslatedString,
 };
 
 #[tokio::main]
@@ -24,8 +24,9 @@ async fn main() -> Result<()> {
     let ticket = EventTicketObject {
         id: ticket_id.clone(),
         class_id: format!("{}.concert_class", config.issuer_id),
-        state: Some("ACTIVE".to_string()),
+        state: Some(State::Active),
         ticket_holder_name: Some("Jane Smith".to_string()),
+        notify_preference: None,
         seat_info: Some(EventSeat {
             seat: Some(LocalizedString {
                 default_value: Some(TranslatedString {
@@ -54,13 +55,24 @@ async fn main() -> Result<()> {
             value: "CONCERT-B15-001".to_string(),
             alternate_text: Some("CONCERT-B15-001".to_string()),
         }),
+        rotating_barcode: None,
+        smart_tap_redemption_value: None,
+        app_link_data: None,
+        links_module_data: None,
+        image_modules_data: None,
+        grouping_info: None,
+        notifications: None,
+        messages: None,
     };
 
     let created_ticket = client.create_event_ticket(&ticket).await?;
     println!("✓ Created ticket: {}", created_ticket.id);
     println!(
         "  Holder: {}",
-        created_ticket.ticket_holder_name.unwrap_or_default()
+        created_ticket
+            .ticket_holder_name
+            .as_deref()
+            .unwrap_or_default()
     );
 
     if let Some(seat_info) = &created_ticket.seat_info {
@@ -71,18 +83,13 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Generate save URL
-    // Note: We need to get the full object for JWT generation
-    // For event tickets, we'd typically use eventTicketObjects in the JWT
-    // For simplicity here, we'll use the generic object pattern
-    let generic_obj = GenericObject {
-        id: created_ticket.id.clone(),
-        class_id: created_ticket.class_id.clone(),
-        state: created_ticket.state.clone(),
-        barcode: created_ticket.barcode.clone(),
-        ..Default::default()
-    };
-    let save_url = client.generate_save_url(&generic_obj).await?;
+    // Generate save URL using the eventTicketObjects payload
+    let save_url = client
+        .generate_save_url_for(&JwtObjectPayload {
+            event_ticket_objects: Some(vec![created_ticket.clone()]),
+            ..Default::default()
+        })
+        .await?;
     println!("\n🎫 Add ticket to Google Wallet:");
     println!("{}", save_url);
 
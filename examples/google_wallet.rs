@@ -1,7 +1,7 @@
 use porter::error::Result;
 use porter::google::{
     AddMessageRequest, Barcode, GenericClass, GenericObject, GoogleWalletClient,
-    GoogleWalletConfig, LocalizedString, Message, TranslatedString,
+    GoogleWalletConfig, LocalizedString, Message, ReviewStatus, State, TranslatedString,
 };
 
 #[tokio::main]
@@ -24,8 +24,8 @@ async fn main() -> Result<()> {
     let class = GenericClass {
         id: class_id.clone(),
         issuer_name: Some("Porter Example".to_string()),
-        review_status: Some("UNDER_REVIEW".to_string()),
-        class_template_info: None,
+        review_status: Some(ReviewStatus::UnderReview),
+        ..Default::default()
     };
 
     match client.create_generic_class(&class).await {
@@ -39,7 +39,7 @@ async fn main() -> Result<()> {
     let pass = GenericObject {
         id: pass_id.clone(),
         class_id: class_id.clone(),
-        state: Some("ACTIVE".to_string()),
+        state: Some(State::Active),
         card_title: Some(LocalizedString {
             default_value: Some(TranslatedString {
                 language: "en-US".to_string(),
@@ -79,7 +79,10 @@ async fn main() -> Result<()> {
     println!("✓ Retrieved pass: {}", retrieved_pass.id);
     println!(
         "  State: {}",
-        retrieved_pass.state.as_deref().unwrap_or("UNKNOWN")
+        retrieved_pass
+            .state
+            .as_ref()
+            .map_or("UNKNOWN", |s| s.as_str())
     );
 
     // Step 4: Update the pass
@@ -104,7 +107,7 @@ async fn main() -> Result<()> {
         message: Message {
             header: Some("Important Update".to_string()),
             body: Some("This is an example message sent to your pass!".to_string()),
-            display_interval: None,
+            ..Default::default()
         },
     };
 
@@ -1,7 +1,9 @@
 /// This example shows how to create passes using the unified PassBuilder API
 /// The same pass definition can be used for Google Wallet or (in the future) Apple Wallet
 use porter::builder::PassBuilder;
-use porter::google::{GenericClass, GenericObject, GoogleWalletClient, GoogleWalletConfig};
+use porter::google::{
+    GenericClass, GenericObject, GoogleWalletClient, GoogleWalletConfig, ReviewStatus,
+};
 use porter::models::{BarcodeFormat, PassType};
 use porter::Result;
 
@@ -25,8 +27,8 @@ async fn main() -> Result<()> {
     let class = GenericClass {
         id: class_id.clone(),
         issuer_name: Some("Porter Unified API Demo".to_string()),
-        review_status: Some("UNDER_REVIEW".to_string()),
-        class_template_info: None,
+        review_status: Some(ReviewStatus::UnderReview),
+        ..Default::default()
     };
 
     match client.create_generic_class(&class).await {
@@ -0,0 +1,161 @@
+//! actix-web adapter for the Apple Wallet web service
+//!
+//! Mount [`scope`] at the path of the pass's `webServiceURL` to serve the
+//! registration, serial number, log and `.pkpass` download endpoints with a
+//! [`WebServiceHandler`].
+
+use crate::apple::web_service::{handle_request, WebServiceHandler};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse, Scope};
+use std::sync::Arc;
+
+/// A scope at `path` answering web service requests with `handler`
+///
+/// # Example
+///
+/// ```no_run
+/// use actix_web::{App, HttpServer};
+/// use porter::apple::{actix, WebServiceHandler};
+/// use std::sync::Arc;
+///
+/// # async fn example(handler: Arc<impl WebServiceHandler + 'static>) -> std::io::Result<()> {
+/// // webServiceURL: https://example.com/wallet
+/// HttpServer::new(move || App::new().service(actix::scope("/wallet", handler.clone())))
+///     .bind(("0.0.0.0", 8080))?
+///     .run()
+///     .await
+/// # }
+/// ```
+pub fn scope<H: WebServiceHandler + 'static>(path: &str, handler: Arc<H>) -> Scope {
+    web::scope(path)
+        .app_data(web::Data::from(handler))
+        .default_service(web::to(respond::<H>))
+}
+
+async fn respond<H: WebServiceHandler + 'static>(
+    handler: web::Data<H>,
+    request: HttpRequest,
+    body: web::Bytes,
+) -> HttpResponse {
+    let path = request
+        .uri()
+        .path_and_query()
+        .map_or(request.path(), |path| path.as_str());
+    let headers: Vec<(&str, &str)> = request
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+        .collect();
+
+    let response = handle_request(
+        handler.get_ref(),
+        request.method().as_str(),
+        path,
+        &headers,
+        &body,
+    )
+    .await;
+
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut builder = HttpResponse::build(status);
+    for header in response.headers {
+        builder.insert_header(header);
+    }
+    builder.body(response.body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apple::web_service::{
+        LatestPass, PassRegistration, RegistrationRequest, RegistrationStatus, SerialNumbers,
+    };
+    use crate::error::{PorterError, Result};
+    use actix_web::{test, App};
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    struct SinglePass;
+
+    #[async_trait]
+    impl WebServiceHandler for SinglePass {
+        async fn register_device(
+            &self,
+            _registration: &PassRegistration,
+            _authentication_token: &str,
+            _request: RegistrationRequest,
+        ) -> Result<RegistrationStatus> {
+            Ok(RegistrationStatus::Created)
+        }
+
+        async fn serial_numbers(
+            &self,
+            _device_library_identifier: &str,
+            _pass_type_identifier: &str,
+            _passes_updated_since: Option<&str>,
+        ) -> Result<Option<SerialNumbers>> {
+            Ok(None)
+        }
+
+        async fn latest_pass(
+            &self,
+            _pass_type_identifier: &str,
+            serial_number: &str,
+            _authentication_token: &str,
+            _if_modified_since: Option<DateTime<Utc>>,
+        ) -> Result<LatestPass> {
+            if serial_number != "T1" {
+                return Err(PorterError::NotFound(serial_number.to_string()));
+            }
+            Ok(LatestPass::Updated {
+                pkpass: vec![1, 2, 3],
+                last_modified: DateTime::from_timestamp(1_800_000_000, 0).unwrap(),
+            })
+        }
+
+        async fn unregister_device(
+            &self,
+            _registration: &PassRegistration,
+            _authentication_token: &str,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_scope() {
+        let app =
+            test::init_service(App::new().service(scope("/wallet", Arc::new(SinglePass)))).await;
+        let authorization = ("Authorization", "ApplePass 3f0c4e1b9a7d2c65");
+
+        let request = test::TestRequest::post()
+            .uri("/wallet/v1/devices/device-1/registrations/pass.com.example/T1")
+            .insert_header(authorization)
+            .set_payload(r#"{"pushToken": "apns-token"}"#)
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), 201);
+
+        let request = test::TestRequest::get()
+            .uri("/wallet/v1/passes/pass.com.example/T1")
+            .insert_header(authorization)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers().get("Last-Modified").unwrap(),
+            "Fri, 15 Jan 2027 08:00:00 GMT"
+        );
+        assert_eq!(test::read_body(response).await.as_ref(), [1, 2, 3]);
+
+        let request = test::TestRequest::get()
+            .uri("/wallet/v1/passes/pass.com.example/T2")
+            .insert_header(authorization)
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), 404);
+
+        let request = test::TestRequest::get()
+            .uri("/wallet/v1/devices/device-1/registrations/pass.com.example")
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), 204);
+    }
+}
@@ -0,0 +1,356 @@
+//! Apple Push Notification service client for pass updates
+//!
+//! Wallet does not poll for new pass versions. When a pass changes, the
+//! issuer sends an empty push to every device registered for it, and the
+//! device then fetches the pass from the web service.
+
+use crate::apple::RegistrationStore;
+use crate::error::{PorterError, Result};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const APNS_SERVER: &str = "https://api.push.apple.com";
+/// APNs rejects provider tokens older than an hour, and refreshing more
+/// often than every 20 minutes
+const TOKEN_LIFETIME: Duration = Duration::from_secs(50 * 60);
+
+/// Credentials for token-based APNs authentication
+#[derive(Clone)]
+pub struct ApnsConfig {
+    /// Id of the `.p8` key, from the Apple Developer account
+    pub key_id: String,
+    pub team_id: String,
+    /// Contents of the `.p8` key file
+    pub private_key: String,
+    /// Pass type identifier of the passes to update
+    pub topic: String,
+}
+
+/// Provider token claims
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    iss: String,
+    iat: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    reason: String,
+}
+
+/// Outcome of [`ApnsClient::push_updates`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PushReport {
+    /// Push tokens that were notified
+    pub notified: Vec<String>,
+    /// Push tokens APNs no longer accepts, whose registrations were removed
+    pub pruned: Vec<String>,
+    /// Push tokens that could not be notified
+    pub failed: Vec<PushFailure>,
+}
+
+/// A push token that could not be notified
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushFailure {
+    pub push_token: String,
+    pub error: String,
+}
+
+/// Apple passes whose devices are notified after a change made through
+/// another client
+///
+/// Update the passes your web service serves before the change is made:
+/// notified devices download them straight away.
+pub struct ApplePush<'a> {
+    pub pass_type_identifier: &'a str,
+    pub serial_numbers: &'a [&'a str],
+    pub registrations: &'a dyn RegistrationStore,
+    pub apns: &'a ApnsClient,
+}
+
+impl ApplePush<'_> {
+    /// Mark the passes updated and notify their devices
+    ///
+    /// See [`ApnsClient::push_updates`].
+    pub async fn run(&self) -> Result<PushReport> {
+        self.apns
+            .push_updates(
+                self.registrations,
+                self.pass_type_identifier,
+                self.serial_numbers,
+            )
+            .await
+    }
+}
+
+/// Provider token and when it was issued
+struct CachedToken {
+    token: String,
+    issued: SystemTime,
+}
+
+/// Sends pass update notifications through APNs
+pub struct ApnsClient {
+    client: Client,
+    config: ApnsConfig,
+    server: String,
+    token: Arc<tokio::sync::Mutex<Option<CachedToken>>>,
+}
+
+impl ApnsClient {
+    /// Create a client for the production APNs server, which Wallet uses for all passes
+    pub fn new(config: ApnsConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config,
+            server: APNS_SERVER.to_string(),
+            token: Arc::new(tokio::sync::Mutex::new(None)),
+        }
+    }
+
+    /// Send notifications through another server, such as an APNs proxy
+    pub fn with_server(mut self, server: impl Into<String>) -> Self {
+        self.server = server.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Tell the device holding `push_token` that its pass changed
+    ///
+    /// The device asks the web service for the passes updated since its
+    /// last check, then downloads them. A token that is no longer registered
+    /// with APNs fails with [`PorterError::NotFound`]; delete its
+    /// registrations, as [`push_updates`](Self::push_updates) does.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use porter::apple::{ApnsClient, ApnsConfig};
+    ///
+    /// # async fn example(push_token: &str) -> porter::error::Result<()> {
+    /// let client = ApnsClient::new(ApnsConfig {
+    ///     key_id: "ABC123DEFG".to_string(),
+    ///     team_id: "ABCDE12345".to_string(),
+    ///     private_key: std::fs::read_to_string("AuthKey_ABC123DEFG.p8")?,
+    ///     topic: "pass.com.example.ticket".to_string(),
+    /// });
+    ///
+    /// client.notify_pass_update(push_token).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn notify_pass_update(&self, push_token: &str) -> Result<()> {
+        let token = self.provider_token().await?;
+        let response = self
+            .client
+            .post(format!("{}/3/device/{}", self.server, push_token))
+            .bearer_auth(token)
+            .header("apns-topic", &self.config.topic)
+            .header("apns-push-type", "background")
+            .header("apns-priority", "5")
+            .json(&serde_json::json!({}))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let message = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|error| error.reason)
+            .unwrap_or_default();
+        if status.as_u16() == 410 {
+            return Err(PorterError::NotFound(format!(
+                "Push token is no longer active: {}",
+                message
+            )));
+        }
+        Err(PorterError::ApiError {
+            status: status.as_u16(),
+            message,
+        })
+    }
+
+    /// Record that passes changed and notify every device holding them
+    ///
+    /// Each pass is marked updated in `store`, then each distinct push token
+    /// registered for any of them gets one notification, however many of
+    /// the passes its device holds. Tokens APNs reports as unregistered are
+    /// removed from the store. Failed pushes are collected in the report;
+    /// only store errors abort.
+    pub async fn push_updates(
+        &self,
+        store: &dyn RegistrationStore,
+        pass_type_identifier: &str,
+        serial_numbers: &[&str],
+    ) -> Result<PushReport> {
+        let mut push_tokens = vec![];
+        let mut seen = HashSet::new();
+        for serial_number in serial_numbers {
+            store
+                .mark_updated(pass_type_identifier, serial_number)
+                .await?;
+            for push_token in store
+                .push_tokens(pass_type_identifier, serial_number)
+                .await?
+            {
+                if seen.insert(push_token.clone()) {
+                    push_tokens.push(push_token);
+                }
+            }
+        }
+
+        let mut report = PushReport::default();
+        for push_token in push_tokens {
+            match self.notify_pass_update(&push_token).await {
+                Ok(()) => report.notified.push(push_token),
+                Err(PorterError::NotFound(_)) => {
+                    store.remove_push_token(&push_token).await?;
+                    report.pruned.push(push_token);
+                }
+                Err(e) => report.failed.push(PushFailure {
+                    push_token,
+                    error: e.to_string(),
+                }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// The current provider token, signing a new one when it is due
+    async fn provider_token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.issued.elapsed().unwrap_or(TOKEN_LIFETIME) < TOKEN_LIFETIME {
+                return Ok(token.token.clone());
+            }
+        }
+
+        let issued = SystemTime::now();
+        let token = self.sign_token(issued)?;
+        *cached = Some(CachedToken {
+            token: token.clone(),
+            issued,
+        });
+        Ok(token)
+    }
+
+    fn sign_token(&self, issued: SystemTime) -> Result<String> {
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(self.config.key_id.clone());
+        let claims = Claims {
+            iss: self.config.team_id.clone(),
+            iat: issued
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| PorterError::AuthError(format!("Time error: {}", e)))?
+                .as_secs(),
+        };
+
+        let key = EncodingKey::from_ec_pem(self.config.private_key.as_bytes())?;
+        Ok(encode(&header, &claims, &key)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{decode, DecodingKey, Validation};
+
+    fn client() -> ApnsClient {
+        ApnsClient::new(ApnsConfig {
+            key_id: "ABC123DEFG".to_string(),
+            team_id: "ABCDE12345".to_string(),
+            private_key: include_str!("testdata/apns_key.p8").to_string(),
+            topic: "pass.com.example.ticket".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_provider_token() {
+        let client = client();
+        let token = client.provider_token().await.unwrap();
+
+        let key = DecodingKey::from_ec_pem(include_bytes!("testdata/apns_key.pub.pem")).unwrap();
+        let mut validation = Validation::new(Algorithm::ES256);
+        validation.required_spec_claims.clear();
+        let decoded = decode::<Claims>(&token, &key, &validation).unwrap();
+        assert_eq!(decoded.header.kid.as_deref(), Some("ABC123DEFG"));
+        assert_eq!(decoded.claims.iss, "ABCDE12345");
+
+        // Reused until it is due for a refresh
+        assert_eq!(client.provider_token().await.unwrap(), token);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server() {
+        let client = client().with_server("http://127.0.0.1:9/");
+
+        assert!(matches!(
+            client.notify_pass_update("token").await,
+            Err(PorterError::HttpError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_push_updates_dedupes_and_prunes() {
+        use crate::apple::{MemoryRegistrationStore, PassRegistration};
+        use crate::mock_server;
+
+        let (server, received) = mock_server::serve(vec![
+            ("POST", "/3/device/live", 200, serde_json::json!({})),
+            (
+                "POST",
+                "/3/device/dead",
+                410,
+                serde_json::json!({ "reason": "Unregistered" }),
+            ),
+        ])
+        .await;
+        let client = client().with_server(server);
+
+        let store = MemoryRegistrationStore::new();
+        for (device, serial_number, push_token) in [
+            ("phone", "A", "live"),
+            ("phone", "B", "live"),
+            ("tablet", "A", "dead"),
+            ("watch", "C", "other"),
+        ] {
+            let registration = PassRegistration {
+                device_library_identifier: device.to_string(),
+                pass_type_identifier: "pass.com.example.ticket".to_string(),
+                serial_number: serial_number.to_string(),
+            };
+            store.register(&registration, push_token).await.unwrap();
+        }
+
+        let report = client
+            .push_updates(&store, "pass.com.example.ticket", &["A", "B"])
+            .await
+            .unwrap();
+        assert_eq!(report.notified, vec!["live"]);
+        assert_eq!(report.pruned, vec!["dead"]);
+        assert!(report.failed.is_empty());
+        let mut paths: Vec<_> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| format!("{} {}", request.method, request.path))
+            .collect();
+        paths.sort();
+        assert_eq!(paths, ["POST /3/device/dead", "POST /3/device/live"]);
+        assert!(received.lock().unwrap()[0].body.is_some());
+
+        assert_eq!(
+            store
+                .push_tokens("pass.com.example.ticket", "A")
+                .await
+                .unwrap(),
+            vec!["live"]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Images of a pass bundle
+//!
+//! Each image slot holds up to three PNGs, at @1x, @2x and @3x. Which slots
+//! a pass may use depends on its style, and every pass needs an icon.
+
+use crate::apple::{PassStyle, PkPassBundle};
+use crate::error::{PorterError, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Image slots of a pass, with their size in points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AssetSlot {
+    /// 29 x 29, shown on the lock screen and in notifications
+    Icon,
+    /// Up to 160 x 50, top left of the pass
+    Logo,
+    /// 375 x 123 behind the primary fields of coupons and store cards
+    Strip,
+    /// 375 x 98 behind the primary fields of event tickets
+    EventStrip,
+    /// Up to 90 x 90, next to the primary fields
+    Thumbnail,
+    /// 180 x 220, blurred behind an event ticket
+    Background,
+    /// Up to 286 x 15, above the barcode of boarding passes
+    Footer,
+}
+
+impl AssetSlot {
+    /// File name without scale suffix or extension
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            Self::Icon => "icon",
+            Self::Logo => "logo",
+            Self::Strip | Self::EventStrip => "strip",
+            Self::Thumbnail => "thumbnail",
+            Self::Background => "background",
+            Self::Footer => "footer",
+        }
+    }
+
+    /// File name of the image at `scale`, such as `logo@2x.png`
+    pub fn file_name(&self, scale: u8) -> String {
+        match scale {
+            1 => format!("{}.png", self.file_stem()),
+            scale => format!("{}@{}x.png", self.file_stem(), scale),
+        }
+    }
+
+    /// Size at @1x, in points
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            Self::Icon => (29, 29),
+            Self::Logo => (160, 50),
+            Self::Strip => (375, 123),
+            Self::EventStrip => (375, 98),
+            Self::Thumbnail => (90, 90),
+            Self::Background => (180, 220),
+            Self::Footer => (286, 15),
+        }
+    }
+}
+
+/// The images of a pass, by slot and scale
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::{AssetSet, AssetSlot, PassStructure, PassStyle};
+///
+/// # let (icon, icon_2x) = (vec![], vec![]);
+/// let assets = AssetSet::new()
+///     .image(AssetSlot::Icon, 1, icon)?
+///     .image(AssetSlot::Icon, 2, icon_2x)?;
+///
+/// assert!(assets.validate(&PassStyle::Generic(PassStructure::default())).is_ok());
+/// assert_eq!(assets.file_names(), ["icon.png", "icon@2x.png"]);
+/// # Ok::<(), porter::PorterError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AssetSet {
+    images: BTreeMap<(AssetSlot, u8), Vec<u8>>,
+}
+
+impl AssetSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the PNG for `slot` at `scale` (1, 2 or 3), replacing any previous one
+    pub fn image(mut self, slot: AssetSlot, scale: u8, png: Vec<u8>) -> Result<Self> {
+        if !(1..=3).contains(&scale) {
+            return Err(PorterError::ValidationError(format!(
+                "Image scale must be 1, 2 or 3, not {}",
+                scale
+            )));
+        }
+        self.images.insert((slot, scale), png);
+        Ok(self)
+    }
+
+    /// Read the PNG for `slot` at `scale` from a file
+    pub fn image_file(self, slot: AssetSlot, scale: u8, path: impl AsRef<Path>) -> Result<Self> {
+        let png = std::fs::read(path)?;
+        self.image(slot, scale, png)
+    }
+
+    /// Resize one source image into all three scales of `slot`
+    #[cfg(feature = "image")]
+    pub fn resized(mut self, slot: AssetSlot, source: &[u8]) -> Result<Self> {
+        for (scale, variant) in (1..).zip(super::resize_asset(source, slot)?) {
+            self.images.insert((slot, scale), variant.png);
+        }
+        Ok(self)
+    }
+
+    /// Check the images against what `style` can show
+    ///
+    /// Every pass needs an icon. Boarding passes add a logo and footer,
+    /// coupons and store cards a logo and strip, generic passes a logo and
+    /// thumbnail, and event tickets a logo with either a strip or a
+    /// background and thumbnail.
+    pub fn validate(&self, style: &PassStyle) -> Result<()> {
+        if !self.has(AssetSlot::Icon) {
+            return Err(PorterError::ValidationError(
+                "Apple passes require an icon image".to_string(),
+            ));
+        }
+
+        let allowed: &[AssetSlot] = match style {
+            PassStyle::BoardingPass(_) => &[AssetSlot::Footer],
+            PassStyle::Coupon(_) | PassStyle::StoreCard(_) => &[AssetSlot::Strip],
+            PassStyle::EventTicket(_) => &[
+                AssetSlot::EventStrip,
+                AssetSlot::Background,
+                AssetSlot::Thumbnail,
+            ],
+            PassStyle::Generic(_) => &[AssetSlot::Thumbnail],
+        };
+        for (slot, _) in self.images.keys() {
+            if !matches!(slot, AssetSlot::Icon | AssetSlot::Logo) && !allowed.contains(slot) {
+                return Err(PorterError::ValidationError(format!(
+                    "{} images are not shown on this pass style",
+                    slot.file_stem()
+                )));
+            }
+        }
+
+        if self.has(AssetSlot::EventStrip)
+            && (self.has(AssetSlot::Background) || self.has(AssetSlot::Thumbnail))
+        {
+            return Err(PorterError::ValidationError(
+                "Event tickets with a strip image cannot have a background or thumbnail"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// File names the images are stored under in the bundle
+    pub fn file_names(&self) -> Vec<String> {
+        self.images
+            .keys()
+            .map(|(slot, scale)| slot.file_name(*scale))
+            .collect()
+    }
+
+    pub(crate) fn has(&self, slot: AssetSlot) -> bool {
+        self.images.keys().any(|(s, _)| *s == slot)
+    }
+}
+
+impl PkPassBundle {
+    /// Add every image of `assets` under its retina file name
+    ///
+    /// Check the set with [`AssetSet::validate`] first; this does not know
+    /// the pass style.
+    pub fn assets(mut self, assets: &AssetSet) -> Result<Self> {
+        for ((slot, scale), png) in &assets.images {
+            self = self.file(slot.file_name(*scale), png.clone())?;
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apple::PassStructure;
+
+    fn assets(slots: &[AssetSlot]) -> AssetSet {
+        slots.iter().fold(AssetSet::new(), |assets, slot| {
+            assets.image(*slot, 2, vec![]).unwrap()
+        })
+    }
+
+    #[test]
+    fn test_validate_per_style() {
+        let ticket = PassStyle::EventTicket(PassStructure::default());
+        let generic = PassStyle::Generic(PassStructure::default());
+
+        assert!(assets(&[AssetSlot::Logo]).validate(&generic).is_err());
+        assert!(assets(&[AssetSlot::Icon, AssetSlot::Thumbnail])
+            .validate(&generic)
+            .is_ok());
+        assert!(assets(&[AssetSlot::Icon, AssetSlot::Footer])
+            .validate(&generic)
+            .is_err());
+        assert!(
+            assets(&[AssetSlot::Icon, AssetSlot::Background, AssetSlot::Thumbnail])
+                .validate(&ticket)
+                .is_ok()
+        );
+        assert!(
+            assets(&[AssetSlot::Icon, AssetSlot::EventStrip, AssetSlot::Thumbnail])
+                .validate(&ticket)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bundle_file_names() {
+        let assets = AssetSet::new()
+            .image(AssetSlot::Icon, 1, vec![1])
+            .unwrap()
+            .image(AssetSlot::EventStrip, 3, vec![2])
+            .unwrap();
+        assert!(assets.clone().image(AssetSlot::Icon, 4, vec![]).is_err());
+
+        let bundle = PkPassBundle::new(&serde_json::json!({}))
+            .unwrap()
+            .assets(&assets)
+            .unwrap();
+        let manifest = bundle.manifest();
+        let names: Vec<_> = manifest.keys().copied().collect();
+        assert_eq!(names, ["icon.png", "pass.json", "strip@3x.png"]);
+    }
+}
@@ -0,0 +1,149 @@
+/// MIME type Safari and Wallet expect when serving a `.pkpass` file
+pub const PKPASS_MIME_TYPE: &str = "application/vnd.apple.pkpass";
+
+/// MIME type for a `.pkpasses` archive of several passes
+pub const PKPASSES_MIME_TYPE: &str = "application/vnd.apple.pkpasses";
+
+/// File name of Apple's US/UK "Add to Apple Wallet" badge artwork
+///
+/// Apple does not host the badge for hotlinking; download the artwork from the
+/// Apple Wallet identity guidelines and serve it alongside your pages.
+pub const DEFAULT_BADGE_SRC: &str = "Add_to_Apple_Wallet_rgb_US-UK.svg";
+
+const DEFAULT_ALT_TEXT: &str = "Add to Apple Wallet";
+
+/// "Add to Apple Wallet" badge linking to a hosted `.pkpass`
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::AddToWalletBadge;
+///
+/// let html = AddToWalletBadge::new("https://example.com/passes/ticket.pkpass")
+///     .badge_src("/static/add-to-apple-wallet.svg")
+///     .height(48)
+///     .to_html();
+/// assert!(html.contains("href=\"https://example.com/passes/ticket.pkpass\""));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AddToWalletBadge {
+    pass_url: String,
+    badge_src: String,
+    alt_text: String,
+    height: Option<u32>,
+}
+
+impl AddToWalletBadge {
+    /// Create a badge for the pass hosted at `pass_url`
+    pub fn new(pass_url: impl Into<String>) -> Self {
+        Self {
+            pass_url: pass_url.into(),
+            badge_src: DEFAULT_BADGE_SRC.to_string(),
+            alt_text: DEFAULT_ALT_TEXT.to_string(),
+            height: None,
+        }
+    }
+
+    /// Set the location of the badge artwork
+    pub fn badge_src(mut self, src: impl Into<String>) -> Self {
+        self.badge_src = src.into();
+        self
+    }
+
+    /// Set the alternative text (use a localized string for non-English pages)
+    pub fn alt_text(mut self, alt_text: impl Into<String>) -> Self {
+        self.alt_text = alt_text.into();
+        self
+    }
+
+    /// Set the rendered height of the badge in pixels
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Render the badge as an HTML anchor
+    pub fn to_html(&self) -> String {
+        let height = self
+            .height
+            .map(|height| format!(" height=\"{}\"", height))
+            .unwrap_or_default();
+
+        format!(
+            "<a href=\"{}\"><img src=\"{}\" alt=\"{}\"{}></a>",
+            escape_attribute(&self.pass_url),
+            escape_attribute(&self.badge_src),
+            escape_attribute(&self.alt_text),
+            height
+        )
+    }
+}
+
+/// Response headers for serving a `.pkpass` download
+///
+/// `file_name` should end in `.pkpass` so browsers without Wallet save it
+/// with the right extension. Names ending in `.pkpasses` are served as a
+/// multi-pass archive.
+pub fn download_headers(file_name: &str) -> Vec<(&'static str, String)> {
+    let file_name = file_name.replace(['"', '\\', '\r', '\n'], "");
+    let mime_type = if file_name.ends_with(".pkpasses") {
+        PKPASSES_MIME_TYPE
+    } else {
+        PKPASS_MIME_TYPE
+    };
+
+    vec![
+        ("Content-Type", mime_type.to_string()),
+        (
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", file_name),
+        ),
+        ("Cache-Control", "no-cache".to_string()),
+    ]
+}
+
+fn escape_attribute(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badge_html_escapes_attributes() {
+        let html = AddToWalletBadge::new("https://example.com/pass?id=1&v=\"2\"").to_html();
+
+        assert_eq!(
+            html,
+            "<a href=\"https://example.com/pass?id=1&amp;v=&quot;2&quot;\">\
+             <img src=\"Add_to_Apple_Wallet_rgb_US-UK.svg\" alt=\"Add to Apple Wallet\"></a>"
+        );
+    }
+
+    #[test]
+    fn test_download_headers() {
+        let headers = download_headers("ticket\".pkpass");
+
+        assert_eq!(headers[0], ("Content-Type", PKPASS_MIME_TYPE.to_string()));
+        assert_eq!(
+            headers[1].1,
+            "attachment; filename=\"ticket.pkpass\"".to_string()
+        );
+
+        assert_eq!(
+            download_headers("tickets.pkpasses")[0].1,
+            PKPASSES_MIME_TYPE
+        );
+    }
+}
@@ -0,0 +1,542 @@
+//! `.pkpass` archive assembly
+//!
+//! A pass bundle is a zip of `pass.json`, its images and localizations, and
+//! a `manifest.json` of SHA-1 hashes that the `signature` file signs.
+//! Several signed passes can be delivered together as a `.pkpasses` zip.
+
+use crate::apple::ApplePass;
+use crate::error::{PorterError, Result};
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Write};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+const PASS_JSON: &str = "pass.json";
+const MANIFEST_JSON: &str = "manifest.json";
+const SIGNATURE: &str = "signature";
+/// Most passes Wallet accepts in one `.pkpasses` archive
+const MAX_PASSES: usize = 10;
+
+/// Files of a `.pkpass` archive
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::PkPassBundle;
+///
+/// let bundle = PkPassBundle::new(&serde_json::json!({"formatVersion": 1}))?
+///     .file("icon.png", vec![0x89, b'P', b'N', b'G'])?
+///     .file("de.lproj/pass.strings", b"\"Concert\" = \"Konzert\";\n".to_vec())?;
+///
+/// assert_eq!(bundle.manifest().len(), 3);
+/// let pkpass = bundle.to_zip()?;
+/// # Ok::<(), porter::PorterError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PkPassBundle {
+    files: BTreeMap<String, Vec<u8>>,
+    signature: Option<Vec<u8>>,
+    /// `manifest.json` of a read archive, kept byte for byte so its
+    /// signature stays valid until a file changes
+    read_manifest: Option<Vec<u8>>,
+}
+
+impl PkPassBundle {
+    /// Start a bundle with `pass` serialized as `pass.json`
+    pub fn new(pass: &impl Serialize) -> Result<Self> {
+        Ok(Self {
+            files: BTreeMap::from([(PASS_JSON.to_string(), serde_json::to_vec(pass)?)]),
+            signature: None,
+            read_manifest: None,
+        })
+    }
+
+    /// Unzip an existing `.pkpass`
+    ///
+    /// Every file must be listed in `manifest.json` with a matching hash,
+    /// and `pass.json` must parse as an [`ApplePass`]. The signature is kept
+    /// but not checked; with the `apple-signing` feature,
+    /// `verify_signature` checks it.
+    pub fn read(pkpass: &[u8]) -> Result<Self> {
+        let mut archive = ZipArchive::new(Cursor::new(pkpass)).map_err(zip_error)?;
+        let mut files = BTreeMap::new();
+        let mut manifest_json = None;
+        let mut signature = None;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(zip_error)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = entry.name().to_string();
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            match name.as_str() {
+                MANIFEST_JSON => manifest_json = Some(contents),
+                SIGNATURE => signature = Some(contents),
+                _ => {
+                    validate_name(&name)?;
+                    files.insert(name, contents);
+                }
+            }
+        }
+
+        let manifest_json = manifest_json.ok_or_else(|| {
+            PorterError::ValidationError("Bundle has no manifest.json".to_string())
+        })?;
+        let mut manifest: BTreeMap<String, String> = serde_json::from_slice(&manifest_json)?;
+        for (name, contents) in &files {
+            let hash = manifest.remove(name).ok_or_else(|| {
+                PorterError::ValidationError(format!("{} is not in manifest.json", name))
+            })?;
+            if !hash.eq_ignore_ascii_case(&sha1_hex(contents)) {
+                return Err(PorterError::ValidationError(format!(
+                    "{} does not match its manifest.json hash",
+                    name
+                )));
+            }
+        }
+        if let Some(name) = manifest.keys().next() {
+            return Err(PorterError::ValidationError(format!(
+                "{} is in manifest.json but not in the bundle",
+                name
+            )));
+        }
+
+        let bundle = Self {
+            files,
+            signature,
+            read_manifest: Some(manifest_json),
+        };
+        bundle.pass()?;
+        Ok(bundle)
+    }
+
+    /// Parse `pass.json`
+    pub fn pass(&self) -> Result<ApplePass> {
+        let pass_json = self
+            .files
+            .get(PASS_JSON)
+            .ok_or_else(|| PorterError::ValidationError("Bundle has no pass.json".to_string()))?;
+        Ok(serde_json::from_slice(pass_json)?)
+    }
+
+    /// Contents of a file in the bundle, such as `pass.json` or `icon.png`
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        self.files.get(name).map(Vec::as_slice)
+    }
+
+    /// Names of the files in the bundle, excluding the manifest and signature
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// The detached signature, if the bundle is signed
+    pub fn signature_der(&self) -> Option<&[u8]> {
+        self.signature.as_deref()
+    }
+
+    /// Add a file such as `logo@2x.png` or `fr.lproj/pass.strings`
+    ///
+    /// Adding a file that is already in the bundle replaces it. The manifest
+    /// and signature are generated, so those names are rejected. Any
+    /// signature no longer covers the manifest and is dropped, so sign the
+    /// bundle again afterwards.
+    pub fn file(mut self, name: impl Into<String>, contents: Vec<u8>) -> Result<Self> {
+        let name = name.into();
+        validate_name(&name)?;
+        self.files.insert(name, contents);
+        self.read_manifest = None;
+        self.signature = None;
+        Ok(self)
+    }
+
+    /// Mark the pass as void, so Wallet shows it as no longer usable
+    ///
+    /// Only `voided` in `pass.json` changes; other keys are kept as they
+    /// are. The old signature no longer matches and is dropped, so sign the
+    /// bundle again before serving it. With the `apple-signing` feature,
+    /// `void` does both.
+    pub fn voided(self) -> Result<Self> {
+        let pass_json = self
+            .files
+            .get(PASS_JSON)
+            .ok_or_else(|| PorterError::ValidationError("Bundle has no pass.json".to_string()))?;
+        let mut pass: serde_json::Value = serde_json::from_slice(pass_json)?;
+        let object = pass.as_object_mut().ok_or_else(|| {
+            PorterError::ValidationError("pass.json is not an object".to_string())
+        })?;
+        object.insert("voided".to_string(), true.into());
+
+        self.file(PASS_JSON, serde_json::to_vec(&pass)?)
+    }
+
+    /// Set the detached PKCS#7 signature of [`manifest_json`](Self::manifest_json)
+    pub fn signature(mut self, der: Vec<u8>) -> Self {
+        self.signature = Some(der);
+        self
+    }
+
+    /// SHA-1 hex digest of every file, keyed by file name
+    pub fn manifest(&self) -> BTreeMap<&str, String> {
+        self.files
+            .iter()
+            .map(|(name, contents)| (name.as_str(), sha1_hex(contents)))
+            .collect()
+    }
+
+    /// The `manifest.json` bytes that the signature must cover
+    ///
+    /// A bundle [read](Self::read) from an archive keeps its original
+    /// manifest until a file is added.
+    pub fn manifest_json(&self) -> Result<Vec<u8>> {
+        if let Some(manifest_json) = &self.read_manifest {
+            return Ok(manifest_json.clone());
+        }
+        Ok(serde_json::to_vec_pretty(&self.manifest())?)
+    }
+
+    /// Zip the bundle into `.pkpass` bytes
+    ///
+    /// Serve the result with [`PKPASS_MIME_TYPE`](super::PKPASS_MIME_TYPE).
+    /// Wallet refuses bundles without a signature, so unsigned archives are
+    /// only useful for inspection or signing elsewhere.
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        let manifest = self.manifest_json()?;
+        zip_package(&self.files, &manifest, self.signature.as_deref())
+    }
+}
+
+/// Signed passes delivered together as a `.pkpasses` archive
+///
+/// Serve the result with [`PKPASSES_MIME_TYPE`](super::PKPASSES_MIME_TYPE);
+/// Wallet offers to add every pass in it at once.
+///
+/// # Example
+///
+/// ```no_run
+/// use porter::apple::{download_headers, PkPassesBundle};
+///
+/// # fn example(tickets: Vec<Vec<u8>>) -> porter::Result<()> {
+/// let mut bundle = PkPassesBundle::new();
+/// for (i, pkpass) in tickets.into_iter().enumerate() {
+///     bundle = bundle.pkpass(format!("ticket-{}.pkpass", i + 1), pkpass)?;
+/// }
+///
+/// let pkpasses = bundle.to_zip()?;
+/// let headers = download_headers("tickets.pkpasses");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PkPassesBundle {
+    passes: BTreeMap<String, Vec<u8>>,
+}
+
+impl PkPassesBundle {
+    /// Start an empty bundle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a signed `.pkpass` archive under `name`, such as `ticket-1.pkpass`
+    pub fn pkpass(mut self, name: impl Into<String>, pkpass: Vec<u8>) -> Result<Self> {
+        let name = name.into();
+        if name.contains('/') || name.contains('\\') || !name.ends_with(".pkpass") {
+            return Err(PorterError::ValidationError(format!(
+                "Invalid pass file name: {}",
+                name
+            )));
+        }
+        if !self.passes.contains_key(&name) && self.passes.len() == MAX_PASSES {
+            return Err(PorterError::ValidationError(format!(
+                "A .pkpasses archive holds at most {} passes",
+                MAX_PASSES
+            )));
+        }
+        self.passes.insert(name, pkpass);
+        Ok(self)
+    }
+
+    /// Zip a signed bundle and add it under `name`
+    pub fn bundle(self, name: impl Into<String>, bundle: &PkPassBundle) -> Result<Self> {
+        if bundle.signature.is_none() {
+            return Err(PorterError::ValidationError(
+                "Passes in a .pkpasses archive must be signed".to_string(),
+            ));
+        }
+        let pkpass = bundle.to_zip()?;
+        self.pkpass(name, pkpass)
+    }
+
+    /// Names of the passes in the bundle
+    pub fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.passes.keys().map(String::as_str)
+    }
+
+    /// Zip the passes into a `.pkpasses` archive
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        if self.passes.is_empty() {
+            return Err(PorterError::ValidationError(
+                "A .pkpasses archive needs at least one pass".to_string(),
+            ));
+        }
+
+        zip_files(
+            self.passes
+                .iter()
+                .map(|(name, pkpass)| (name.as_str(), pkpass.as_slice())),
+        )
+    }
+}
+
+/// Zip a package's files followed by its manifest and signature
+pub(super) fn zip_package(
+    files: &BTreeMap<String, Vec<u8>>,
+    manifest_json: &[u8],
+    signature: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    let generated = [
+        Some((MANIFEST_JSON, manifest_json)),
+        signature.map(|der| (SIGNATURE, der)),
+    ];
+    zip_files(
+        files
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.as_slice()))
+            .chain(generated.into_iter().flatten()),
+    )
+}
+
+fn zip_files<'a>(files: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    for (name, contents) in files {
+        zip.start_file(name, options).map_err(zip_error)?;
+        zip.write_all(contents)?;
+    }
+
+    Ok(zip.finish().map_err(zip_error)?.into_inner())
+}
+
+/// Reject names that would escape the bundle or clash with generated files
+pub(super) fn validate_name(name: &str) -> Result<()> {
+    let invalid = name.is_empty()
+        || name.starts_with('/')
+        || name.contains('\\')
+        || name.split('/').any(|part| part.is_empty() || part == "..");
+    if invalid {
+        return Err(PorterError::ValidationError(format!(
+            "Invalid bundle file name: {}",
+            name
+        )));
+    }
+    if name == MANIFEST_JSON || name == SIGNATURE {
+        return Err(PorterError::ValidationError(format!(
+            "{} is generated and cannot be added to a bundle",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn sha1_hex(contents: &[u8]) -> String {
+    digest(&SHA1_FOR_LEGACY_USE_ONLY, contents)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn zip_error(e: zip::result::ZipError) -> PorterError {
+    PorterError::IoError(std::io::Error::other(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    #[test]
+    fn test_manifest_hashes() {
+        let bundle = PkPassBundle::new(&serde_json::json!({}))
+            .unwrap()
+            .file("icon.png", b"abc".to_vec())
+            .unwrap();
+
+        let manifest = bundle.manifest();
+        assert_eq!(
+            manifest["icon.png"],
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(manifest["pass.json"], sha1_hex(b"{}"));
+    }
+
+    #[test]
+    fn test_file_drops_signature() {
+        let signed = PkPassBundle::new(&serde_json::json!({"formatVersion": 1}))
+            .unwrap()
+            .signature(vec![1, 2, 3]);
+        assert!(signed.signature_der().is_some());
+
+        let changed = signed.file("icon.png", b"abc".to_vec()).unwrap();
+        assert!(changed.signature_der().is_none());
+        let mut archive = ZipArchive::new(Cursor::new(changed.to_zip().unwrap())).unwrap();
+        assert!(archive.by_name(SIGNATURE).is_err());
+    }
+
+    #[test]
+    fn test_zip_contents() {
+        let bundle = PkPassBundle::new(&serde_json::json!({"formatVersion": 1}))
+            .unwrap()
+            .file("en.lproj/pass.strings", b"\"a\" = \"b\";\n".to_vec())
+            .unwrap()
+            .signature(vec![1, 2, 3]);
+
+        let mut archive = ZipArchive::new(Cursor::new(bundle.to_zip().unwrap())).unwrap();
+        let mut names: Vec<_> = archive.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            [
+                "en.lproj/pass.strings",
+                "manifest.json",
+                "pass.json",
+                "signature"
+            ]
+        );
+
+        let mut manifest = Vec::new();
+        archive
+            .by_name("manifest.json")
+            .unwrap()
+            .read_to_end(&mut manifest)
+            .unwrap();
+        assert_eq!(manifest, bundle.manifest_json().unwrap());
+    }
+
+    #[test]
+    fn test_read_round_trip() {
+        let pass = crate::apple::ApplePass::new(
+            "pass.com.example",
+            "001",
+            "ABCDE12345",
+            "Example",
+            "Example pass",
+            crate::apple::PassStyle::Generic(Default::default()),
+        );
+        let bundle = PkPassBundle::new(&pass)
+            .unwrap()
+            .file("icon.png", vec![1, 2, 3])
+            .unwrap()
+            .signature(vec![4, 5, 6]);
+        let pkpass = bundle.to_zip().unwrap();
+
+        let read = PkPassBundle::read(&pkpass).unwrap();
+        assert_eq!(read.pass().unwrap(), pass);
+        assert_eq!(read.get("icon.png"), Some(&[1, 2, 3][..]));
+        assert_eq!(read.signature_der(), Some(&[4, 5, 6][..]));
+        assert_eq!(
+            read.manifest_json().unwrap(),
+            bundle.manifest_json().unwrap()
+        );
+        assert_eq!(read.to_zip().unwrap(), pkpass);
+    }
+
+    #[test]
+    fn test_read_rejects_tampered_files() {
+        let bundle = PkPassBundle::new(&serde_json::json!({}))
+            .unwrap()
+            .file("icon.png", vec![1, 2, 3])
+            .unwrap();
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in [
+            ("pass.json", b"{}".to_vec()),
+            ("icon.png", vec![9, 9, 9]),
+            ("manifest.json", bundle.manifest_json().unwrap()),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&contents).unwrap();
+        }
+        let pkpass = zip.finish().unwrap().into_inner();
+
+        assert!(matches!(
+            PkPassBundle::read(&pkpass),
+            Err(PorterError::ValidationError(message)) if message.contains("icon.png")
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_names() {
+        let bundle = PkPassBundle::new(&serde_json::json!({})).unwrap();
+
+        for name in [
+            "",
+            "/etc/passwd",
+            "../icon.png",
+            "a//b.png",
+            "manifest.json",
+            "signature",
+        ] {
+            assert!(bundle.clone().file(name, vec![]).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_pkpasses() {
+        let signed = PkPassBundle::new(&serde_json::json!({}))
+            .unwrap()
+            .signature(b"signature".to_vec());
+        let bundle = PkPassesBundle::new()
+            .bundle("ticket-1.pkpass", &signed)
+            .unwrap()
+            .bundle("ticket-2.pkpass", &signed)
+            .unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bundle.to_zip().unwrap())).unwrap();
+        let mut pkpass = vec![];
+        archive
+            .by_name("ticket-2.pkpass")
+            .unwrap()
+            .read_to_end(&mut pkpass)
+            .unwrap();
+        assert_eq!(pkpass, signed.to_zip().unwrap());
+        assert_eq!(archive.len(), 2);
+
+        let unsigned = PkPassBundle::new(&serde_json::json!({})).unwrap();
+        assert!(PkPassesBundle::new()
+            .bundle("ticket.pkpass", &unsigned)
+            .is_err());
+        assert!(PkPassesBundle::new()
+            .pkpass("tickets/1.pkpass", vec![])
+            .is_err());
+        assert!(PkPassesBundle::new().to_zip().is_err());
+    }
+
+    #[test]
+    fn test_pkpasses_limit() {
+        let bundle = (0..MAX_PASSES).fold(PkPassesBundle::new(), |bundle, i| {
+            bundle.pkpass(format!("{}.pkpass", i), vec![]).unwrap()
+        });
+
+        assert!(bundle.clone().pkpass("0.pkpass", vec![1]).is_ok());
+        assert!(bundle.pkpass("extra.pkpass", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_voided() {
+        let bundle = PkPassBundle::new(&serde_json::json!({"formatVersion": 1, "custom": "kept"}))
+            .unwrap()
+            .signature(b"signature".to_vec())
+            .voided()
+            .unwrap();
+
+        let pass: serde_json::Value =
+            serde_json::from_slice(bundle.get(PASS_JSON).unwrap()).unwrap();
+        assert_eq!(pass["voided"], true);
+        assert_eq!(pass["custom"], "kept");
+        assert!(bundle.signature_der().is_none());
+    }
+}
@@ -0,0 +1,203 @@
+//! Apple Wallet credentials
+
+#[cfg(feature = "apple-signing")]
+use crate::credentials::pem_encode;
+use crate::credentials::{
+    self, pem_body, rsa_key_pair, AppleCredentialReport, CredentialThresholds,
+};
+use crate::error::{PorterError, Result};
+use ring::signature::KeyPair;
+use std::path::Path;
+use x509_parser::oid_registry::OID_USERID;
+
+/// Configuration for signing Apple Wallet passes
+///
+/// The certificate is the Pass Type ID certificate issued for
+/// `pass_type_identifier`; Apple names the identifier in its subject, so
+/// [`validate`](Self::validate) can catch a certificate for another pass type.
+#[derive(Clone)]
+pub struct AppleWalletConfig {
+    pub pass_type_identifier: String,
+    pub team_identifier: String,
+    /// Pass Type ID certificate, PEM encoded
+    pub certificate: String,
+    /// Private key of the certificate, PKCS#8 or PKCS#1 PEM
+    pub private_key: String,
+    /// Apple WWDR intermediate certificate, PEM encoded
+    pub wwdr_certificate: String,
+}
+
+impl AppleWalletConfig {
+    /// Load the certificate, private key and WWDR intermediate from PEM files
+    pub fn from_pem_files(
+        pass_type_identifier: impl Into<String>,
+        team_identifier: impl Into<String>,
+        certificate_path: impl AsRef<Path>,
+        private_key_path: impl AsRef<Path>,
+        wwdr_path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let config = Self {
+            pass_type_identifier: pass_type_identifier.into(),
+            team_identifier: team_identifier.into(),
+            certificate: std::fs::read_to_string(certificate_path)?,
+            private_key: std::fs::read_to_string(private_key_path)?,
+            wwdr_certificate: std::fs::read_to_string(wwdr_path)?,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load the certificate and private key from a `.p12` export
+    ///
+    /// Keychain Access exports the Pass Type ID certificate and its key
+    /// together as a `.p12`; the WWDR intermediate is downloaded from Apple
+    /// separately.
+    #[cfg(feature = "apple-signing")]
+    pub fn from_p12(
+        pass_type_identifier: impl Into<String>,
+        team_identifier: impl Into<String>,
+        p12: &[u8],
+        passphrase: &str,
+        wwdr_certificate: impl Into<String>,
+    ) -> Result<Self> {
+        let keystore = p12_keystore::KeyStore::from_pkcs12(p12, passphrase)
+            .map_err(|e| PorterError::ConfigError(format!("Invalid p12: {}", e)))?;
+        let (_, chain) = keystore.private_key_chain().ok_or_else(|| {
+            PorterError::ConfigError("p12 does not contain a private key".to_string())
+        })?;
+        let certificate = chain.chain().first().ok_or_else(|| {
+            PorterError::ConfigError("p12 does not contain a certificate".to_string())
+        })?;
+
+        let config = Self {
+            pass_type_identifier: pass_type_identifier.into(),
+            team_identifier: team_identifier.into(),
+            certificate: pem_encode("CERTIFICATE", certificate.as_der()),
+            private_key: pem_encode("PRIVATE KEY", chain.key()),
+            wwdr_certificate: wwdr_certificate.into(),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Report when the certificate and WWDR intermediate expire, and
+    /// whether the certificate was issued by the intermediate
+    ///
+    /// Problems are reported as warnings against `thresholds` rather than
+    /// errors; only unparseable certificates fail. Merge the report into the
+    /// Google one with [`CredentialReport::with_apple`](crate::credentials::CredentialReport::with_apple).
+    pub fn check_credentials(
+        &self,
+        thresholds: &CredentialThresholds,
+    ) -> Result<AppleCredentialReport> {
+        credentials::check_apple_certificates(
+            &self.pass_type_identifier,
+            &self.certificate,
+            &self.wwdr_certificate,
+            thresholds,
+            chrono::Utc::now(),
+        )
+    }
+
+    /// Check that the certificate was issued for this pass type and team,
+    /// and that the private key belongs to it
+    pub fn validate(&self) -> Result<()> {
+        let der = pem_body(&self.certificate)?;
+        let (_, certificate) = x509_parser::parse_x509_certificate(&der)
+            .map_err(|e| PorterError::ConfigError(format!("Invalid pass certificate: {}", e)))?;
+        let subject = certificate.subject();
+
+        let pass_type = subject
+            .iter_by_oid(&OID_USERID)
+            .find_map(|attribute| attribute.as_str().ok());
+        if pass_type != Some(self.pass_type_identifier.as_str()) {
+            return Err(PorterError::ConfigError(format!(
+                "Certificate is for pass type {}, not {}",
+                pass_type.unwrap_or("(none)"),
+                self.pass_type_identifier
+            )));
+        }
+
+        let team = subject
+            .iter_organizational_unit()
+            .find_map(|attribute| attribute.as_str().ok());
+        if let Some(team) = team.filter(|team| *team != self.team_identifier) {
+            return Err(PorterError::ConfigError(format!(
+                "Certificate is for team {}, not {}",
+                team, self.team_identifier
+            )));
+        }
+
+        let key = rsa_key_pair(&self.private_key)?;
+        if certificate.public_key().subject_public_key.data.as_ref() != key.public_key().as_ref() {
+            return Err(PorterError::ConfigError(
+                "Private key does not match the pass certificate".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AppleWalletConfig {
+        AppleWalletConfig {
+            pass_type_identifier: "pass.com.example.ticket".to_string(),
+            team_identifier: "ABCDE12345".to_string(),
+            certificate: include_str!("testdata/pass_cert.pem").to_string(),
+            private_key: include_str!("testdata/pass_key.pem").to_string(),
+            wwdr_certificate: include_str!("testdata/wwdr.pem").to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+
+        let other_pass = AppleWalletConfig {
+            pass_type_identifier: "pass.com.example.coupon".to_string(),
+            ..config()
+        };
+        assert!(other_pass.validate().is_err());
+
+        let other_team = AppleWalletConfig {
+            team_identifier: "ZZZZZ99999".to_string(),
+            ..config()
+        };
+        assert!(other_team.validate().is_err());
+
+        let other_key = AppleWalletConfig {
+            private_key: include_str!("testdata/other_key.pem").to_string(),
+            ..config()
+        };
+        assert!(other_key.validate().is_err());
+    }
+
+    #[cfg(feature = "apple-signing")]
+    #[test]
+    fn test_from_p12() {
+        let loaded = AppleWalletConfig::from_p12(
+            "pass.com.example.ticket",
+            "ABCDE12345",
+            include_bytes!("testdata/pass.p12"),
+            "porter",
+            include_str!("testdata/wwdr.pem"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            pem_body(&loaded.certificate).unwrap(),
+            pem_body(&config().certificate).unwrap()
+        );
+        assert!(AppleWalletConfig::from_p12(
+            "pass.com.example.ticket",
+            "ABCDE12345",
+            include_bytes!("testdata/pass.p12"),
+            "wrong",
+            include_str!("testdata/wwdr.pem"),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! Apple Wallet pass fields
+
+use crate::apple::{DateStyle, Field, FieldValue, NumberStyle, TextAlignment};
+use crate::models::{self, DateTimeStyle, FieldFormat, NumberFormat, PassField};
+
+/// The `attributedValue` of a field with a link
+///
+/// Wallet renders the anchor as a tappable link on the back of the pass.
+/// Fields without a link have no attributed value.
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::attributed_value;
+/// use porter::PassBuilder;
+///
+/// let pass = PassBuilder::new("issuer.pass", "issuer.class")
+///     .link_field("support", "Support", "Call us", "tel:+15550100")
+///     .build();
+///
+/// assert_eq!(
+///     attributed_value(&pass.fields[0]).as_deref(),
+///     Some("<a href=\"tel:+15550100\">Call us</a>")
+/// );
+/// ```
+pub fn attributed_value(field: &PassField) -> Option<String> {
+    let link = field.link.as_ref()?;
+    Some(format!(
+        "<a href=\"{}\">{}</a>",
+        escape(link),
+        escape(&field.value)
+    ))
+}
+
+/// Convert a unified field, formatting its value natively
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::{Field, FieldValue};
+/// use porter::models::FieldFormat;
+/// use porter::PassBuilder;
+///
+/// let pass = PassBuilder::new("issuer.pass", "issuer.class")
+///     .formatted_field("balance", "Balance", "12.50", FieldFormat::Currency("EUR".to_string()))
+///     .build();
+///
+/// let field = Field::from(&pass.fields[0]);
+/// assert_eq!(field.value, FieldValue::Number(12.5));
+/// assert_eq!(field.currency_code.as_deref(), Some("EUR"));
+/// ```
+impl From<&PassField> for Field {
+    fn from(field: &PassField) -> Self {
+        let mut converted = Field::new(&field.key, &field.label, field.value.as_str());
+        converted.attributed_value = attributed_value(field);
+        converted.change_message = field.change_message.clone();
+        converted.text_alignment = field
+            .text_alignment
+            .as_ref()
+            .map(|alignment| match alignment {
+                models::TextAlignment::Left => TextAlignment::Left,
+                models::TextAlignment::Center => TextAlignment::Center,
+                models::TextAlignment::Right => TextAlignment::Right,
+                models::TextAlignment::Natural => TextAlignment::Natural,
+            });
+
+        let number = || {
+            field
+                .value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .map(FieldValue::Number)
+        };
+        match &field.format {
+            Some(FieldFormat::DateTime {
+                date_style,
+                time_style,
+            }) => converted.with_date_style((*date_style).into(), (*time_style).into()),
+            Some(FieldFormat::Number(style)) => Field {
+                value: number().unwrap_or(converted.value),
+                number_style: Some((*style).into()),
+                ..converted
+            },
+            Some(FieldFormat::Currency(currency_code)) => Field {
+                value: number().unwrap_or(converted.value),
+                currency_code: Some(currency_code.clone()),
+                ..converted
+            },
+            None => converted,
+        }
+    }
+}
+
+impl From<DateTimeStyle> for DateStyle {
+    fn from(style: DateTimeStyle) -> Self {
+        match style {
+            DateTimeStyle::None => Self::None,
+            DateTimeStyle::Short => Self::Short,
+            DateTimeStyle::Medium => Self::Medium,
+            DateTimeStyle::Long => Self::Long,
+            DateTimeStyle::Full => Self::Full,
+        }
+    }
+}
+
+impl From<NumberFormat> for NumberStyle {
+    fn from(format: NumberFormat) -> Self {
+        match format {
+            NumberFormat::Decimal => Self::Decimal,
+            NumberFormat::Percent => Self::Percent,
+            NumberFormat::Scientific => Self::Scientific,
+            NumberFormat::SpellOut => Self::SpellOut,
+        }
+    }
+}
+
+/// Escape text for the HTML subset Wallet accepts in attributed values
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PassBuilder;
+
+    #[test]
+    fn test_formatted_fields() {
+        let pass = PassBuilder::new("issuer.pass", "issuer.class")
+            .date_field(
+                "doors",
+                "Doors",
+                "2026-05-01T18:30:00Z".parse().unwrap(),
+                DateTimeStyle::None,
+                DateTimeStyle::Short,
+            )
+            .formatted_field(
+                "discount",
+                "Discount",
+                "0.15",
+                FieldFormat::Number(NumberFormat::Percent),
+            )
+            .formatted_field(
+                "total",
+                "Total",
+                "n/a",
+                FieldFormat::Currency("USD".to_string()),
+            )
+            .change_message("doors", "Doors now open at %@")
+            .build();
+        let fields: Vec<_> = pass.fields.iter().map(Field::from).collect();
+
+        assert_eq!(
+            serde_json::to_value(&fields[0]).unwrap(),
+            serde_json::json!({
+                "key": "doors",
+                "label": "Doors",
+                "value": "2026-05-01T18:30:00+00:00",
+                "dateStyle": "PKDateStyleNone",
+                "timeStyle": "PKDateStyleShort",
+                "changeMessage": "Doors now open at %@",
+            })
+        );
+        assert_eq!(fields[1].value, FieldValue::Number(0.15));
+        assert_eq!(fields[1].number_style, Some(NumberStyle::Percent));
+        // Values that are not numbers are kept as text
+        assert_eq!(fields[2].value, FieldValue::Text("n/a".to_string()));
+        assert_eq!(fields[2].currency_code.as_deref(), Some("USD"));
+    }
+}
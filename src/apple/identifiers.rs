@@ -0,0 +1,76 @@
+//! Serial numbers and authentication tokens for new passes
+//!
+//! Both come from the system's secure random source, so they are safe to
+//! hand out without checking for collisions and cannot be guessed.
+
+use crate::apple::web_service::MIN_AUTHENTICATION_TOKEN_LEN;
+use crate::error::{PorterError, Result};
+use chrono::Utc;
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Generate a unique `serialNumber` for a new pass or order
+///
+/// Serials are 32 lowercase hex characters: the creation time in
+/// milliseconds followed by 80 random bits, so they sort by age and are
+/// also valid in Google Wallet object IDs.
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::generate_serial_number;
+///
+/// let serial = generate_serial_number()?;
+/// assert_eq!(serial.len(), 32);
+/// # Ok::<(), porter::PorterError>(())
+/// ```
+pub fn generate_serial_number() -> Result<String> {
+    let millis = Utc::now().timestamp_millis().max(0) as u64;
+    Ok(format!(
+        "{:012x}{}",
+        millis & 0xffff_ffff_ffff,
+        random_hex::<10>()?
+    ))
+}
+
+/// Generate a random `authenticationToken` for a new pass
+///
+/// Tokens are 256 bits of hex, well over Wallet's minimum length. Store the
+/// token with the pass and compare it in
+/// [`WebServiceHandler`](super::WebServiceHandler) methods with
+/// [`verify_authentication_token`](super::verify_authentication_token).
+pub fn generate_authentication_token() -> Result<String> {
+    let token = random_hex::<32>()?;
+    debug_assert!(token.len() >= MIN_AUTHENTICATION_TOKEN_LEN);
+    Ok(token)
+}
+
+fn random_hex<const N: usize>() -> Result<String> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| PorterError::ConfigError("System random source unavailable".to_string()))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serial_numbers() {
+        let first = generate_serial_number().unwrap();
+        let second = generate_serial_number().unwrap();
+        assert_eq!(first.len(), 32);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+        assert!(first[..12] <= second[..12]);
+    }
+
+    #[test]
+    fn test_authentication_tokens() {
+        let token = generate_authentication_token().unwrap();
+        assert_eq!(token.len(), 64);
+        assert!(token.len() >= MIN_AUTHENTICATION_TOKEN_LEN);
+        assert_ne!(token, generate_authentication_token().unwrap());
+    }
+}
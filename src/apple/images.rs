@@ -0,0 +1,94 @@
+//! Apple Wallet image variants
+//!
+//! Pass bundles carry each image at @1x, @2x and @3x. [`resize_asset`]
+//! produces all three PNGs for a slot from one high-resolution source.
+
+use crate::apple::AssetSlot;
+use crate::error::{PorterError, Result};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use std::io::Cursor;
+
+/// One resized image, ready to add to a pass bundle
+#[derive(Debug, Clone)]
+pub struct AssetVariant {
+    /// File name such as `logo@2x.png`
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+    pub png: Vec<u8>,
+}
+
+/// Resize a PNG or JPEG into the @1x, @2x and @3x variants of `slot`
+///
+/// Icons, strips and backgrounds are cropped to fill the slot exactly;
+/// logos, thumbnails and footers keep their aspect ratio and fit inside it.
+pub fn resize_asset(source: &[u8], slot: AssetSlot) -> Result<Vec<AssetVariant>> {
+    let image = image::load_from_memory(source)
+        .map_err(|e| PorterError::ValidationError(format!("Invalid image: {}", e)))?;
+
+    let (width, height) = slot.size();
+    [1, 2, 3]
+        .iter()
+        .map(|scale| {
+            let (width, height) = (width * scale, height * scale);
+            let resized = if fills(slot) {
+                image.resize_to_fill(width, height, FilterType::Lanczos3)
+            } else {
+                image.resize(width, height, FilterType::Lanczos3)
+            };
+            Ok(AssetVariant {
+                file_name: slot.file_name(*scale as u8),
+                width: resized.width(),
+                height: resized.height(),
+                png: encode_png(&resized)?,
+            })
+        })
+        .collect()
+}
+
+/// Whether the image is cropped to fill the slot, rather than scaled to fit inside it
+fn fills(slot: AssetSlot) -> bool {
+    matches!(
+        slot,
+        AssetSlot::Icon | AssetSlot::Strip | AssetSlot::EventStrip | AssetSlot::Background
+    )
+}
+
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| PorterError::ValidationError(format!("Could not encode PNG: {}", e)))?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_asset_variants() {
+        let source = encode_png(&DynamicImage::new_rgba8(1200, 600)).unwrap();
+
+        let logos = resize_asset(&source, AssetSlot::Logo).unwrap();
+        let sizes: Vec<_> = logos
+            .iter()
+            .map(|v| (v.file_name.as_str(), v.width, v.height))
+            .collect();
+        assert_eq!(
+            sizes,
+            [
+                ("logo.png", 100, 50),
+                ("logo@2x.png", 200, 100),
+                ("logo@3x.png", 300, 150)
+            ]
+        );
+
+        let icons = resize_asset(&source, AssetSlot::Icon).unwrap();
+        assert_eq!((icons[2].width, icons[2].height), (87, 87));
+        assert!(image::load_from_memory(&icons[2].png).is_ok());
+
+        assert!(resize_asset(b"not an image", AssetSlot::Icon).is_err());
+    }
+}
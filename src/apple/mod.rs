@@ -1,37 +1,51 @@
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "actix")]
+pub mod actix;
+pub mod apns;
+pub mod assets;
+pub mod badge;
+pub mod bundle;
+pub mod config;
+pub mod fields;
+pub mod identifiers;
+#[cfg(feature = "image")]
+pub mod images;
+pub mod orders;
+pub mod pass;
+pub mod registrations;
+pub mod schema;
+pub mod semantics;
+#[cfg(feature = "apple-signing")]
+pub mod signing;
+pub mod strings;
+pub mod web_service;
 
-/// Apple Wallet Pass (stub for future implementation)
-///
-/// Apple Wallet uses the PKPass format which requires:
-/// - A pass.json file with pass data
-/// - Images (icon, logo, background, etc.)
-/// - A manifest.json file listing all files and their SHA1 hashes
-/// - A signature file for the manifest
-///
-/// This will be implemented in a future version.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApplePass {
-    pub format_version: u8,
-    pub pass_type_identifier: String,
-    pub serial_number: String,
-    pub team_identifier: String,
-    pub organization_name: String,
-    pub description: String,
-}
-
-/// Apple Wallet client (stub)
-pub struct AppleWalletClient {
-    // Will be implemented with PKPass generation
-}
-
-impl AppleWalletClient {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
-impl Default for AppleWalletClient {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+pub use apns::{ApnsClient, ApnsConfig, ApplePush, PushFailure, PushReport};
+pub use assets::{AssetSet, AssetSlot};
+pub use badge::{download_headers, AddToWalletBadge, PKPASSES_MIME_TYPE, PKPASS_MIME_TYPE};
+pub use bundle::{PkPassBundle, PkPassesBundle};
+pub use config::AppleWalletConfig;
+pub use fields::attributed_value;
+pub use identifiers::{generate_authentication_token, generate_serial_number};
+#[cfg(feature = "image")]
+pub use images::{resize_asset, AssetVariant};
+pub use orders::{
+    Customer, Fulfillment, LineItem, Merchant, Order, OrderAmount, OrderPackage, OrderStatus,
+    OrderType, Payment, PaymentStatus, PickupFulfillment, PickupStatus, ShippingFulfillment,
+    ShippingStatus, ORDER_MIME_TYPE,
+};
+pub use pass::{
+    ApplePass, Barcode, BarcodeFormat, Beacon, DataDetectorType, DateStyle, Field, FieldValue,
+    Location, Nfc, NumberStyle, PassStructure, PassStyle, StyleScheme, TextAlignment, TransitType,
+};
+pub use registrations::{MemoryRegistrationStore, RegistrationStore};
+pub use schema::{pass_json_violations, validate_pass_json, SchemaViolation, PASS_SCHEMA};
+pub use semantics::{
+    CurrencyAmount, EventType, PersonName, SemanticLocation, SemanticSeat, SemanticTags,
+};
+#[cfg(feature = "apple-signing")]
+pub use signing::PassSigner;
+pub use strings::pass_strings;
+pub use web_service::{
+    handle_request, verify_authentication_token, LatestPass, LogEntries, PassRegistration,
+    RegistrationRequest, RegistrationStatus, SerialNumbers, WebServiceHandler, WebServiceResponse,
+};
@@ -0,0 +1,573 @@
+//! Apple Wallet orders
+//!
+//! An order package (`.order`) lets Wallet track a purchase: its line items,
+//! payment and shipping or pickup progress. It is built like a pass bundle,
+//! a zip of `order.json`, images and a signed `manifest.json`, but the
+//! manifest hashes files with SHA-256 and the signing certificate is an
+//! Order Type ID certificate.
+
+use crate::apple::bundle::{validate_name, zip_package};
+use crate::apple::web_service::MIN_AUTHENTICATION_TOKEN_LEN;
+use crate::error::{PorterError, Result};
+use chrono::{DateTime, Utc};
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// MIME type Safari and Wallet expect when serving an `.order` file
+pub const ORDER_MIME_TYPE: &str = "application/vnd.apple.finance.order";
+
+const ORDER_JSON: &str = "order.json";
+
+/// The order.json of an Apple Wallet order
+///
+/// # Example
+///
+/// ```
+/// use chrono::Utc;
+/// use porter::apple::{LineItem, Merchant, Order, OrderAmount, Payment, PaymentStatus};
+///
+/// let order = Order::new(
+///     "order.com.example.shop",
+///     "ORDER-1001",
+///     Merchant::new("com.example.shop", "Example Shop", "https://example.com", "logo.png"),
+///     Utc::now(),
+/// )
+/// .with_line_item(LineItem::new("Rust Mug", 2).with_price(OrderAmount::new(24.0, "USD")))
+/// .with_payment(Payment {
+///     total: OrderAmount::new(24.0, "USD"),
+///     status: PaymentStatus::Paid,
+/// });
+///
+/// let json = serde_json::to_value(&order)?;
+/// assert_eq!(json["orderType"], "ecommerce");
+/// assert_eq!(json["lineItems"][0]["quantity"], 2);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    pub schema_version: u8,
+    /// Order Type ID the order is signed for, such as `order.com.example.shop`
+    pub order_type_identifier: String,
+    /// Unique within the order type
+    pub order_identifier: String,
+    pub order_type: OrderType,
+    /// Order number shown to the customer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_number: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub status: OrderStatus,
+    pub merchant: Merchant,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<Customer>,
+    /// Page where the customer manages the order on the merchant's site
+    #[serde(rename = "orderManagementURL", skip_serializing_if = "Option::is_none")]
+    pub order_management_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub line_items: Vec<LineItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment: Option<Payment>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub fulfillments: Vec<Fulfillment>,
+    /// Base URL of the order update web service
+    #[serde(rename = "webServiceURL", skip_serializing_if = "Option::is_none")]
+    pub web_service_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authentication_token: Option<String>,
+}
+
+impl Order {
+    /// Create an open e-commerce order with no line items
+    pub fn new(
+        order_type_identifier: impl Into<String>,
+        order_identifier: impl Into<String>,
+        merchant: Merchant,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            schema_version: 1,
+            order_type_identifier: order_type_identifier.into(),
+            order_identifier: order_identifier.into(),
+            order_type: OrderType::Ecommerce,
+            order_number: None,
+            created_at,
+            updated_at: created_at,
+            status: OrderStatus::Open,
+            merchant,
+            customer: None,
+            order_management_url: None,
+            line_items: vec![],
+            payment: None,
+            fulfillments: vec![],
+            web_service_url: None,
+            authentication_token: None,
+        }
+    }
+
+    /// Set the order number shown to the customer
+    pub fn with_order_number(mut self, order_number: impl Into<String>) -> Self {
+        self.order_number = Some(order_number.into());
+        self
+    }
+
+    pub fn with_customer(mut self, customer: Customer) -> Self {
+        self.customer = Some(customer);
+        self
+    }
+
+    pub fn with_line_item(mut self, line_item: LineItem) -> Self {
+        self.line_items.push(line_item);
+        self
+    }
+
+    pub fn with_payment(mut self, payment: Payment) -> Self {
+        self.payment = Some(payment);
+        self
+    }
+
+    pub fn with_fulfillment(mut self, fulfillment: Fulfillment) -> Self {
+        self.fulfillments.push(fulfillment);
+        self
+    }
+
+    /// Change the status, recording when it changed
+    pub fn with_status(mut self, status: OrderStatus, updated_at: DateTime<Utc>) -> Self {
+        self.status = status;
+        self.updated_at = updated_at;
+        self
+    }
+
+    /// Make the order update-capable through the web service at `url`
+    ///
+    /// Like [`ApplePass::with_web_service`](super::ApplePass::with_web_service),
+    /// except that Wallet only accepts HTTPS for orders.
+    pub fn with_web_service(
+        mut self,
+        url: impl Into<String>,
+        authentication_token: impl Into<String>,
+    ) -> Result<Self> {
+        let url = url.into();
+        let authentication_token = authentication_token.into();
+        if !url.starts_with("https://") {
+            return Err(PorterError::ValidationError(format!(
+                "Web service URL must be HTTPS: {}",
+                url
+            )));
+        }
+        if authentication_token.len() < MIN_AUTHENTICATION_TOKEN_LEN {
+            return Err(PorterError::ValidationError(format!(
+                "Authentication token must be at least {} characters",
+                MIN_AUTHENTICATION_TOKEN_LEN
+            )));
+        }
+
+        self.web_service_url = Some(url.trim_end_matches('/').to_string());
+        self.authentication_token = Some(authentication_token);
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderType {
+    Ecommerce,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderStatus {
+    Open,
+    Completed,
+    Cancelled,
+}
+
+/// The merchant the order was placed with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Merchant {
+    pub merchant_identifier: String,
+    pub display_name: String,
+    pub url: String,
+    /// File name of the logo in the order package
+    pub logo: String,
+}
+
+impl Merchant {
+    pub fn new(
+        merchant_identifier: impl Into<String>,
+        display_name: impl Into<String>,
+        url: impl Into<String>,
+        logo: impl Into<String>,
+    ) -> Self {
+        Self {
+            merchant_identifier: merchant_identifier.into(),
+            display_name: display_name.into(),
+            url: url.into(),
+            logo: logo.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Customer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+}
+
+/// A product in the order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineItem {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    pub quantity: u32,
+    /// Price of one unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<OrderAmount>,
+    /// File name of the product image in the order package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gtin: Option<String>,
+}
+
+impl LineItem {
+    pub fn new(title: impl Into<String>, quantity: u32) -> Self {
+        Self {
+            title: title.into(),
+            subtitle: None,
+            quantity,
+            price: None,
+            image: None,
+            gtin: None,
+        }
+    }
+
+    pub fn with_price(mut self, price: OrderAmount) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Show the image added to the package under `file_name`
+    pub fn with_image(mut self, file_name: impl Into<String>) -> Self {
+        self.image = Some(file_name.into());
+        self
+    }
+}
+
+/// An amount of money
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderAmount {
+    pub amount: f64,
+    /// ISO 4217 code, such as `USD`
+    pub currency: String,
+}
+
+impl OrderAmount {
+    pub fn new(amount: f64, currency: impl Into<String>) -> Self {
+        Self {
+            amount,
+            currency: currency.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Payment {
+    pub total: OrderAmount,
+    pub status: PaymentStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PaymentStatus {
+    Pending,
+    Authorized,
+    Paid,
+    Refunded,
+}
+
+/// How the order reaches the customer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "fulfillmentType", rename_all = "camelCase")]
+pub enum Fulfillment {
+    Shipping(ShippingFulfillment),
+    Pickup(PickupFulfillment),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShippingFulfillment {
+    /// Unique within the order
+    pub fulfillment_identifier: String,
+    pub status: ShippingStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carrier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_number: Option<String>,
+    #[serde(rename = "trackingURL", skip_serializing_if = "Option::is_none")]
+    pub tracking_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_delivery_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl ShippingFulfillment {
+    pub fn new(fulfillment_identifier: impl Into<String>, status: ShippingStatus) -> Self {
+        Self {
+            fulfillment_identifier: fulfillment_identifier.into(),
+            status,
+            carrier: None,
+            tracking_number: None,
+            tracking_url: None,
+            estimated_delivery_at: None,
+            delivered_at: None,
+        }
+    }
+
+    /// Set the carrier and its tracking number and page
+    pub fn with_tracking(
+        mut self,
+        carrier: impl Into<String>,
+        tracking_number: impl Into<String>,
+        tracking_url: Option<String>,
+    ) -> Self {
+        self.carrier = Some(carrier.into());
+        self.tracking_number = Some(tracking_number.into());
+        self.tracking_url = tracking_url;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShippingStatus {
+    Open,
+    Processing,
+    OnTheWay,
+    OutForDelivery,
+    Delivered,
+    Issue,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickupFulfillment {
+    /// Unique within the order
+    pub fulfillment_identifier: String,
+    pub status: PickupStatus,
+    /// Code or barcode value the customer shows at pickup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pickup_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PickupStatus {
+    Open,
+    Processing,
+    ReadyForPickup,
+    PickedUp,
+    Issue,
+    Cancelled,
+}
+
+/// Files of an `.order` package
+///
+/// Add the merchant logo and line item images under the file names the
+/// order refers to, sign with an Order Type ID certificate, and serve the
+/// result with [`ORDER_MIME_TYPE`].
+///
+/// ```no_run
+/// use porter::apple::{Order, OrderPackage};
+///
+/// # fn sign_manifest(manifest: &[u8]) -> porter::Result<Vec<u8>> { unimplemented!() }
+/// # fn example(order: &Order) -> porter::Result<()> {
+/// let package = OrderPackage::new(order)?.file("logo.png", std::fs::read("logo.png")?)?;
+/// // With the `apple-signing` feature, `package.sign(&signer)` does this
+/// let signature = sign_manifest(&package.manifest_json()?)?;
+/// let bytes = package.signature(signature).to_zip()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrderPackage {
+    files: BTreeMap<String, Vec<u8>>,
+    signature: Option<Vec<u8>>,
+}
+
+impl OrderPackage {
+    /// Start a package with `order` serialized as `order.json`
+    pub fn new(order: &Order) -> Result<Self> {
+        Ok(Self {
+            files: BTreeMap::from([(ORDER_JSON.to_string(), serde_json::to_vec(order)?)]),
+            signature: None,
+        })
+    }
+
+    /// Add a file such as `logo.png`, replacing any file of the same name
+    ///
+    /// Any signature no longer covers the manifest and is dropped.
+    pub fn file(mut self, name: impl Into<String>, contents: Vec<u8>) -> Result<Self> {
+        let name = name.into();
+        validate_name(&name)?;
+        self.files.insert(name, contents);
+        self.signature = None;
+        Ok(self)
+    }
+
+    /// Set the detached PKCS#7 signature of [`manifest_json`](Self::manifest_json)
+    pub fn signature(mut self, der: Vec<u8>) -> Self {
+        self.signature = Some(der);
+        self
+    }
+
+    /// The detached signature, if the package is signed
+    pub fn signature_der(&self) -> Option<&[u8]> {
+        self.signature.as_deref()
+    }
+
+    /// SHA-256 hex digest of every file, keyed by file name
+    pub fn manifest(&self) -> BTreeMap<&str, String> {
+        self.files
+            .iter()
+            .map(|(name, contents)| {
+                let hash = digest(&SHA256, contents)
+                    .as_ref()
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                (name.as_str(), hash)
+            })
+            .collect()
+    }
+
+    /// The `manifest.json` bytes that the signature must cover
+    pub fn manifest_json(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(&self.manifest())?)
+    }
+
+    /// Zip the package into `.order` bytes
+    pub fn to_zip(&self) -> Result<Vec<u8>> {
+        zip_package(
+            &self.files,
+            &self.manifest_json()?,
+            self.signature.as_deref(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::{Cursor, Read};
+    use zip::ZipArchive;
+
+    fn order() -> Order {
+        Order::new(
+            "order.com.example.shop",
+            "ORDER-1001",
+            Merchant::new(
+                "com.example.shop",
+                "Example Shop",
+                "https://example.com",
+                "logo.png",
+            ),
+            "2026-05-01T09:00:00Z".parse().unwrap(),
+        )
+        .with_order_number("1001")
+        .with_line_item(
+            LineItem::new("Rust Mug", 2)
+                .with_price(OrderAmount::new(12.0, "USD"))
+                .with_image("mug.png"),
+        )
+        .with_fulfillment(Fulfillment::Shipping(
+            ShippingFulfillment::new("shipment-1", ShippingStatus::OnTheWay).with_tracking(
+                "Example Post",
+                "EP123456789",
+                Some("https://post.example.com/EP123456789".to_string()),
+            ),
+        ))
+    }
+
+    #[test]
+    fn test_serializes_order_json_keys() {
+        let json = serde_json::to_value(order()).unwrap();
+
+        assert_eq!(json["schemaVersion"], 1);
+        assert_eq!(json["status"], "open");
+        assert_eq!(json["createdAt"], "2026-05-01T09:00:00Z");
+        assert_eq!(
+            json["lineItems"][0]["price"],
+            json!({"amount": 12.0, "currency": "USD"})
+        );
+        assert_eq!(json["fulfillments"][0]["fulfillmentType"], "shipping");
+        assert_eq!(json["fulfillments"][0]["status"], "onTheWay");
+        assert_eq!(
+            json["fulfillments"][0]["trackingURL"],
+            "https://post.example.com/EP123456789"
+        );
+
+        let back: Order = serde_json::from_value(json).unwrap();
+        assert_eq!(back, order());
+    }
+
+    #[test]
+    fn test_package_manifest() {
+        let package = OrderPackage::new(&order())
+            .unwrap()
+            .file("logo.png", b"abc".to_vec())
+            .unwrap();
+
+        assert_eq!(
+            package.manifest()["logo.png"],
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(package.clone().file("manifest.json", vec![]).is_err());
+
+        let mut archive = ZipArchive::new(Cursor::new(package.to_zip().unwrap())).unwrap();
+        let mut manifest = vec![];
+        archive
+            .by_name("manifest.json")
+            .unwrap()
+            .read_to_end(&mut manifest)
+            .unwrap();
+        assert_eq!(manifest, package.manifest_json().unwrap());
+        assert!(archive.by_name("order.json").is_ok());
+
+        let resigned = package
+            .signature(vec![1, 2, 3])
+            .file("logo.png", b"abcd".to_vec())
+            .unwrap();
+        assert!(resigned.signature_der().is_none());
+    }
+
+    #[test]
+    fn test_with_web_service() {
+        assert!(order()
+            .with_web_service("https://example.com/orders", "3f0c4e1b9a7d2c65")
+            .is_ok());
+        assert!(order()
+            .with_web_service("http://example.com/orders", "3f0c4e1b9a7d2c65")
+            .is_err());
+        assert!(order()
+            .with_web_service("https://example.com/orders", "short")
+            .is_err());
+    }
+}
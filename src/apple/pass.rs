@@ -0,0 +1,1190 @@
+//! The pass.json data model
+//!
+//! Mirrors Apple's pass.json keys, so a serialized [`ApplePass`] is ready to
+//! go into a [`PkPassBundle`](super::PkPassBundle).
+
+use crate::apple::web_service::MIN_AUTHENTICATION_TOKEN_LEN;
+use crate::apple::{AssetSet, AssetSlot, SemanticTags};
+use crate::credentials::pem_body;
+use crate::error::{PorterError, Result};
+use crate::models::{
+    AppLink, Grouping, Pass, PassState, Relevance, RelevantBeacon, RelevantLocation, TimeInterval,
+    SEAT_NUMBER_KEY, SEAT_ROW_KEY, SEAT_SECTION_KEY,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use x509_parser::oid_registry::{OID_EC_P256, OID_KEY_TYPE_EC_PUBLIC_KEY};
+use x509_parser::prelude::FromDer;
+use x509_parser::x509::SubjectPublicKeyInfo;
+
+/// The pass.json of an Apple Wallet pass
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::{validate_pass_json, ApplePass, Field, PassStructure, PassStyle};
+///
+/// let mut pass = ApplePass::new(
+///     "pass.com.example.ticket",
+///     "TICKET123",
+///     "ABCDE12345",
+///     "Example Events",
+///     "Concert ticket",
+///     PassStyle::EventTicket(PassStructure::default()),
+/// );
+/// pass.style
+///     .structure_mut()
+///     .primary_fields
+///     .push(Field::new("event", "Event", "The Rust Band Live"));
+///
+/// assert!(validate_pass_json(&serde_json::to_value(&pass)?).is_ok());
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplePass {
+    pub format_version: u8,
+    pub pass_type_identifier: String,
+    pub serial_number: String,
+    pub team_identifier: String,
+    pub organization_name: String,
+    pub description: String,
+    /// Text next to the logo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo_text: Option<String>,
+    /// CSS-style color such as `rgb(255, 255, 255)`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foreground_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_color: Option<String>,
+    /// Groups event tickets and boarding passes of the same trip or event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_strip_shine: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sharing_prohibited: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voided: Option<bool>,
+    /// When the pass is shown on the lock screen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevant_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_date: Option<DateTime<Utc>>,
+    /// Meters from a location within which the pass is relevant
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_distance: Option<f64>,
+    #[serde(rename = "appLaunchURL", skip_serializing_if = "Option::is_none")]
+    pub app_launch_url: Option<String>,
+    /// App Store ids of apps associated with the pass
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub associated_store_identifiers: Vec<u64>,
+    /// Custom data for the issuer's companion app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_info: Option<Value>,
+    /// Base URL of the pass update web service
+    #[serde(rename = "webServiceURL", skip_serializing_if = "Option::is_none")]
+    pub web_service_url: Option<String>,
+    /// Token the device sends to the web service, at least 16 characters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authentication_token: Option<String>,
+    /// Barcodes in order of preference; the first the device supports is shown
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub barcodes: Vec<Barcode>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub beacons: Vec<Beacon>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nfc: Option<Nfc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantics: Option<SemanticTags>,
+    /// Layouts the pass supports, most preferred first
+    ///
+    /// Devices without the first scheme fall back to the next one.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub preferred_style_schemes: Vec<StyleScheme>,
+    /// Text in place of the event logo of poster event tickets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_logo_text: Option<String>,
+    /// Color behind the footer of poster event tickets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_background_color: Option<String>,
+    /// Let Wallet pick poster event ticket colors from the background image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_automatic_colors: Option<bool>,
+    /// Style of the pass and its fields
+    #[serde(flatten)]
+    pub style: PassStyle,
+}
+
+impl ApplePass {
+    /// Create a pass with the required keys and no optional ones
+    pub fn new(
+        pass_type_identifier: impl Into<String>,
+        serial_number: impl Into<String>,
+        team_identifier: impl Into<String>,
+        organization_name: impl Into<String>,
+        description: impl Into<String>,
+        style: PassStyle,
+    ) -> Self {
+        Self {
+            format_version: 1,
+            pass_type_identifier: pass_type_identifier.into(),
+            serial_number: serial_number.into(),
+            team_identifier: team_identifier.into(),
+            organization_name: organization_name.into(),
+            description: description.into(),
+            logo_text: None,
+            foreground_color: None,
+            background_color: None,
+            label_color: None,
+            grouping_identifier: None,
+            suppress_strip_shine: None,
+            sharing_prohibited: None,
+            voided: None,
+            relevant_date: None,
+            expiration_date: None,
+            max_distance: None,
+            app_launch_url: None,
+            associated_store_identifiers: vec![],
+            user_info: None,
+            web_service_url: None,
+            authentication_token: None,
+            barcodes: vec![],
+            locations: vec![],
+            beacons: vec![],
+            nfc: None,
+            semantics: None,
+            preferred_style_schemes: vec![],
+            event_logo_text: None,
+            footer_background_color: None,
+            use_automatic_colors: None,
+            style,
+        }
+    }
+
+    /// Set the relevant date, locations, beacons and maximum distance from
+    /// a unified [`Relevance`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use porter::apple::{ApplePass, PassStructure, PassStyle};
+    /// use porter::PassBuilder;
+    ///
+    /// let pass = PassBuilder::new("issuer.pass", "issuer.class")
+    ///     .relevant_location(51.5033, -0.1196, Some("Doors open at 7pm".to_string()))
+    ///     .max_distance(200.0)
+    ///     .build();
+    ///
+    /// let apple = ApplePass::new(
+    ///     "pass.com.example.ticket",
+    ///     "TICKET123",
+    ///     "ABCDE12345",
+    ///     "Example Events",
+    ///     "Concert ticket",
+    ///     PassStyle::EventTicket(PassStructure::default()),
+    /// )
+    /// .with_relevance(&pass.relevance);
+    ///
+    /// assert_eq!(apple.locations.len(), 1);
+    /// assert_eq!(apple.max_distance, Some(200.0));
+    /// ```
+    pub fn with_relevance(mut self, relevance: &Relevance) -> Self {
+        self.relevant_date = relevance.relevant_date;
+        self.locations = relevance.locations.iter().map(Location::from).collect();
+        self.beacons = relevance.beacons.iter().map(Beacon::from).collect();
+        self.max_distance = relevance.max_distance;
+        self
+    }
+
+    /// Prefer the full-bleed poster layout for an event ticket
+    ///
+    /// Devices that do not support poster event tickets show the classic
+    /// event ticket layout instead. Check the pass with
+    /// [`validate_poster`](Self::validate_poster).
+    pub fn with_poster_style(mut self) -> Self {
+        self.preferred_style_schemes =
+            vec![StyleScheme::PosterEventTicket, StyleScheme::EventTicket];
+        self
+    }
+
+    /// Check that a pass preferring the poster layout can be shown with it
+    ///
+    /// Poster event tickets need a background image for their artwork and
+    /// semantic tags naming the event, its venue and its start date, which
+    /// Wallet lays out in place of the primary fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use porter::apple::{ApplePass, AssetSet, AssetSlot, PassStructure, PassStyle, SemanticTags};
+    ///
+    /// let pass = ApplePass::new(
+    ///     "pass.com.example.ticket",
+    ///     "TICKET123",
+    ///     "ABCDE12345",
+    ///     "Example Events",
+    ///     "Concert ticket",
+    ///     PassStyle::EventTicket(PassStructure::default()),
+    /// )
+    /// .with_poster_style();
+    /// let assets = AssetSet::new()
+    ///     .image(AssetSlot::Icon, 2, vec![])?
+    ///     .image(AssetSlot::Background, 2, vec![])?;
+    /// assert!(pass.validate_poster(&assets).is_err());
+    ///
+    /// let mut pass = pass;
+    /// pass.semantics = Some(
+    ///     SemanticTags::default()
+    ///         .event("The Rust Band Live", Utc.with_ymd_and_hms(2026, 11, 20, 19, 30, 0).unwrap())
+    ///         .venue("Example Arena", None),
+    /// );
+    /// assert!(pass.validate_poster(&assets).is_ok());
+    /// # Ok::<(), porter::PorterError>(())
+    /// ```
+    pub fn validate_poster(&self, assets: &AssetSet) -> Result<()> {
+        if !matches!(self.style, PassStyle::EventTicket(_)) {
+            return Err(PorterError::ValidationError(
+                "Only event tickets have a poster layout".to_string(),
+            ));
+        }
+        assets.validate(&self.style)?;
+        if !assets.has(AssetSlot::Background) {
+            return Err(PorterError::ValidationError(
+                "Poster event tickets require a background image".to_string(),
+            ));
+        }
+
+        let semantics = self.semantics.as_ref();
+        let missing: Vec<_> = [
+            (
+                "eventName",
+                semantics.is_some_and(|s| s.event_name.is_some()),
+            ),
+            (
+                "venueName",
+                semantics.is_some_and(|s| s.venue_name.is_some()),
+            ),
+            (
+                "eventStartDate",
+                semantics.is_some_and(|s| s.event_start_date.is_some()),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, present)| !present)
+        .map(|(tag, _)| tag)
+        .collect();
+        if !missing.is_empty() {
+            return Err(PorterError::ValidationError(format!(
+                "Poster event tickets require the semantic tags {}",
+                missing.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
+    /// Make the pass update-capable through the web service at `url`
+    ///
+    /// Wallet sends `authentication_token` with every request for this pass;
+    /// [`generate_authentication_token`](super::generate_authentication_token)
+    /// makes a suitable one. The URL must be HTTPS, except during
+    /// development with HTTP services allowed on the device.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use porter::apple::{generate_authentication_token, ApplePass, PassStructure, PassStyle};
+    ///
+    /// let pass = ApplePass::new(
+    ///     "pass.com.example.ticket",
+    ///     "TICKET123",
+    ///     "ABCDE12345",
+    ///     "Example Events",
+    ///     "Concert ticket",
+    ///     PassStyle::EventTicket(PassStructure::default()),
+    /// )
+    /// .with_web_service("https://example.com/passes", generate_authentication_token()?)?;
+    /// # Ok::<(), porter::PorterError>(())
+    /// ```
+    pub fn with_web_service(
+        mut self,
+        url: impl Into<String>,
+        authentication_token: impl Into<String>,
+    ) -> Result<Self> {
+        let url = url.into();
+        let authentication_token = authentication_token.into();
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(PorterError::ValidationError(format!(
+                "Web service URL must be HTTPS: {}",
+                url
+            )));
+        }
+        if authentication_token.len() < MIN_AUTHENTICATION_TOKEN_LEN {
+            return Err(PorterError::ValidationError(format!(
+                "Authentication token must be at least {} characters",
+                MIN_AUTHENTICATION_TOKEN_LEN
+            )));
+        }
+
+        self.web_service_url = Some(url.trim_end_matches('/').to_string());
+        self.authentication_token = Some(authentication_token);
+        Ok(self)
+    }
+
+    /// Associate an app by its App Store id
+    ///
+    /// Wallet offers to open the app from the pass, or to install the
+    /// first associated app the device supports.
+    pub fn with_associated_app(mut self, store_identifier: u64) -> Self {
+        if !self
+            .associated_store_identifiers
+            .contains(&store_identifier)
+        {
+            self.associated_store_identifiers.push(store_identifier);
+        }
+        self
+    }
+
+    /// Set the URL passed to the associated app when it is opened from the pass
+    pub fn with_app_launch_url(mut self, url: impl Into<String>) -> Self {
+        self.app_launch_url = Some(url.into());
+        self
+    }
+
+    /// Set the launch URL, and the associated app if known, from a unified app link
+    pub fn with_app_link(self, link: &AppLink) -> Self {
+        let pass = self.with_app_launch_url(link.uri.clone());
+        match link.app_store_id {
+            Some(store_identifier) => pass.with_associated_app(store_identifier),
+            None => pass,
+        }
+    }
+
+    /// Set the grouping identifier from a unified grouping
+    ///
+    /// Wallet only groups event tickets and boarding passes.
+    pub fn with_grouping(mut self, grouping: &Grouping) -> Self {
+        self.grouping_identifier = Some(grouping.id.clone());
+        self
+    }
+
+    /// Set custom data the associated app can read from the pass
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use porter::apple::{ApplePass, PassStructure, PassStyle};
+    ///
+    /// let pass = ApplePass::new(
+    ///     "pass.com.example.loyalty",
+    ///     "MEMBER-0042",
+    ///     "ABCDE12345",
+    ///     "Example Coffee",
+    ///     "Loyalty card",
+    ///     PassStyle::StoreCard(PassStructure::default()),
+    /// )
+    /// .with_associated_app(284882215)
+    /// .with_app_launch_url("examplecoffee://card/MEMBER-0042")
+    /// .with_user_info(&serde_json::json!({"memberId": 42}))?;
+    ///
+    /// assert_eq!(pass.user_info.unwrap()["memberId"], 42);
+    /// # Ok::<(), porter::PorterError>(())
+    /// ```
+    pub fn with_user_info(mut self, user_info: &impl Serialize) -> Result<Self> {
+        self.user_info = Some(serde_json::to_value(user_info)?);
+        Ok(self)
+    }
+
+    /// Set the expiration date from the end of a unified validity interval,
+    /// and void the pass when its state is [`PassState::Expired`]
+    ///
+    /// Wallet greys out a pass after its expiration date, and a voided pass
+    /// straight away.
+    pub fn with_validity(mut self, interval: Option<&TimeInterval>, state: &PassState) -> Self {
+        self.expiration_date = interval.and_then(|interval| interval.end);
+        if *state == PassState::Expired {
+            self.voided = Some(true);
+        }
+        self
+    }
+
+    /// Create an event ticket from a unified pass
+    ///
+    /// The pass title becomes the primary field and the seat section, row
+    /// and number become auxiliary fields, keyed like the unified fields.
+    /// Semantic tags, relevance and validity are taken from the same pass.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use porter::apple::ApplePass;
+    /// use porter::models::{PassType, Seat};
+    /// use porter::PassBuilder;
+    ///
+    /// let pass = PassBuilder::new("issuer.ticket-123", "issuer.concert")
+    ///     .pass_type(PassType::EventTicket)
+    ///     .title("The Rust Band Live")
+    ///     .seat(Seat {
+    ///         section: Some("102".to_string()),
+    ///         row: Some("F".to_string()),
+    ///         number: Some("12".to_string()),
+    ///     })
+    ///     .build();
+    ///
+    /// let apple = ApplePass::event_ticket(&pass, "pass.com.example.ticket", "ABCDE12345", "Example Events");
+    /// assert_eq!(apple.style.structure().auxiliary_fields.len(), 3);
+    /// ```
+    pub fn event_ticket(
+        pass: &Pass,
+        pass_type_identifier: impl Into<String>,
+        team_identifier: impl Into<String>,
+        organization_name: impl Into<String>,
+    ) -> Self {
+        let mut structure = PassStructure {
+            primary_fields: vec![Field::new("event", "Event", pass.header.title.as_str())],
+            ..Default::default()
+        };
+        if let Some(seat) = pass.seat() {
+            structure.auxiliary_fields = [
+                (SEAT_SECTION_KEY, "Section", seat.section),
+                (SEAT_ROW_KEY, "Row", seat.row),
+                (SEAT_NUMBER_KEY, "Seat", seat.number),
+            ]
+            .into_iter()
+            .filter_map(|(key, label, value)| value.map(|value| Field::new(key, label, value)))
+            .collect();
+        }
+        let mut apple = Self::new(
+            pass_type_identifier,
+            pass.id.as_str(),
+            team_identifier,
+            organization_name,
+            pass.header.title.as_str(),
+            PassStyle::EventTicket(structure),
+        )
+        .with_relevance(&pass.relevance)
+        .with_validity(pass.valid_time_interval.as_ref(), &pass.state);
+        apple.semantics = Some(SemanticTags::from_pass(pass));
+        apple
+    }
+}
+
+/// Style of a pass, stored under the key of the same name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PassStyle {
+    /// Requires [`PassStructure::transit_type`]
+    BoardingPass(PassStructure),
+    Coupon(PassStructure),
+    EventTicket(PassStructure),
+    Generic(PassStructure),
+    StoreCard(PassStructure),
+}
+
+impl PassStyle {
+    /// The fields of the pass
+    pub fn structure(&self) -> &PassStructure {
+        match self {
+            Self::BoardingPass(structure)
+            | Self::Coupon(structure)
+            | Self::EventTicket(structure)
+            | Self::Generic(structure)
+            | Self::StoreCard(structure) => structure,
+        }
+    }
+
+    /// The fields of the pass, for editing
+    pub fn structure_mut(&mut self) -> &mut PassStructure {
+        match self {
+            Self::BoardingPass(structure)
+            | Self::Coupon(structure)
+            | Self::EventTicket(structure)
+            | Self::Generic(structure)
+            | Self::StoreCard(structure) => structure,
+        }
+    }
+}
+
+/// Fields of a pass, by where they are shown
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassStructure {
+    /// Shown on the front even when passes are stacked
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub header_fields: Vec<Field>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub primary_fields: Vec<Field>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub secondary_fields: Vec<Field>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub auxiliary_fields: Vec<Field>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub back_fields: Vec<Field>,
+    /// Shown below the event details of poster event tickets
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub additional_info_fields: Vec<Field>,
+    /// Boarding passes only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transit_type: Option<TransitType>,
+}
+
+/// A label and value shown on a pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Field {
+    /// Unique within the pass
+    pub key: String,
+    pub value: FieldValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Lock screen message when the value changes; `%@` is the new value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_message: Option<String>,
+    /// HTML value of back fields, see [`attributed_value`](super::attributed_value)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributed_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_alignment: Option<TextAlignment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_style: Option<DateStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_style: Option<DateStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_relative: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignores_time_zone: Option<bool>,
+    /// ISO 4217 code; formats a numeric value as an amount of money
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_style: Option<NumberStyle>,
+    /// Data detectors run on back fields; empty disables them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_detector_types: Option<Vec<DataDetectorType>>,
+    /// Machine-readable meaning of the value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semantics: Option<SemanticTags>,
+}
+
+impl Field {
+    /// Create a labelled field
+    pub fn new(
+        key: impl Into<String>,
+        label: impl Into<String>,
+        value: impl Into<FieldValue>,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            label: Some(label.into()),
+            change_message: None,
+            attributed_value: None,
+            text_alignment: None,
+            date_style: None,
+            time_style: None,
+            is_relative: None,
+            ignores_time_zone: None,
+            currency_code: None,
+            number_style: None,
+            data_detector_types: None,
+            semantics: None,
+        }
+    }
+
+    /// Describe the value with semantic tags
+    pub fn with_semantics(mut self, semantics: SemanticTags) -> Self {
+        self.semantics = Some(semantics);
+        self
+    }
+
+    /// Notify the holder when the value changes, with `%@` standing for the new value
+    pub fn with_change_message(mut self, message: impl Into<String>) -> Self {
+        self.change_message = Some(message.into());
+        self
+    }
+
+    /// Show the value, an ISO 8601 date, with these date and time styles
+    pub fn with_date_style(mut self, date_style: DateStyle, time_style: DateStyle) -> Self {
+        self.date_style = Some(date_style);
+        self.time_style = Some(time_style);
+        self
+    }
+
+    /// Show the numeric value in `style`
+    pub fn with_number_style(mut self, style: NumberStyle) -> Self {
+        self.number_style = Some(style);
+        self
+    }
+
+    /// Show the numeric value as an amount of money in an ISO 4217 currency
+    pub fn with_currency(mut self, currency_code: impl Into<String>) -> Self {
+        self.currency_code = Some(currency_code.into());
+        self
+    }
+}
+
+/// Value of a field
+///
+/// Dates are text in ISO 8601 format, displayed according to the field's
+/// date and time styles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Text(String),
+    Number(f64),
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<DateTime<Utc>> for FieldValue {
+    fn from(value: DateTime<Utc>) -> Self {
+        Self::Text(value.to_rfc3339())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextAlignment {
+    #[serde(rename = "PKTextAlignmentLeft")]
+    Left,
+    #[serde(rename = "PKTextAlignmentCenter")]
+    Center,
+    #[serde(rename = "PKTextAlignmentRight")]
+    Right,
+    #[serde(rename = "PKTextAlignmentNatural")]
+    Natural,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateStyle {
+    #[serde(rename = "PKDateStyleNone")]
+    None,
+    #[serde(rename = "PKDateStyleShort")]
+    Short,
+    #[serde(rename = "PKDateStyleMedium")]
+    Medium,
+    #[serde(rename = "PKDateStyleLong")]
+    Long,
+    #[serde(rename = "PKDateStyleFull")]
+    Full,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumberStyle {
+    #[serde(rename = "PKNumberStyleDecimal")]
+    Decimal,
+    #[serde(rename = "PKNumberStylePercent")]
+    Percent,
+    #[serde(rename = "PKNumberStyleScientific")]
+    Scientific,
+    #[serde(rename = "PKNumberStyleSpellOut")]
+    SpellOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataDetectorType {
+    #[serde(rename = "PKDataDetectorTypePhoneNumber")]
+    PhoneNumber,
+    #[serde(rename = "PKDataDetectorTypeLink")]
+    Link,
+    #[serde(rename = "PKDataDetectorTypeAddress")]
+    Address,
+    #[serde(rename = "PKDataDetectorTypeCalendarEvent")]
+    CalendarEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransitType {
+    #[serde(rename = "PKTransitTypeAir")]
+    Air,
+    #[serde(rename = "PKTransitTypeBoat")]
+    Boat,
+    #[serde(rename = "PKTransitTypeBus")]
+    Bus,
+    #[serde(rename = "PKTransitTypeGeneric")]
+    Generic,
+    #[serde(rename = "PKTransitTypeTrain")]
+    Train,
+}
+
+/// Barcode shown on the front of a pass
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Barcode {
+    pub format: BarcodeFormat,
+    pub message: String,
+    /// Encoding of `message` in the barcode, usually `iso-8859-1`
+    pub message_encoding: String,
+    /// Text shown below the barcode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BarcodeFormat {
+    #[serde(rename = "PKBarcodeFormatQR")]
+    Qr,
+    #[serde(rename = "PKBarcodeFormatPDF417")]
+    Pdf417,
+    #[serde(rename = "PKBarcodeFormatAztec")]
+    Aztec,
+    /// Not shown on Apple Watch
+    #[serde(rename = "PKBarcodeFormatCode128")]
+    Code128,
+}
+
+/// Layout of a pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StyleScheme {
+    /// Full-bleed event ticket, on iOS 18 and later
+    PosterEventTicket,
+    EventTicket,
+}
+
+/// A place where the pass is relevant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<f64>,
+    /// Lock screen text shown near the location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevant_text: Option<String>,
+}
+
+/// A Bluetooth beacon near which the pass is relevant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Beacon {
+    #[serde(rename = "proximityUUID")]
+    pub proximity_uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub major: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minor: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relevant_text: Option<String>,
+}
+
+impl From<&RelevantLocation> for Location {
+    fn from(location: &RelevantLocation) -> Self {
+        Self {
+            latitude: location.latitude,
+            longitude: location.longitude,
+            altitude: location.altitude,
+            relevant_text: location.relevant_text.clone(),
+        }
+    }
+}
+
+impl From<&RelevantBeacon> for Beacon {
+    fn from(beacon: &RelevantBeacon) -> Self {
+        Self {
+            proximity_uuid: beacon.proximity_uuid.clone(),
+            major: beacon.major,
+            minor: beacon.minor,
+            relevant_text: beacon.relevant_text.clone(),
+        }
+    }
+}
+
+/// NFC payload for Value Added Services terminals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Nfc {
+    pub message: String,
+    /// Base64 encoded public key the terminal uses to encrypt the message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encryption_public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requires_authentication: Option<bool>,
+}
+
+impl Nfc {
+    /// Longest message a terminal accepts, in bytes
+    pub const MAX_MESSAGE_LEN: usize = 64;
+
+    /// Create a payload that sends `message` to the terminal
+    pub fn new(message: impl Into<String>) -> Result<Self> {
+        let message = message.into();
+        if message.len() > Self::MAX_MESSAGE_LEN {
+            return Err(PorterError::ValidationError(format!(
+                "NFC message is {} bytes, the limit is {}",
+                message.len(),
+                Self::MAX_MESSAGE_LEN
+            )));
+        }
+        Ok(Self {
+            message,
+            encryption_public_key: None,
+            requires_authentication: None,
+        })
+    }
+
+    /// Encrypt the message for the terminal holding the private half of `public_key_pem`
+    ///
+    /// The key must be a P-256 public key in a PEM `PUBLIC KEY` document,
+    /// as issued with an NFC certificate from Apple.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use porter::apple::Nfc;
+    ///
+    /// # let public_key_pem = "-----BEGIN PUBLIC KEY-----
+    /// # MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEWr7RN+P5g+kJbQs/sYch8sL+qv1x
+    /// # UsY4Lecy4MyR/WEbJynn8X3WiVCv5J63MG2lhbNMB43CtEc21pp84hPwNQ==
+    /// # -----END PUBLIC KEY-----";
+    /// let nfc = Nfc::new("MEMBER-0042")?
+    ///     .encryption_public_key(public_key_pem)?
+    ///     .requires_authentication();
+    /// # Ok::<(), porter::PorterError>(())
+    /// ```
+    pub fn encryption_public_key(mut self, public_key_pem: &str) -> Result<Self> {
+        let der = pem_body(public_key_pem)?;
+        let (_, key) = SubjectPublicKeyInfo::from_der(&der)
+            .map_err(|e| PorterError::ValidationError(format!("Invalid NFC public key: {}", e)))?;
+        let curve = key
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.as_oid().ok());
+        if key.algorithm.algorithm != OID_KEY_TYPE_EC_PUBLIC_KEY || curve != Some(OID_EC_P256) {
+            return Err(PorterError::ValidationError(
+                "NFC public key must be a P-256 key".to_string(),
+            ));
+        }
+        self.encryption_public_key = Some(STANDARD.encode(&der));
+        Ok(self)
+    }
+
+    /// Require the device to be unlocked before sending the message
+    pub fn requires_authentication(mut self) -> Self {
+        self.requires_authentication = Some(true);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apple::validate_pass_json;
+    use serde_json::json;
+
+    fn boarding_pass() -> ApplePass {
+        let mut pass = ApplePass::new(
+            "pass.com.example.flight",
+            "BP-001",
+            "ABCDE12345",
+            "Example Air",
+            "Boarding pass",
+            PassStyle::BoardingPass(PassStructure {
+                primary_fields: vec![
+                    Field::new("origin", "London", "LHR"),
+                    Field::new("destination", "Boston", "BOS")
+                        .with_change_message("Now arriving at %@"),
+                ],
+                auxiliary_fields: vec![Field::new("fare", "Fare", 420.0)],
+                transit_type: Some(TransitType::Air),
+                ..Default::default()
+            }),
+        );
+        pass.barcodes.push(Barcode {
+            format: BarcodeFormat::Pdf417,
+            message: "M1LOVELACE/ADA".to_string(),
+            message_encoding: "iso-8859-1".to_string(),
+            alt_text: None,
+        });
+        pass.web_service_url = Some("https://example.com/passes".to_string());
+        pass
+    }
+
+    #[test]
+    fn test_serializes_pass_json_keys() {
+        let json = serde_json::to_value(boarding_pass()).unwrap();
+
+        assert_eq!(json["formatVersion"], 1);
+        assert_eq!(json["webServiceURL"], "https://example.com/passes");
+        assert_eq!(json["barcodes"][0]["format"], "PKBarcodeFormatPDF417");
+        assert_eq!(json["boardingPass"]["transitType"], "PKTransitTypeAir");
+        assert_eq!(
+            json["boardingPass"]["auxiliaryFields"][0],
+            json!({"key": "fare", "value": 420.0, "label": "Fare"})
+        );
+        assert!(json.get("locations").is_none());
+        assert!(validate_pass_json(&json).is_ok());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let pass = boarding_pass();
+        let json = serde_json::to_string(&pass).unwrap();
+
+        assert_eq!(serde_json::from_str::<ApplePass>(&json).unwrap(), pass);
+    }
+
+    #[test]
+    fn test_with_relevance() {
+        let relevance = crate::PassBuilder::new("issuer.pass", "issuer.class")
+            .relevant_date("2026-05-01T18:30:00Z".parse().unwrap())
+            .relevant_location(42.3656, -71.0096, Some("Gate B12".to_string()))
+            .relevant_beacon(RelevantBeacon {
+                proximity_uuid: "E2C56DB5-DFFB-48D2-B060-D0F5A71096E0".to_string(),
+                major: Some(1),
+                minor: None,
+                relevant_text: None,
+            })
+            .build()
+            .relevance;
+
+        let json = serde_json::to_value(boarding_pass().with_relevance(&relevance)).unwrap();
+        assert_eq!(json["relevantDate"], "2026-05-01T18:30:00Z");
+        assert_eq!(
+            json["locations"][0],
+            json!({"latitude": 42.3656, "longitude": -71.0096, "relevantText": "Gate B12"})
+        );
+        assert_eq!(
+            json["beacons"][0],
+            json!({"proximityUUID": "E2C56DB5-DFFB-48D2-B060-D0F5A71096E0", "major": 1})
+        );
+        assert!(json.get("maxDistance").is_none());
+        assert!(validate_pass_json(&json).is_ok());
+    }
+
+    #[test]
+    fn test_nfc() {
+        let key = include_str!("testdata/apns_key.pub.pem");
+        let nfc = Nfc::new("MEMBER-0042")
+            .unwrap()
+            .encryption_public_key(key)
+            .unwrap()
+            .requires_authentication();
+
+        assert_eq!(
+            STANDARD
+                .decode(nfc.encryption_public_key.as_ref().unwrap())
+                .unwrap(),
+            pem_body(key).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&nfc).unwrap()["requiresAuthentication"],
+            true
+        );
+
+        assert!(Nfc::new("x".repeat(Nfc::MAX_MESSAGE_LEN + 1)).is_err());
+        assert!(Nfc::new("MEMBER-0042")
+            .unwrap()
+            .encryption_public_key(include_str!("testdata/pass_key.pub.pem"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_with_validity() {
+        let end = "2026-12-31T23:00:00Z".parse().unwrap();
+        let pass = crate::PassBuilder::new("issuer.pass", "issuer.class")
+            .valid_until(end)
+            .build();
+
+        let active = boarding_pass().with_validity(pass.valid_time_interval.as_ref(), &pass.state);
+        assert_eq!(active.expiration_date, Some(end));
+        assert_eq!(active.voided, None);
+
+        let expired = boarding_pass().with_validity(None, &PassState::Expired);
+        assert_eq!(expired.expiration_date, None);
+        assert_eq!(expired.voided, Some(true));
+    }
+
+    #[test]
+    fn test_event_ticket_seat_fields() {
+        let pass = crate::PassBuilder::new("issuer.ticket-123", "issuer.concert")
+            .pass_type(crate::models::PassType::EventTicket)
+            .title("The Rust Band Live")
+            .seat(crate::models::Seat {
+                section: Some("102".to_string()),
+                row: Some("F".to_string()),
+                number: Some("12".to_string()),
+            })
+            .build();
+        let apple = ApplePass::event_ticket(
+            &pass,
+            "pass.com.example.ticket",
+            "ABCDE12345",
+            "Example Events",
+        );
+        let json = serde_json::to_value(&apple).unwrap();
+
+        assert_eq!(json["serialNumber"], "issuer.ticket-123");
+        assert_eq!(
+            json["eventTicket"]["primaryFields"],
+            json!([{"key": "event", "label": "Event", "value": "The Rust Band Live"}])
+        );
+        assert_eq!(
+            json["eventTicket"]["auxiliaryFields"],
+            json!([
+                {"key": "section", "label": "Section", "value": "102"},
+                {"key": "row", "label": "Row", "value": "F"},
+                {"key": "seat", "label": "Seat", "value": "12"},
+            ])
+        );
+        assert_eq!(json["semantics"]["seats"][0]["seatSection"], "102");
+        assert_eq!(json["semantics"]["eventName"], "The Rust Band Live");
+        assert!(validate_pass_json(&json).is_ok());
+
+        let unseated = crate::PassBuilder::new("issuer.ticket-124", "issuer.concert")
+            .seat(crate::models::Seat {
+                row: Some("G".to_string()),
+                ..Default::default()
+            })
+            .build();
+        let apple = ApplePass::event_ticket(
+            &unseated,
+            "pass.com.example.ticket",
+            "ABCDE12345",
+            "Example Events",
+        );
+        assert_eq!(apple.style.structure().auxiliary_fields.len(), 1);
+        assert_eq!(apple.style.structure().auxiliary_fields[0].key, "row");
+    }
+
+    #[test]
+    fn test_event_ticket_snapshot() {
+        let pass = crate::PassBuilder::new("issuer.ticket-123", "issuer.concert")
+            .pass_type(crate::models::PassType::EventTicket)
+            .title("The Rust Band Live")
+            .valid_from("2026-11-20T19:30:00Z".parse().unwrap())
+            .valid_until("2026-11-20T23:00:00Z".parse().unwrap())
+            .seat(crate::models::Seat {
+                section: Some("102".to_string()),
+                row: Some("F".to_string()),
+                number: Some("12".to_string()),
+            })
+            .build();
+        let apple = ApplePass::event_ticket(
+            &pass,
+            "pass.com.example.ticket",
+            "ABCDE12345",
+            "Example Events",
+        )
+        .with_web_service("https://example.com/passes", "3f0c4e1b9a7d2c65")
+        .unwrap();
+
+        crate::snapshot::assert_pass_snapshot(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/snapshots/apple_event_ticket.json"
+            ),
+            &apple,
+        );
+    }
+
+    #[test]
+    fn test_associated_apps() {
+        let pass = boarding_pass()
+            .with_associated_app(284882215)
+            .with_associated_app(284882215)
+            .with_app_launch_url("exampleair://booking/BP-001");
+        let json = serde_json::to_value(pass).unwrap();
+
+        assert_eq!(json["associatedStoreIdentifiers"], json!([284882215]));
+        assert_eq!(json["appLaunchURL"], "exampleair://booking/BP-001");
+        assert!(validate_pass_json(&json).is_ok());
+
+        let linked = boarding_pass().with_app_link(&AppLink {
+            app_store_id: Some(284882215),
+            ..AppLink::new("exampleair://booking/BP-001")
+        });
+        assert_eq!(linked.associated_store_identifiers, vec![284882215]);
+        assert_eq!(
+            linked.app_launch_url.as_deref(),
+            Some("exampleair://booking/BP-001")
+        );
+    }
+
+    #[test]
+    fn test_with_grouping() {
+        let pass = crate::PassBuilder::new("issuer.pass", "issuer.class")
+            .grouping("order-1", Some(2))
+            .build();
+        let apple = boarding_pass().with_grouping(pass.grouping.as_ref().unwrap());
+        let json = serde_json::to_value(apple).unwrap();
+        assert_eq!(json["groupingIdentifier"], "order-1");
+    }
+
+    #[test]
+    fn test_with_web_service() {
+        let pass = boarding_pass()
+            .with_web_service("https://example.com/passes/", "3f0c4e1b9a7d2c65")
+            .unwrap();
+        assert_eq!(
+            pass.web_service_url.as_deref(),
+            Some("https://example.com/passes")
+        );
+
+        assert!(boarding_pass()
+            .with_web_service("https://example.com/passes", "short")
+            .is_err());
+        assert!(boarding_pass()
+            .with_web_service("ftp://example.com/passes", "3f0c4e1b9a7d2c65")
+            .is_err());
+    }
+
+    #[test]
+    fn test_poster_style() {
+        let pass = ApplePass::new(
+            "pass.com.example.ticket",
+            "TICKET123",
+            "ABCDE12345",
+            "Example Events",
+            "Concert ticket",
+            PassStyle::EventTicket(PassStructure {
+                additional_info_fields: vec![Field::new("doors", "Doors", "7pm")],
+                ..Default::default()
+            }),
+        )
+        .with_poster_style();
+        let json = serde_json::to_value(&pass).unwrap();
+
+        assert_eq!(
+            json["preferredStyleSchemes"],
+            json!(["posterEventTicket", "eventTicket"])
+        );
+        assert_eq!(
+            json["eventTicket"]["additionalInfoFields"][0]["key"],
+            "doors"
+        );
+        assert!(validate_pass_json(&json).is_ok());
+
+        let assets = AssetSet::new().image(AssetSlot::Icon, 2, vec![]).unwrap();
+        let error = pass.validate_poster(&assets).unwrap_err().to_string();
+        assert!(error.contains("background"), "{}", error);
+        assert!(boarding_pass()
+            .with_poster_style()
+            .validate_poster(&assets)
+            .is_err());
+    }
+}
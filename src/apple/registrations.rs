@@ -0,0 +1,369 @@
+//! Device registration storage for the web service
+//!
+//! The web service has to remember which devices hold which passes, the
+//! push token of each device, and when each pass last changed.
+//! [`RegistrationStore`] is that storage; back it with your database, or
+//! use [`MemoryRegistrationStore`] for tests and single-process services.
+
+use crate::apple::{PassRegistration, RegistrationStatus, SerialNumbers};
+use crate::error::Result;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Storage of device registrations and pass update tags
+///
+/// Update tags are opaque strings handed to devices as `lastUpdated` and
+/// sent back as `passesUpdatedSince`; a store only needs to order them.
+#[async_trait]
+pub trait RegistrationStore: Send + Sync {
+    /// Register a device for update notifications of a pass, saving its push token
+    async fn register(
+        &self,
+        registration: &PassRegistration,
+        push_token: &str,
+    ) -> Result<RegistrationStatus>;
+
+    /// Stop notifying a device about a pass
+    async fn unregister(&self, registration: &PassRegistration) -> Result<()>;
+
+    /// Serial numbers of the passes of a type registered to a device,
+    /// limited to those updated after the tag `updated_since` if given
+    ///
+    /// Returns `None` when no passes match.
+    async fn serial_numbers(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        updated_since: Option<&str>,
+    ) -> Result<Option<SerialNumbers>>;
+
+    /// Registrations of every device holding a pass
+    async fn registrations(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Vec<PassRegistration>>;
+
+    /// Push tokens of the devices holding a pass
+    async fn push_tokens(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Vec<String>>;
+
+    /// Record that a pass changed, so devices asking for updates get it
+    async fn mark_updated(&self, pass_type_identifier: &str, serial_number: &str) -> Result<()>;
+
+    /// Remove every registration of the devices using `push_token`
+    ///
+    /// Called when APNs reports the token as no longer registered, which
+    /// happens when the pass or app is removed from the device.
+    async fn remove_push_token(&self, push_token: &str) -> Result<()>;
+}
+
+/// [`RegistrationStore`] kept in memory
+///
+/// Tags are a counter that increases with every update.
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::{MemoryRegistrationStore, PassRegistration, RegistrationStore};
+///
+/// # async fn example() -> porter::Result<()> {
+/// let store = MemoryRegistrationStore::new();
+/// let registration = PassRegistration {
+///     device_library_identifier: "device-1".to_string(),
+///     pass_type_identifier: "pass.com.example.ticket".to_string(),
+///     serial_number: "TICKET123".to_string(),
+/// };
+/// store.register(&registration, "apns-token").await?;
+///
+/// store.mark_updated("pass.com.example.ticket", "TICKET123").await?;
+/// let tokens = store.push_tokens("pass.com.example.ticket", "TICKET123").await?;
+/// assert_eq!(tokens, vec!["apns-token".to_string()]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryRegistrationStore {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    /// Push token by device library identifier
+    devices: HashMap<String, String>,
+    /// Devices holding each pass, keyed by pass type and serial number
+    registrations: BTreeMap<(String, String), Vec<String>>,
+    /// Update tag of each pass
+    updated: HashMap<(String, String), u64>,
+    /// Last tag handed out
+    tag: u64,
+}
+
+impl MemoryRegistrationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn pass_key(pass_type_identifier: &str, serial_number: &str) -> (String, String) {
+    (pass_type_identifier.to_string(), serial_number.to_string())
+}
+
+#[async_trait]
+impl RegistrationStore for MemoryRegistrationStore {
+    async fn register(
+        &self,
+        registration: &PassRegistration,
+        push_token: &str,
+    ) -> Result<RegistrationStatus> {
+        let mut state = self.state();
+        let device = &registration.device_library_identifier;
+        state.devices.insert(device.clone(), push_token.to_string());
+
+        let devices = state
+            .registrations
+            .entry(pass_key(
+                &registration.pass_type_identifier,
+                &registration.serial_number,
+            ))
+            .or_default();
+        if devices.contains(device) {
+            return Ok(RegistrationStatus::AlreadyRegistered);
+        }
+        devices.push(device.clone());
+        Ok(RegistrationStatus::Created)
+    }
+
+    async fn unregister(&self, registration: &PassRegistration) -> Result<()> {
+        let mut state = self.state();
+        let key = pass_key(
+            &registration.pass_type_identifier,
+            &registration.serial_number,
+        );
+        if let Some(devices) = state.registrations.get_mut(&key) {
+            devices.retain(|device| *device != registration.device_library_identifier);
+            if devices.is_empty() {
+                state.registrations.remove(&key);
+            }
+        }
+
+        let device = &registration.device_library_identifier;
+        if !state.registrations.values().any(|d| d.contains(device)) {
+            state.devices.remove(device);
+        }
+        Ok(())
+    }
+
+    async fn serial_numbers(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        updated_since: Option<&str>,
+    ) -> Result<Option<SerialNumbers>> {
+        let state = self.state();
+        let since = updated_since.and_then(|tag| tag.parse::<u64>().ok());
+        let serial_numbers: Vec<String> = state
+            .registrations
+            .iter()
+            .filter(|((pass_type, _), devices)| {
+                pass_type == pass_type_identifier
+                    && devices.iter().any(|d| d == device_library_identifier)
+            })
+            .filter(|(key, _)| {
+                let updated = state.updated.get(*key).copied().unwrap_or_default();
+                since.is_none_or(|since| updated > since)
+            })
+            .map(|((_, serial_number), _)| serial_number.clone())
+            .collect();
+
+        if serial_numbers.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(SerialNumbers {
+            serial_numbers,
+            last_updated: state.tag.to_string(),
+        }))
+    }
+
+    async fn registrations(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Vec<PassRegistration>> {
+        let state = self.state();
+        let devices = state
+            .registrations
+            .get(&pass_key(pass_type_identifier, serial_number));
+        Ok(devices
+            .into_iter()
+            .flatten()
+            .map(|device| PassRegistration {
+                device_library_identifier: device.clone(),
+                pass_type_identifier: pass_type_identifier.to_string(),
+                serial_number: serial_number.to_string(),
+            })
+            .collect())
+    }
+
+    async fn push_tokens(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Vec<String>> {
+        let state = self.state();
+        let devices = state
+            .registrations
+            .get(&pass_key(pass_type_identifier, serial_number));
+        Ok(devices
+            .into_iter()
+            .flatten()
+            .filter_map(|device| state.devices.get(device).cloned())
+            .collect())
+    }
+
+    async fn mark_updated(&self, pass_type_identifier: &str, serial_number: &str) -> Result<()> {
+        let mut state = self.state();
+        state.tag += 1;
+        let tag = state.tag;
+        state
+            .updated
+            .insert(pass_key(pass_type_identifier, serial_number), tag);
+        Ok(())
+    }
+
+    async fn remove_push_token(&self, push_token: &str) -> Result<()> {
+        let mut state = self.state();
+        let dead: Vec<String> = state
+            .devices
+            .iter()
+            .filter(|(_, token)| *token == push_token)
+            .map(|(device, _)| device.clone())
+            .collect();
+        for device in &dead {
+            state.devices.remove(device);
+        }
+        state.registrations.retain(|_, devices| {
+            devices.retain(|device| !dead.contains(device));
+            !devices.is_empty()
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASS_TYPE: &str = "pass.com.example.ticket";
+
+    fn registration(device: &str, serial_number: &str) -> PassRegistration {
+        PassRegistration {
+            device_library_identifier: device.to_string(),
+            pass_type_identifier: PASS_TYPE.to_string(),
+            serial_number: serial_number.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        let store = MemoryRegistrationStore::new();
+        assert_eq!(
+            store
+                .register(&registration("phone", "A"), "token-1")
+                .await
+                .unwrap(),
+            RegistrationStatus::Created
+        );
+        assert_eq!(
+            store
+                .register(&registration("phone", "A"), "token-2")
+                .await
+                .unwrap(),
+            RegistrationStatus::AlreadyRegistered
+        );
+        store
+            .register(&registration("phone", "B"), "token-2")
+            .await
+            .unwrap();
+        store
+            .register(&registration("watch", "A"), "token-3")
+            .await
+            .unwrap();
+
+        let all = store
+            .serial_numbers("phone", PASS_TYPE, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(all.serial_numbers, vec!["A", "B"]);
+
+        store.mark_updated(PASS_TYPE, "B").await.unwrap();
+        let updated = store
+            .serial_numbers("phone", PASS_TYPE, Some(&all.last_updated))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.serial_numbers, vec!["B"]);
+        assert!(store
+            .serial_numbers("phone", PASS_TYPE, Some(&updated.last_updated))
+            .await
+            .unwrap()
+            .is_none());
+
+        assert_eq!(
+            store.push_tokens(PASS_TYPE, "A").await.unwrap(),
+            vec!["token-2", "token-3"]
+        );
+        assert_eq!(
+            store.registrations(PASS_TYPE, "A").await.unwrap(),
+            vec![registration("phone", "A"), registration("watch", "A")]
+        );
+
+        store.unregister(&registration("phone", "A")).await.unwrap();
+        store.unregister(&registration("phone", "B")).await.unwrap();
+        assert!(store
+            .serial_numbers("phone", PASS_TYPE, None)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            store.push_tokens(PASS_TYPE, "A").await.unwrap(),
+            vec!["token-3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_push_token() {
+        let store = MemoryRegistrationStore::new();
+        for (device, serial_number, token) in [
+            ("phone", "A", "token-1"),
+            ("phone", "B", "token-1"),
+            ("watch", "A", "token-2"),
+        ] {
+            store
+                .register(&registration(device, serial_number), token)
+                .await
+                .unwrap();
+        }
+
+        store.remove_push_token("token-1").await.unwrap();
+        assert!(store
+            .serial_numbers("phone", PASS_TYPE, None)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store.push_tokens(PASS_TYPE, "B").await.unwrap().is_empty());
+        assert_eq!(
+            store.push_tokens(PASS_TYPE, "A").await.unwrap(),
+            vec!["token-2"]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::error::{PorterError, Result};
+use serde_json::Value;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// JSON Schema for pass.json, bundled with the crate
+pub const PASS_SCHEMA: &str = include_str!("pass.schema.json");
+
+/// A place where pass.json does not match the schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value (empty for the root)
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+/// Check pass.json against the bundled schema
+///
+/// Wallet silently refuses passes with structural errors, so run this before
+/// signing.
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::validate_pass_json;
+/// use serde_json::json;
+///
+/// let pass = json!({
+///     "formatVersion": 1,
+///     "passTypeIdentifier": "pass.com.example",
+///     "serialNumber": "001",
+///     "teamIdentifier": "ABCDE12345",
+///     "organizationName": "Example",
+///     "description": "Example pass",
+///     "generic": { "primaryFields": [{ "key": "name", "value": "Ada" }] }
+/// });
+/// assert!(validate_pass_json(&pass).is_ok());
+/// ```
+pub fn validate_pass_json(pass: &Value) -> Result<()> {
+    let violations = pass_json_violations(pass);
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    Err(PorterError::ValidationError(format!(
+        "pass.json does not match the schema: {}",
+        violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    )))
+}
+
+/// List every place pass.json does not match the bundled schema
+pub fn pass_json_violations(pass: &Value) -> Vec<SchemaViolation> {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    let schema =
+        SCHEMA.get_or_init(|| serde_json::from_str(PASS_SCHEMA).expect("bundled schema is valid"));
+
+    let mut violations = Vec::new();
+    Validator { root: schema }.validate(schema, pass, "", &mut violations);
+    violations
+}
+
+/// Validator for the subset of JSON Schema used by the bundled schema
+struct Validator<'a> {
+    root: &'a Value,
+}
+
+impl Validator<'_> {
+    fn validate(&self, schema: &Value, value: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+        let mut fail = |message: String| {
+            out.push(SchemaViolation {
+                path: path.to_string(),
+                message,
+            })
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                Some(target) => self.validate(target, value, path, out),
+                None => fail(format!("unresolved schema reference {}", reference)),
+            }
+            return;
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            if !types.iter().any(|t| is_type(value, t)) {
+                fail(format!(
+                    "expected {}, found {}",
+                    types.join(" or "),
+                    type_name(value)
+                ));
+                return;
+            }
+        }
+
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            if !options.contains(value) {
+                fail(format!(
+                    "{} is not one of {}",
+                    value,
+                    Value::from(options.clone())
+                ));
+            }
+        }
+
+        if let (Some(min), Some(s)) = (
+            schema.get("minLength").and_then(Value::as_u64),
+            value.as_str(),
+        ) {
+            if (s.chars().count() as u64) < min {
+                fail(format!("must be at least {} characters", min));
+            }
+        }
+
+        if let Some(items) = value.as_array() {
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    fail(format!("must have at most {} items", max));
+                }
+            }
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    fail(format!("must have at least {} items", min));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    self.validate(item_schema, item, &format!("{}/{}", path, index), out);
+                }
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            for key in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(key) = key.as_str() {
+                    if !object.contains_key(key) {
+                        out.push(SchemaViolation {
+                            path: path.to_string(),
+                            message: format!("missing required key `{}`", key),
+                        });
+                    }
+                }
+            }
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (key, property_schema) in properties {
+                    if let Some(property) = object.get(key) {
+                        self.validate(property_schema, property, &format!("{}/{}", path, key), out);
+                    }
+                }
+            }
+        }
+
+        for sub_schema in schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.validate(sub_schema, value, path, out);
+        }
+
+        if let Some(options) = schema.get("oneOf").and_then(Value::as_array) {
+            let matching = options
+                .iter()
+                .filter(|option| {
+                    let mut scratch = Vec::new();
+                    self.validate(option, value, path, &mut scratch);
+                    scratch.is_empty()
+                })
+                .count();
+            if matching != 1 {
+                out.push(SchemaViolation {
+                    path: path.to_string(),
+                    message: format!(
+                        "must match exactly one of {} alternatives, matched {}",
+                        options.len(),
+                        matching
+                    ),
+                });
+            }
+        }
+    }
+
+    /// Resolve a local `#/...` reference
+    fn resolve(&self, reference: &str) -> Option<&Value> {
+        self.root.pointer(reference.strip_prefix('#')?)
+    }
+}
+
+fn is_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn valid_pass() -> Value {
+        json!({
+            "formatVersion": 1,
+            "passTypeIdentifier": "pass.com.example",
+            "serialNumber": "001",
+            "teamIdentifier": "ABCDE12345",
+            "organizationName": "Example",
+            "description": "Boarding pass",
+            "barcodes": [{
+                "format": "PKBarcodeFormatQR",
+                "message": "ABC",
+                "messageEncoding": "iso-8859-1"
+            }],
+            "boardingPass": {
+                "transitType": "PKTransitTypeAir",
+                "primaryFields": [{ "key": "origin", "value": "SFO" }]
+            }
+        })
+    }
+
+    #[test]
+    fn test_valid_pass() {
+        assert_eq!(pass_json_violations(&valid_pass()), vec![]);
+    }
+
+    #[test]
+    fn test_structural_errors() {
+        let mut pass = valid_pass();
+        pass["formatVersion"] = json!("1");
+        pass["barcodes"][0]["format"] = json!("QR");
+        pass["boardingPass"]["primaryFields"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("value");
+        pass.as_object_mut().unwrap().remove("serialNumber");
+
+        let paths: Vec<String> = pass_json_violations(&pass)
+            .into_iter()
+            .map(|v| v.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "",
+                "/barcodes/0/format",
+                "/boardingPass/primaryFields/0",
+                "/formatVersion",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_requires_single_style() {
+        let mut pass = valid_pass();
+        pass["generic"] = json!({});
+        assert!(validate_pass_json(&pass).is_err());
+
+        pass.as_object_mut().unwrap().remove("generic");
+        pass.as_object_mut().unwrap().remove("boardingPass");
+        assert!(validate_pass_json(&pass).is_err());
+    }
+}
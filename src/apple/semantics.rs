@@ -0,0 +1,324 @@
+//! Apple Wallet semantic tags
+//!
+//! Semantic tags describe a pass in machine-readable form, so Wallet and Siri
+//! can surface details such as seat assignments. They go under the
+//! `semantics` key of pass.json.
+
+use crate::models::{Pass, PassType, Seat};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable details of a pass or one of its fields
+///
+/// Set on [`ApplePass::semantics`](super::ApplePass::semantics) for the
+/// whole pass, or on [`Field::semantics`](super::Field::semantics) for the
+/// value of one field.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use porter::apple::{SemanticLocation, SemanticTags};
+///
+/// let tags = SemanticTags::default()
+///     .event("The Rust Band Live", Utc.with_ymd_and_hms(2026, 11, 20, 19, 30, 0).unwrap())
+///     .venue("Example Arena", Some(SemanticLocation { latitude: 51.5, longitude: -0.1 }));
+///
+/// let json = serde_json::to_value(&tags)?;
+/// assert_eq!(json["eventName"], "The Rust Band Live");
+/// assert_eq!(json["venueName"], "Example Arena");
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTags {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub seats: Vec<SemanticSeat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<EventType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_start_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_end_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub performer_names: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue_location: Option<SemanticLocation>,
+    /// Entrance or gate to use at the venue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue_entrance: Option<String>,
+    /// IATA code such as `BA`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airline_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_number: Option<u32>,
+    /// Airline code and flight number, such as `BA212`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub departure_airport_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub departure_airport_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub departure_gate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_airport_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_airport_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_departure_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_departure_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_arrival_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_arrival_date: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boarding_group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passenger_name: Option<PersonName>,
+    /// Balance of a store card or gift card
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<CurrencyAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_price: Option<CurrencyAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmation_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub membership_program_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub membership_program_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub silence_requested: Option<bool>,
+}
+
+impl SemanticTags {
+    /// Semantic tags for a pass, from the same fields used for Google passes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use porter::apple::SemanticTags;
+    /// use porter::models::Seat;
+    /// use porter::PassBuilder;
+    ///
+    /// let pass = PassBuilder::new("issuer.pass", "issuer.class")
+    ///     .seat(Seat {
+    ///         section: Some("102".to_string()),
+    ///         row: Some("F".to_string()),
+    ///         number: Some("12".to_string()),
+    ///     })
+    ///     .build();
+    ///
+    /// let tags = SemanticTags::from_pass(&pass);
+    /// assert_eq!(tags.seats[0].seat_row.as_deref(), Some("F"));
+    /// ```
+    ///
+    /// Event tickets also carry the pass title as the event name, and the
+    /// validity interval as the event dates.
+    pub fn from_pass(pass: &Pass) -> Self {
+        let mut tags = Self {
+            seats: pass.seat().iter().map(SemanticSeat::from).collect(),
+            ..Default::default()
+        };
+        if pass.pass_type == PassType::EventTicket {
+            tags.event_name = Some(pass.header.title.clone());
+            if let Some(interval) = &pass.valid_time_interval {
+                tags.event_start_date = Some(interval.start);
+                tags.event_end_date = interval.end;
+            }
+        }
+        tags
+    }
+
+    /// Set the event name and start date
+    pub fn event(mut self, name: impl Into<String>, start: DateTime<Utc>) -> Self {
+        self.event_name = Some(name.into());
+        self.event_start_date = Some(start);
+        self
+    }
+
+    /// Set the venue name and location
+    pub fn venue(mut self, name: impl Into<String>, location: Option<SemanticLocation>) -> Self {
+        self.venue_name = Some(name.into());
+        self.venue_location = location;
+        self
+    }
+
+    /// Add a seat
+    pub fn seat(mut self, seat: SemanticSeat) -> Self {
+        self.seats.push(seat);
+        self
+    }
+
+    /// Set the airline code and flight number, and the flight code made from them
+    pub fn flight(mut self, airline_code: impl Into<String>, flight_number: u32) -> Self {
+        let airline_code = airline_code.into();
+        self.flight_code = Some(format!("{}{}", airline_code, flight_number));
+        self.airline_code = Some(airline_code);
+        self.flight_number = Some(flight_number);
+        self
+    }
+
+    /// Set the departure and destination airport codes
+    pub fn route(mut self, departure: impl Into<String>, destination: impl Into<String>) -> Self {
+        self.departure_airport_code = Some(departure.into());
+        self.destination_airport_code = Some(destination.into());
+        self
+    }
+
+    /// Set the passenger or ticket holder's name
+    pub fn passenger(
+        mut self,
+        given_name: impl Into<String>,
+        family_name: impl Into<String>,
+    ) -> Self {
+        self.passenger_name = Some(PersonName {
+            given_name: Some(given_name.into()),
+            family_name: Some(family_name.into()),
+        });
+        self
+    }
+
+    /// Set the balance of a store card, as a decimal amount such as `"12.50"`
+    pub fn balance(mut self, amount: impl Into<String>, currency_code: impl Into<String>) -> Self {
+        self.balance = Some(CurrencyAmount {
+            amount: amount.into(),
+            currency_code: currency_code.into(),
+        });
+        self
+    }
+}
+
+/// Kind of event an event ticket is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventType {
+    #[serde(rename = "PKEventTypeGeneric")]
+    Generic,
+    #[serde(rename = "PKEventTypeLivePerformance")]
+    LivePerformance,
+    #[serde(rename = "PKEventTypeMovie")]
+    Movie,
+    #[serde(rename = "PKEventTypeSports")]
+    Sports,
+    #[serde(rename = "PKEventTypeConference")]
+    Conference,
+    #[serde(rename = "PKEventTypeConvention")]
+    Convention,
+    #[serde(rename = "PKEventTypeWorkshop")]
+    Workshop,
+    #[serde(rename = "PKEventTypeSocialGathering")]
+    SocialGathering,
+}
+
+/// A point on the map
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SemanticLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// An amount of money
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyAmount {
+    /// Decimal amount, such as `"12.50"`
+    pub amount: String,
+    /// ISO 4217 code
+    pub currency_code: String,
+}
+
+/// A person's name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonName {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+}
+
+/// A seat, as an entry of the `seats` semantic tag
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticSeat {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_section: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_row: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_number: Option<String>,
+    /// Free-form seat description, such as "Aisle"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_description: Option<String>,
+}
+
+impl From<&Seat> for SemanticSeat {
+    fn from(seat: &Seat) -> Self {
+        Self {
+            seat_section: seat.section.clone(),
+            seat_row: seat.row.clone(),
+            seat_number: seat.number.clone(),
+            seat_description: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PassBuilder;
+
+    #[test]
+    fn test_seat_semantics() {
+        let pass = PassBuilder::new("issuer.pass", "issuer.class")
+            .seat(Seat {
+                section: Some("102".to_string()),
+                row: None,
+                number: Some("12".to_string()),
+            })
+            .build();
+
+        let json = serde_json::to_value(SemanticTags::from_pass(&pass)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"seats": [{"seatSection": "102", "seatNumber": "12"}]})
+        );
+
+        let plain = PassBuilder::new("issuer.pass", "issuer.class")
+            .title("Concert")
+            .build();
+        assert_eq!(
+            serde_json::to_value(SemanticTags::from_pass(&plain)).unwrap(),
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn test_event_semantics() {
+        let start = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let pass = PassBuilder::new("issuer.pass", "issuer.class")
+            .pass_type(PassType::EventTicket)
+            .title("Concert")
+            .valid_from(start)
+            .build();
+
+        let tags = SemanticTags::from_pass(&pass).flight("BA", 212);
+        let json = serde_json::to_value(&tags).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "eventName": "Concert",
+                "eventStartDate": "2027-01-15T08:00:00Z",
+                "airlineCode": "BA",
+                "flightNumber": 212,
+                "flightCode": "BA212"
+            })
+        );
+        assert_eq!(serde_json::from_value::<SemanticTags>(json).unwrap(), tags);
+    }
+}
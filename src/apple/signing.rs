@@ -0,0 +1,488 @@
+//! Detached PKCS#7 signatures for pass manifests
+//!
+//! Wallet only installs a pass whose `signature` file is a CMS signature of
+//! `manifest.json`, made with a Pass Type ID certificate and carrying Apple's
+//! WWDR intermediate so the chain can be built on device.
+//! [`PkPassBundle::verify_signature`] checks the same for an existing pass.
+//!
+//! Signing is implemented with the RustCrypto `cms` and `rsa` crates, so
+//! it works where OpenSSL cannot be linked.
+
+use crate::apple::{AppleWalletConfig, OrderPackage, PkPassBundle};
+use crate::error::{PorterError, Result};
+use cms::builder::{create_signing_time_attribute, SignedDataBuilder, SignerInfoBuilder};
+use cms::cert::{CertificateChoices, IssuerAndSerialNumber};
+use cms::content_info::ContentInfo;
+use cms::signed_data::{EncapsulatedContentInfo, SignedData, SignerIdentifier};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePublicKey};
+use rsa::signature::Verifier;
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::time::SystemTime;
+use x509_cert::der::oid::ObjectIdentifier;
+use x509_cert::der::{Decode, DecodePem, Encode};
+use x509_cert::spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_cert::Certificate;
+
+/// `id-data` content type
+const ID_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.1");
+/// `id-sha256` digest algorithm
+const ID_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+/// `id-sha1` digest algorithm, used by older passes
+const ID_SHA1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.14.3.2.26");
+/// `id-messageDigest` signed attribute
+const ID_MESSAGE_DIGEST: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.4");
+/// `sha256WithRSAEncryption` certificate signature
+const SHA256_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+/// `sha1WithRSAEncryption` certificate signature
+const SHA1_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.5");
+
+/// Signs pass manifests with a Pass Type ID certificate
+///
+/// # Example
+///
+/// ```no_run
+/// use porter::apple::{PassSigner, PkPassBundle};
+///
+/// let signer = PassSigner::from_pem(
+///     &std::fs::read_to_string("pass.pem")?,
+///     &std::fs::read_to_string("pass.key")?,
+///     &std::fs::read_to_string("AppleWWDRCAG4.pem")?,
+/// )?;
+///
+/// let pkpass = PkPassBundle::new(&serde_json::json!({"formatVersion": 1}))?
+///     .sign(&signer)?
+///     .to_zip()?;
+/// # Ok::<(), porter::PorterError>(())
+/// ```
+pub struct PassSigner {
+    certificate: Certificate,
+    wwdr: Certificate,
+    key: SigningKey<Sha256>,
+}
+
+impl PassSigner {
+    /// Load the pass certificate, its PKCS#8 or PKCS#1 private key, and the WWDR intermediate
+    ///
+    /// Fails if the private key does not belong to the certificate.
+    pub fn from_pem(certificate_pem: &str, private_key_pem: &str, wwdr_pem: &str) -> Result<Self> {
+        let certificate = parse_certificate(certificate_pem, "pass")?;
+        let wwdr = parse_certificate(wwdr_pem, "WWDR")?;
+
+        let key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_pem(private_key_pem))
+            .map_err(|e| PorterError::ConfigError(format!("Invalid private key: {}", e)))?;
+
+        let public_key = key
+            .to_public_key()
+            .to_public_key_der()
+            .map_err(|e| PorterError::ConfigError(format!("Invalid private key: {}", e)))?;
+        let certificate_key = certificate
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()
+            .map_err(|e| PorterError::ConfigError(format!("Invalid pass certificate: {}", e)))?;
+        if public_key.as_bytes() != certificate_key.as_slice() {
+            return Err(PorterError::ConfigError(
+                "Private key does not match the pass certificate".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            certificate,
+            wwdr,
+            key: SigningKey::new(key),
+        })
+    }
+
+    /// Load the credentials of a validated configuration
+    pub fn from_config(config: &AppleWalletConfig) -> Result<Self> {
+        Self::from_pem(
+            &config.certificate,
+            &config.private_key,
+            &config.wwdr_certificate,
+        )
+    }
+
+    /// DER encoded detached signature of `manifest_json`
+    pub fn sign(&self, manifest_json: &[u8]) -> Result<Vec<u8>> {
+        let digest = Sha256::digest(manifest_json);
+        let content = EncapsulatedContentInfo {
+            econtent_type: ID_DATA,
+            econtent: None,
+        };
+        let digest_algorithm = AlgorithmIdentifierOwned {
+            oid: ID_SHA256,
+            parameters: None,
+        };
+        let sid = SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+            issuer: self.certificate.tbs_certificate.issuer.clone(),
+            serial_number: self.certificate.tbs_certificate.serial_number.clone(),
+        });
+
+        let mut signer_info = SignerInfoBuilder::new(
+            &self.key,
+            sid,
+            digest_algorithm.clone(),
+            &content,
+            Some(&digest),
+        )
+        .map_err(signing_error)?;
+        signer_info
+            .add_signed_attribute(create_signing_time_attribute().map_err(signing_error)?)
+            .map_err(signing_error)?;
+
+        let content_info = SignedDataBuilder::new(&content)
+            .add_digest_algorithm(digest_algorithm)
+            .and_then(|b| {
+                b.add_certificate(CertificateChoices::Certificate(self.certificate.clone()))
+            })
+            .and_then(|b| b.add_certificate(CertificateChoices::Certificate(self.wwdr.clone())))
+            .and_then(|b| b.add_signer_info::<_, Signature>(signer_info))
+            .and_then(|b| b.build())
+            .map_err(signing_error)?;
+        content_info.to_der().map_err(signing_error)
+    }
+}
+
+/// Build and sign a `.pkpass` from `pass.json`, asset files and PEM credentials
+///
+/// Backs the foreign language bindings, which cannot hold a [`PassSigner`]
+/// or bundle across calls. `assets` maps file names such as `icon.png` or
+/// `en.lproj/pass.strings` to their contents.
+#[cfg(any(feature = "uniffi", feature = "python"))]
+pub(crate) fn generate_pkpass(
+    pass_json: &str,
+    assets: impl IntoIterator<Item = (String, Vec<u8>)>,
+    certificate_pem: &str,
+    private_key_pem: &str,
+    wwdr_pem: &str,
+) -> Result<Vec<u8>> {
+    let pass: crate::apple::ApplePass = serde_json::from_str(pass_json)?;
+    let signer = PassSigner::from_pem(certificate_pem, private_key_pem, wwdr_pem)?;
+    let bundle = assets
+        .into_iter()
+        .try_fold(PkPassBundle::new(&pass)?, |bundle, (name, contents)| {
+            bundle.file(name, contents)
+        })?;
+    bundle.sign(&signer)?.to_zip()
+}
+
+impl OrderPackage {
+    /// Sign the manifest with `signer`, replacing any existing signature
+    ///
+    /// Orders are signed with an Order Type ID certificate; load it into a
+    /// [`PassSigner`] the same way as a Pass Type ID certificate.
+    pub fn sign(self, signer: &PassSigner) -> Result<Self> {
+        let signature = signer.sign(&self.manifest_json()?)?;
+        Ok(self.signature(signature))
+    }
+}
+
+impl PkPassBundle {
+    /// Sign the manifest with `signer`, replacing any existing signature
+    ///
+    /// Sign after the last file is added; adding files afterwards changes the
+    /// manifest and invalidates the signature.
+    pub fn sign(self, signer: &PassSigner) -> Result<Self> {
+        let signature = signer.sign(&self.manifest_json()?)?;
+        Ok(self.signature(signature))
+    }
+
+    /// Void the pass and sign the changed bundle
+    ///
+    /// Serve the result as the pass's latest version and notify its devices;
+    /// Wallet then shows the pass as void.
+    pub fn void(self, signer: &PassSigner) -> Result<Self> {
+        self.voided()?.sign(signer)
+    }
+
+    /// Check that the signature covers the manifest and was made by a
+    /// currently valid pass certificate issued by the `wwdr_pem` intermediate
+    ///
+    /// Supports RSA signatures with SHA-256 or SHA-1 digests.
+    pub fn verify_signature(&self, wwdr_pem: &str) -> Result<()> {
+        let der = self
+            .signature_der()
+            .ok_or_else(|| PorterError::SignatureError("Bundle is not signed".to_string()))?;
+        let wwdr = parse_certificate(wwdr_pem, "WWDR")?;
+        let manifest_json = self.manifest_json()?;
+
+        let signed_data = ContentInfo::from_der(der)
+            .and_then(|content_info| content_info.content.decode_as::<SignedData>())
+            .map_err(|e| invalid_signature(format!("not a PKCS#7 signature: {}", e)))?;
+        let signer_info = signed_data
+            .signer_infos
+            .0
+            .get(0)
+            .ok_or_else(|| invalid_signature("no signer".to_string()))?;
+        let SignerIdentifier::IssuerAndSerialNumber(sid) = &signer_info.sid else {
+            return Err(invalid_signature(
+                "signer is not identified by issuer and serial".to_string(),
+            ));
+        };
+        let certificate = signed_data
+            .certificates
+            .iter()
+            .flat_map(|certificates| certificates.0.iter())
+            .find_map(|choice| match choice {
+                CertificateChoices::Certificate(certificate)
+                    if certificate.tbs_certificate.issuer == sid.issuer
+                        && certificate.tbs_certificate.serial_number == sid.serial_number =>
+                {
+                    Some(certificate)
+                }
+                _ => None,
+            })
+            .ok_or_else(|| invalid_signature("signer certificate is missing".to_string()))?;
+
+        let digest_algorithm = signer_info.digest_alg.oid;
+        let digest = match digest_algorithm {
+            ID_SHA256 => Sha256::digest(&manifest_json).to_vec(),
+            ID_SHA1 => Sha1::digest(&manifest_json).to_vec(),
+            oid => return Err(invalid_signature(format!("unsupported digest {}", oid))),
+        };
+        let signed_attributes = signer_info
+            .signed_attrs
+            .as_ref()
+            .ok_or_else(|| invalid_signature("no signed attributes".to_string()))?;
+        let message_digest = signed_attributes
+            .iter()
+            .find(|attribute| attribute.oid == ID_MESSAGE_DIGEST)
+            .and_then(|attribute| attribute.values.iter().next())
+            .ok_or_else(|| invalid_signature("no message digest".to_string()))?;
+        if message_digest.value() != digest.as_slice() {
+            return Err(invalid_signature(
+                "it does not cover manifest.json".to_string(),
+            ));
+        }
+        verify_rsa(
+            &certificate.tbs_certificate.subject_public_key_info,
+            digest_algorithm,
+            &signed_attributes.to_der().map_err(signing_error)?,
+            signer_info.signature.as_bytes(),
+        )
+        .map_err(invalid_signature)?;
+
+        verify_chain(certificate, &wwdr).map_err(invalid_signature)
+    }
+}
+
+impl AppleWalletConfig {
+    /// Check that these credentials can sign passes Wallet will accept
+    ///
+    /// Besides [`validate`](Self::validate), checks that the pass
+    /// certificate was issued and signed by the WWDR intermediate and that
+    /// both are currently valid, so a deployment with an expired or
+    /// mismatched certificate fails at startup rather than when a holder
+    /// adds a pass.
+    pub fn verify_credentials(&self) -> Result<()> {
+        self.validate()?;
+        let certificate = parse_certificate(&self.certificate, "pass")?;
+        let wwdr = parse_certificate(&self.wwdr_certificate, "WWDR")?;
+        if !is_current(&wwdr) {
+            return Err(PorterError::ConfigError(
+                "WWDR certificate is not currently valid".to_string(),
+            ));
+        }
+        verify_chain(&certificate, &wwdr).map_err(PorterError::ConfigError)?;
+        PassSigner::from_config(self).map(|_| ())
+    }
+}
+
+/// Check that `certificate` was signed by `wwdr` and is currently valid
+fn verify_chain(certificate: &Certificate, wwdr: &Certificate) -> std::result::Result<(), String> {
+    if certificate.tbs_certificate.issuer != wwdr.tbs_certificate.subject {
+        return Err("pass certificate was not issued by the WWDR intermediate".to_string());
+    }
+    let certificate_digest = match certificate.signature_algorithm.oid {
+        SHA256_WITH_RSA => ID_SHA256,
+        SHA1_WITH_RSA => ID_SHA1,
+        oid => return Err(format!("unsupported certificate signature {}", oid)),
+    };
+    verify_rsa(
+        &wwdr.tbs_certificate.subject_public_key_info,
+        certificate_digest,
+        &certificate
+            .tbs_certificate
+            .to_der()
+            .map_err(|e| e.to_string())?,
+        certificate.signature.raw_bytes(),
+    )?;
+
+    if !is_current(certificate) {
+        return Err("pass certificate is not currently valid".to_string());
+    }
+    Ok(())
+}
+
+/// Whether the current time is within the certificate's validity period
+fn is_current(certificate: &Certificate) -> bool {
+    let validity = &certificate.tbs_certificate.validity;
+    let now = SystemTime::now();
+    now >= validity.not_before.to_system_time() && now <= validity.not_after.to_system_time()
+}
+
+/// Verify a PKCS#1 v1.5 RSA signature of `message`
+fn verify_rsa(
+    public_key: &SubjectPublicKeyInfoOwned,
+    digest_algorithm: ObjectIdentifier,
+    message: &[u8],
+    signature: &[u8],
+) -> std::result::Result<(), String> {
+    let key = public_key
+        .to_der()
+        .ok()
+        .and_then(|der| RsaPublicKey::from_public_key_der(&der).ok())
+        .ok_or_else(|| "only RSA keys are supported".to_string())?;
+    let signature = Signature::try_from(signature).map_err(|e| e.to_string())?;
+
+    let verified = match digest_algorithm {
+        ID_SHA256 => VerifyingKey::<Sha256>::new(key).verify(message, &signature),
+        _ => VerifyingKey::<Sha1>::new(key).verify(message, &signature),
+    };
+    verified.map_err(|_| "signature does not verify".to_string())
+}
+
+fn parse_certificate(pem: &str, name: &str) -> Result<Certificate> {
+    Certificate::from_pem(pem)
+        .map_err(|e| PorterError::ConfigError(format!("Invalid {} certificate: {}", name, e)))
+}
+
+fn invalid_signature(reason: String) -> PorterError {
+    PorterError::SignatureError(format!("Invalid pass signature: {}", reason))
+}
+
+fn signing_error(e: impl std::fmt::Display) -> PorterError {
+    PorterError::SignatureError(format!("Could not sign manifest: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::signature::Keypair;
+
+    const PASS_CERT: &str = include_str!("testdata/pass_cert.pem");
+    const PASS_KEY: &str = include_str!("testdata/pass_key.pem");
+    const WWDR: &str = include_str!("testdata/wwdr.pem");
+
+    #[test]
+    fn test_detached_signature() {
+        let signer = PassSigner::from_pem(PASS_CERT, PASS_KEY, WWDR).unwrap();
+        let manifest = br#"{"pass.json":"da39a3ee5e6b4b0d3255bfef95601890afd80709"}"#;
+
+        let der = signer.sign(manifest).unwrap();
+        let content_info = ContentInfo::from_der(&der).unwrap();
+        let signed_data = content_info.content.decode_as::<SignedData>().unwrap();
+
+        assert!(signed_data.encap_content_info.econtent.is_none());
+        assert_eq!(signed_data.certificates.unwrap().0.len(), 2);
+
+        let signer_info = signed_data.signer_infos.0.get(0).unwrap();
+        let signed_attributes = signer_info.signed_attrs.as_ref().unwrap();
+        let digest = Sha256::digest(manifest);
+        assert!(signed_attributes.iter().any(|attribute| attribute
+            .values
+            .iter()
+            .any(|value| value.value() == digest.as_slice())));
+
+        let verifying_key: VerifyingKey<Sha256> = signer.key.verifying_key();
+        let signature = Signature::try_from(signer_info.signature.as_bytes()).unwrap();
+        verifying_key
+            .verify(&signed_attributes.to_der().unwrap(), &signature)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let signer = PassSigner::from_pem(PASS_CERT, PASS_KEY, WWDR).unwrap();
+        let pass = crate::apple::ApplePass::new(
+            "pass.com.example.ticket",
+            "001",
+            "ABCDE12345",
+            "Example Events",
+            "Concert ticket",
+            crate::apple::PassStyle::EventTicket(Default::default()),
+        );
+        let pkpass = PkPassBundle::new(&pass)
+            .unwrap()
+            .sign(&signer)
+            .unwrap()
+            .to_zip()
+            .unwrap();
+
+        let bundle = PkPassBundle::read(&pkpass).unwrap();
+        assert!(bundle.verify_signature(WWDR).is_ok());
+        assert!(bundle.verify_signature(PASS_CERT).is_err());
+
+        let voided = bundle.clone().void(&signer).unwrap();
+        assert!(voided.verify_signature(WWDR).is_ok());
+        assert_eq!(voided.pass().unwrap().voided, Some(true));
+
+        let tampered = bundle.file("icon.png", vec![1]).unwrap();
+        assert!(tampered.verify_signature(WWDR).is_err());
+    }
+
+    #[test]
+    fn test_verify_credentials() {
+        let config = AppleWalletConfig {
+            pass_type_identifier: "pass.com.example.ticket".to_string(),
+            team_identifier: "ABCDE12345".to_string(),
+            certificate: PASS_CERT.to_string(),
+            private_key: PASS_KEY.to_string(),
+            wwdr_certificate: WWDR.to_string(),
+        };
+        assert!(config.verify_credentials().is_ok());
+
+        let wrong_intermediate = AppleWalletConfig {
+            wwdr_certificate: PASS_CERT.to_string(),
+            ..config
+        };
+        assert!(matches!(
+            wrong_intermediate.verify_credentials(),
+            Err(PorterError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_sign_order_package() {
+        let signer = PassSigner::from_pem(PASS_CERT, PASS_KEY, WWDR).unwrap();
+        let order = crate::apple::Order::new(
+            "order.com.example.shop",
+            "ORDER-1001",
+            crate::apple::Merchant::new(
+                "com.example.shop",
+                "Example Shop",
+                "https://example.com",
+                "logo.png",
+            ),
+            chrono::Utc::now(),
+        );
+        let package = OrderPackage::new(&order).unwrap().sign(&signer).unwrap();
+
+        let der = package.signature_der().unwrap();
+        let signed_data = ContentInfo::from_der(der)
+            .unwrap()
+            .content
+            .decode_as::<SignedData>()
+            .unwrap();
+        assert!(signed_data.encap_content_info.econtent.is_none());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_key() {
+        let other_key = rsa::pkcs8::EncodePrivateKey::to_pkcs8_pem(
+            &RsaPrivateKey::new(&mut rsa::rand_core::OsRng, 1024).unwrap(),
+            Default::default(),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            PassSigner::from_pem(PASS_CERT, &other_key, WWDR),
+            Err(PorterError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::apple::PkPassBundle;
+use crate::error::{PorterError, Result};
+use crate::models::Pass;
+use std::collections::{BTreeMap, HashMap};
+
+/// Render the `pass.strings` file for a locale from a pass's translations
+///
+/// Each translated string becomes a `"source" = "translation";` entry, so
+/// pass.json can keep the default-language text as its keys. The result goes
+/// in `<locale>.lproj/pass.strings` of the bundle.
+///
+/// Translations fall back to less specific locales, so `fr-CA` uses `fr`
+/// translations when there are no Canadian French ones.
+///
+/// # Example
+///
+/// ```
+/// use porter::apple::pass_strings;
+/// use porter::models::LocalizedValue;
+/// use porter::PassBuilder;
+///
+/// let mut pass = PassBuilder::new("issuer.pass", "issuer.class").title("Concert").build();
+/// pass.translations.push(LocalizedValue {
+///     value: "Concert".to_string(),
+///     translations: [("de".to_string(), "Konzert".to_string())].into(),
+/// });
+///
+/// assert_eq!(pass_strings(&pass, "de"), "\"Concert\" = \"Konzert\";\n");
+/// ```
+pub fn pass_strings(pass: &Pass, locale: &str) -> String {
+    pass.translations
+        .iter()
+        .filter_map(|localized| {
+            localized
+                .translation(locale)
+                .map(|translation| entry(&localized.value, translation))
+        })
+        .collect()
+}
+
+impl PkPassBundle {
+    /// Add a `<locale>.lproj/pass.strings` file for every locale in `strings`
+    ///
+    /// `strings` maps each locale, such as `es` or `pt-BR`, to the
+    /// translations of the pass.json strings in that language. Entries are
+    /// written sorted by key, so the same strings always produce the same
+    /// bundle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use porter::apple::PkPassBundle;
+    /// use std::collections::HashMap;
+    ///
+    /// let strings = HashMap::from([(
+    ///     "es".to_string(),
+    ///     HashMap::from([("Concert".to_string(), "Concierto".to_string())]),
+    /// )]);
+    ///
+    /// let bundle = PkPassBundle::new(&serde_json::json!({}))?.localizations(&strings)?;
+    /// assert_eq!(
+    ///     bundle.get("es.lproj/pass.strings"),
+    ///     Some(&b"\"Concert\" = \"Concierto\";\n"[..])
+    /// );
+    /// # Ok::<(), porter::PorterError>(())
+    /// ```
+    pub fn localizations(
+        mut self,
+        strings: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<Self> {
+        for (locale, translations) in strings {
+            if locale.is_empty()
+                || !locale
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(PorterError::ValidationError(format!(
+                    "Invalid locale: {}",
+                    locale
+                )));
+            }
+            let contents: String = translations
+                .iter()
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .map(|(key, value)| entry(key, value))
+                .collect();
+            self = self.file(
+                format!("{}.lproj/pass.strings", locale),
+                contents.into_bytes(),
+            )?;
+        }
+        Ok(self)
+    }
+}
+
+/// A `"key" = "value";` line of a `.strings` file
+fn entry(key: &str, value: &str) -> String {
+    format!("\"{}\" = \"{}\";\n", escape(key), escape(value))
+}
+
+/// Escape a value for a `.strings` file
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("Say \"hi\"\nnow"), "Say \\\"hi\\\"\\nnow");
+    }
+
+    #[test]
+    fn test_localizations() {
+        let strings = HashMap::from([
+            (
+                "de".to_string(),
+                HashMap::from([
+                    ("Seat".to_string(), "Platz".to_string()),
+                    ("Concert".to_string(), "Konzert".to_string()),
+                ]),
+            ),
+            (
+                "pt-BR".to_string(),
+                HashMap::from([("Seat".to_string(), "Assento".to_string())]),
+            ),
+        ]);
+
+        let bundle = PkPassBundle::new(&serde_json::json!({}))
+            .unwrap()
+            .localizations(&strings)
+            .unwrap();
+        assert_eq!(
+            bundle.get("de.lproj/pass.strings"),
+            Some(&b"\"Concert\" = \"Konzert\";\n\"Seat\" = \"Platz\";\n"[..])
+        );
+        assert!(bundle.manifest().contains_key("pt-BR.lproj/pass.strings"));
+
+        let invalid = HashMap::from([("../de".to_string(), HashMap::new())]);
+        assert!(PkPassBundle::new(&serde_json::json!({}))
+            .unwrap()
+            .localizations(&invalid)
+            .is_err());
+    }
+}
@@ -0,0 +1,482 @@
+//! Apple Wallet web service protocol
+//!
+//! Passes with a `webServiceURL` register with the issuer's server, which
+//! then pushes update notifications and serves new versions of the pass.
+//! Implement [`WebServiceHandler`] against your storage and route requests
+//! under the web service URL to [`handle_request`], which parses them,
+//! checks the `ApplePass` authorization header is present and maps results
+//! to the status codes Wallet expects.
+
+use crate::apple::PKPASS_MIME_TYPE;
+use crate::error::{PorterError, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Authorization scheme Wallet uses for the pass's `authenticationToken`
+const AUTHORIZATION_SCHEME: &str = "ApplePass ";
+/// Shortest `authenticationToken` Wallet accepts
+pub const MIN_AUTHENTICATION_TOKEN_LEN: usize = 16;
+/// Format of the `Last-Modified` and `If-Modified-Since` headers
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Body of a device registration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationRequest {
+    /// APNs token to notify the device with
+    pub push_token: String,
+}
+
+/// Passes registered to a device that changed since a tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialNumbers {
+    pub serial_numbers: Vec<String>,
+    /// Opaque tag the device sends back as `passesUpdatedSince`
+    pub last_updated: String,
+}
+
+/// Error messages a device reports about the web service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntries {
+    pub logs: Vec<String>,
+}
+
+/// A pass on a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PassRegistration {
+    pub device_library_identifier: String,
+    pub pass_type_identifier: String,
+    pub serial_number: String,
+}
+
+/// Outcome of registering a device for a pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationStatus {
+    Created,
+    AlreadyRegistered,
+}
+
+/// Latest version of a pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LatestPass {
+    /// The signed `.pkpass` and when it last changed
+    Updated {
+        pkpass: Vec<u8>,
+        last_modified: DateTime<Utc>,
+    },
+    /// The pass has not changed since `If-Modified-Since`
+    NotModified,
+}
+
+/// Storage behind the web service endpoints
+///
+/// Methods that take an `authentication_token` must compare it with the
+/// token of the pass and return [`PorterError::AuthError`] when it does not
+/// match; [`handle_request`] answers those with `401 Unauthorized`.
+#[async_trait]
+pub trait WebServiceHandler: Send + Sync {
+    /// Record that a device wants update notifications for a pass
+    async fn register_device(
+        &self,
+        registration: &PassRegistration,
+        authentication_token: &str,
+        request: RegistrationRequest,
+    ) -> Result<RegistrationStatus>;
+
+    /// Serial numbers of the passes of a type registered to a device,
+    /// limited to those updated since `passes_updated_since` if given
+    ///
+    /// Return `None` when no passes match.
+    async fn serial_numbers(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        passes_updated_since: Option<&str>,
+    ) -> Result<Option<SerialNumbers>>;
+
+    /// The current signed pass, unless unchanged since `if_modified_since`
+    async fn latest_pass(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+        authentication_token: &str,
+        if_modified_since: Option<DateTime<Utc>>,
+    ) -> Result<LatestPass>;
+
+    /// Stop sending update notifications for a pass to a device
+    async fn unregister_device(
+        &self,
+        registration: &PassRegistration,
+        authentication_token: &str,
+    ) -> Result<()>;
+
+    /// Errors reported by devices; ignored unless overridden
+    async fn log(&self, entries: LogEntries) -> Result<()> {
+        let _ = entries;
+        Ok(())
+    }
+}
+
+/// An HTTP response to send back to the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebServiceResponse {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl WebServiceResponse {
+    fn empty(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: vec![],
+        }
+    }
+
+    fn json(body: &impl Serialize) -> Result<Self> {
+        Ok(Self {
+            status: 200,
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: serde_json::to_vec(body)?,
+        })
+    }
+}
+
+/// Answer a web service request with `handler`
+///
+/// `path` is the request path with its query string; anything before
+/// `/v1/` (the path of the `webServiceURL`) is ignored. `headers` are
+/// matched case-insensitively.
+///
+/// # Example
+///
+/// ```no_run
+/// use porter::apple::{handle_request, WebServiceHandler};
+///
+/// # async fn example(handler: &impl WebServiceHandler, body: Vec<u8>) {
+/// let response = handle_request(
+///     handler,
+///     "POST",
+///     "/wallet/v1/devices/device-1/registrations/pass.com.example/TICKET123",
+///     &[("Authorization", "ApplePass 3f0c4e1b9a7d2c65")],
+///     &body,
+/// )
+/// .await;
+/// assert_eq!(response.status, 201);
+/// # }
+/// ```
+pub async fn handle_request(
+    handler: &impl WebServiceHandler,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> WebServiceResponse {
+    match route(handler, method, path, headers, body).await {
+        Ok(response) => response,
+        Err(e) => WebServiceResponse::empty(match e {
+            PorterError::AuthError(_) => 401,
+            PorterError::NotFound(_) => 404,
+            PorterError::ValidationError(_) | PorterError::JsonError(_) => 400,
+            _ => 500,
+        }),
+    }
+}
+
+async fn route(
+    handler: &impl WebServiceHandler,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<WebServiceResponse> {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+    let segments: Vec<&str> = match path.find("/v1/") {
+        Some(start) => path[start + 4..].split('/').collect(),
+        None => vec![],
+    };
+    let not_found = || PorterError::NotFound(format!("No web service endpoint at {}", path));
+
+    match (method, segments.as_slice()) {
+        ("POST", ["devices", device, "registrations", pass_type, serial]) => {
+            let registration = registration(device, pass_type, serial);
+            let request = serde_json::from_slice(body)?;
+            let status = handler
+                .register_device(&registration, authentication_token(headers)?, request)
+                .await?;
+            Ok(WebServiceResponse::empty(match status {
+                RegistrationStatus::Created => 201,
+                RegistrationStatus::AlreadyRegistered => 200,
+            }))
+        }
+        ("DELETE", ["devices", device, "registrations", pass_type, serial]) => {
+            let registration = registration(device, pass_type, serial);
+            handler
+                .unregister_device(&registration, authentication_token(headers)?)
+                .await?;
+            Ok(WebServiceResponse::empty(200))
+        }
+        ("GET", ["devices", device, "registrations", pass_type]) => {
+            let since = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("passesUpdatedSince="))
+                .filter(|since| !since.is_empty());
+            match handler.serial_numbers(device, pass_type, since).await? {
+                Some(serial_numbers) => WebServiceResponse::json(&serial_numbers),
+                None => Ok(WebServiceResponse::empty(204)),
+            }
+        }
+        ("GET", ["passes", pass_type, serial]) => {
+            let if_modified_since = header(headers, "If-Modified-Since").and_then(|date| {
+                DateTime::parse_from_rfc2822(date)
+                    .ok()
+                    .map(|date| date.with_timezone(&Utc))
+            });
+            let latest = handler
+                .latest_pass(
+                    pass_type,
+                    serial,
+                    authentication_token(headers)?,
+                    if_modified_since,
+                )
+                .await?;
+            Ok(match latest {
+                LatestPass::Updated {
+                    pkpass,
+                    last_modified,
+                } => WebServiceResponse {
+                    status: 200,
+                    headers: vec![
+                        ("Content-Type", PKPASS_MIME_TYPE.to_string()),
+                        (
+                            "Last-Modified",
+                            last_modified.format(HTTP_DATE_FORMAT).to_string(),
+                        ),
+                    ],
+                    body: pkpass,
+                },
+                LatestPass::NotModified => WebServiceResponse::empty(304),
+            })
+        }
+        ("POST", ["log"]) => {
+            handler.log(serde_json::from_slice(body)?).await?;
+            Ok(WebServiceResponse::empty(200))
+        }
+        _ => Err(not_found()),
+    }
+}
+
+fn registration(device: &str, pass_type: &str, serial: &str) -> PassRegistration {
+    PassRegistration {
+        device_library_identifier: device.to_string(),
+        pass_type_identifier: pass_type.to_string(),
+        serial_number: serial.to_string(),
+    }
+}
+
+fn header<'a>(headers: &[(&str, &'a str)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+/// Check the token a device sent against the pass's token, in constant time
+///
+/// Fails with [`PorterError::AuthError`], which [`handle_request`] answers
+/// with `401 Unauthorized`.
+pub fn verify_authentication_token(expected: &str, provided: &str) -> Result<()> {
+    let (expected, provided) = (expected.as_bytes(), provided.as_bytes());
+    let matches = expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0;
+    if matches {
+        Ok(())
+    } else {
+        Err(PorterError::AuthError(
+            "Invalid authentication token".to_string(),
+        ))
+    }
+}
+
+/// The token from an `Authorization: ApplePass <token>` header
+fn authentication_token<'a>(headers: &[(&str, &'a str)]) -> Result<&'a str> {
+    header(headers, "Authorization")
+        .and_then(|value| value.strip_prefix(AUTHORIZATION_SCHEME))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| PorterError::AuthError("Missing ApplePass authorization".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const TOKEN: &str = "3f0c4e1b9a7d2c65";
+
+    #[derive(Default)]
+    struct MemoryHandler {
+        registrations: Mutex<Vec<(PassRegistration, String)>>,
+    }
+
+    fn check(token: &str) -> Result<()> {
+        verify_authentication_token(TOKEN, token)
+    }
+
+    #[async_trait]
+    impl WebServiceHandler for MemoryHandler {
+        async fn register_device(
+            &self,
+            registration: &PassRegistration,
+            authentication_token: &str,
+            request: RegistrationRequest,
+        ) -> Result<RegistrationStatus> {
+            check(authentication_token)?;
+            let mut registrations = self.registrations.lock().unwrap();
+            if registrations.iter().any(|(r, _)| r == registration) {
+                return Ok(RegistrationStatus::AlreadyRegistered);
+            }
+            registrations.push((registration.clone(), request.push_token));
+            Ok(RegistrationStatus::Created)
+        }
+
+        async fn serial_numbers(
+            &self,
+            device_library_identifier: &str,
+            _pass_type_identifier: &str,
+            passes_updated_since: Option<&str>,
+        ) -> Result<Option<SerialNumbers>> {
+            if passes_updated_since == Some("2") {
+                return Ok(None);
+            }
+            let serial_numbers: Vec<_> = self
+                .registrations
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(r, _)| r.device_library_identifier == device_library_identifier)
+                .map(|(r, _)| r.serial_number.clone())
+                .collect();
+            Ok(Some(SerialNumbers {
+                serial_numbers,
+                last_updated: "2".to_string(),
+            }))
+        }
+
+        async fn latest_pass(
+            &self,
+            _pass_type_identifier: &str,
+            _serial_number: &str,
+            authentication_token: &str,
+            if_modified_since: Option<DateTime<Utc>>,
+        ) -> Result<LatestPass> {
+            check(authentication_token)?;
+            let last_modified = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+            if if_modified_since >= Some(last_modified) {
+                return Ok(LatestPass::NotModified);
+            }
+            Ok(LatestPass::Updated {
+                pkpass: vec![1, 2, 3],
+                last_modified,
+            })
+        }
+
+        async fn unregister_device(
+            &self,
+            registration: &PassRegistration,
+            authentication_token: &str,
+        ) -> Result<()> {
+            check(authentication_token)?;
+            self.registrations
+                .lock()
+                .unwrap()
+                .retain(|(r, _)| r != registration);
+            Ok(())
+        }
+    }
+
+    const REGISTRATION: &str = "/wallet/v1/devices/device-1/registrations/pass.com.example/T1";
+    const AUTHORIZATION: [(&str, &str); 1] = [("authorization", "ApplePass 3f0c4e1b9a7d2c65")];
+
+    #[tokio::test]
+    async fn test_registration_flow() {
+        let handler = MemoryHandler::default();
+        let body = br#"{"pushToken": "apns-token"}"#;
+
+        let created = handle_request(&handler, "POST", REGISTRATION, &AUTHORIZATION, body).await;
+        assert_eq!(created.status, 201);
+        let again = handle_request(&handler, "POST", REGISTRATION, &AUTHORIZATION, body).await;
+        assert_eq!(again.status, 200);
+        let unauthorized = handle_request(&handler, "POST", REGISTRATION, &[], body).await;
+        assert_eq!(unauthorized.status, 401);
+
+        let listed = handle_request(
+            &handler,
+            "GET",
+            "/wallet/v1/devices/device-1/registrations/pass.com.example?passesUpdatedSince=1",
+            &[],
+            &[],
+        )
+        .await;
+        assert_eq!(listed.status, 200);
+        assert_eq!(
+            serde_json::from_slice::<SerialNumbers>(&listed.body).unwrap(),
+            SerialNumbers {
+                serial_numbers: vec!["T1".to_string()],
+                last_updated: "2".to_string(),
+            }
+        );
+        let unchanged = handle_request(
+            &handler,
+            "GET",
+            "/wallet/v1/devices/device-1/registrations/pass.com.example?passesUpdatedSince=2",
+            &[],
+            &[],
+        )
+        .await;
+        assert_eq!(unchanged.status, 204);
+
+        let deleted = handle_request(&handler, "DELETE", REGISTRATION, &AUTHORIZATION, &[]).await;
+        assert_eq!(deleted.status, 200);
+        assert!(handler.registrations.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_latest_pass() {
+        let handler = MemoryHandler::default();
+        let path = "/v1/passes/pass.com.example/T1";
+
+        let latest = handle_request(&handler, "GET", path, &AUTHORIZATION, &[]).await;
+        assert_eq!(latest.status, 200);
+        assert_eq!(latest.body, vec![1, 2, 3]);
+        let last_modified = latest.headers[1].1.clone();
+        assert_eq!(last_modified, "Fri, 15 Jan 2027 08:00:00 GMT");
+
+        let headers = [AUTHORIZATION[0], ("If-Modified-Since", &last_modified)];
+        let cached = handle_request(&handler, "GET", path, &headers, &[]).await;
+        assert_eq!(cached.status, 304);
+
+        let unknown = handle_request(&handler, "GET", "/v1/unknown", &[], &[]).await;
+        assert_eq!(unknown.status, 404);
+        let logged = handle_request(&handler, "POST", "/v1/log", &[], br#"{"logs": []}"#).await;
+        assert_eq!(logged.status, 200);
+    }
+
+    #[test]
+    fn test_authentication_tokens() {
+        let token = crate::apple::generate_authentication_token().unwrap();
+        assert!(verify_authentication_token(&token, &token).is_ok());
+        assert!(verify_authentication_token(&token, &token[1..]).is_err());
+        assert!(matches!(
+            verify_authentication_token(TOKEN, "3f0c4e1b9a7d2c66"),
+            Err(PorterError::AuthError(_))
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+//! Audit trail for pass mutations
+//!
+//! Clients with an [`AuditSink`] attached emit an [`AuditEvent`] for every
+//! successful create, update, patch, expire and message call.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Kind of mutation that was performed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Patch,
+    Expire,
+    AddMessage,
+}
+
+/// Structured record of a single mutation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// Who performed the mutation (the service account unless overridden)
+    pub actor: String,
+    pub action: AuditAction,
+    /// API resource type, e.g. `genericObject`
+    pub resource_type: String,
+    pub resource_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// State before the mutation, when the sink asked for it to be captured
+    pub before: Option<Value>,
+    /// State returned by the platform after the mutation
+    pub after: Option<Value>,
+}
+
+impl AuditEvent {
+    /// Field level changes between `before` and `after`
+    ///
+    /// Without a captured `before` state every field of `after` is reported.
+    pub fn diff(&self) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        diff_values(
+            "",
+            self.before.as_ref().unwrap_or(&Value::Null),
+            self.after.as_ref().unwrap_or(&Value::Null),
+            &mut changes,
+        );
+        changes
+    }
+}
+
+/// A single changed field, addressed by a dotted path
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Destination for audit events
+///
+/// # Example
+///
+/// ```
+/// use porter::audit::{AuditEvent, AuditSink};
+///
+/// struct StdoutSink;
+///
+/// #[async_trait::async_trait]
+/// impl AuditSink for StdoutSink {
+///     async fn record(&self, event: AuditEvent) {
+///         println!("{} {:?} {}", event.actor, event.action, event.resource_id);
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Record an event
+    async fn record(&self, event: AuditEvent);
+
+    /// Whether the client should fetch the current state before updates so
+    /// events carry a `before` snapshot (costs one extra GET per update)
+    fn capture_before(&self) -> bool {
+        false
+    }
+}
+
+fn diff_values(path: &str, before: &Value, after: &Value, changes: &mut Vec<FieldChange>) {
+    if before == after {
+        return;
+    }
+
+    match (before, after) {
+        (Value::Object(before_map), Value::Object(after_map)) => {
+            let mut keys: Vec<&String> = before_map.keys().chain(after_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(
+                    &child,
+                    before_map.get(key).unwrap_or(&Value::Null),
+                    after_map.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (Value::Null, Value::Object(_)) => {
+            diff_values(path, &Value::Object(Default::default()), after, changes)
+        }
+        (Value::Object(_), Value::Null) => {
+            diff_values(path, before, &Value::Object(Default::default()), changes)
+        }
+        _ => changes.push(FieldChange {
+            path: path.to_string(),
+            before: Some(before.clone()).filter(|v| !v.is_null()),
+            after: Some(after.clone()).filter(|v| !v.is_null()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(before: Option<Value>, after: Option<Value>) -> AuditEvent {
+        AuditEvent {
+            actor: "test".to_string(),
+            action: AuditAction::Update,
+            resource_type: "genericObject".to_string(),
+            resource_id: "issuer.object".to_string(),
+            timestamp: Utc::now(),
+            before,
+            after,
+        }
+    }
+
+    #[test]
+    fn test_diff_nested_fields() {
+        let changes = event(
+            Some(json!({ "state": "ACTIVE", "barcode": { "value": "1", "type": "QR_CODE" } })),
+            Some(json!({ "state": "EXPIRED", "barcode": { "value": "2", "type": "QR_CODE" } })),
+        )
+        .diff();
+
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange {
+                    path: "barcode.value".to_string(),
+                    before: Some(json!("1")),
+                    after: Some(json!("2")),
+                },
+                FieldChange {
+                    path: "state".to_string(),
+                    before: Some(json!("ACTIVE")),
+                    after: Some(json!("EXPIRED")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_without_before_reports_all_fields() {
+        let changes = event(None, Some(json!({ "id": "a", "state": "ACTIVE" }))).diff();
+
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|change| change.before.is_none()));
+    }
+}
@@ -1,3 +1,6 @@
+use crate::apple::AssetSlot;
+use crate::error::Result;
+use crate::image_host::{host_image, image_type, ImageHost};
 use crate::models::*;
 
 /// Builder for creating passes with a fluent API
@@ -39,6 +42,7 @@ impl PassBuilder {
                     logo: None,
                     background_color: None,
                     foreground_color: None,
+                    hero_image: None,
                 },
                 barcode: None,
                 fields: vec![],
@@ -46,10 +50,82 @@ impl PassBuilder {
                 state: PassState::Active,
                 valid_time_interval: None,
                 updated_at: None,
+                translations: vec![],
+                default_locale: None,
+                relevance: Default::default(),
+                smart_tap_redemption_value: None,
+                app_link: None,
+                images: vec![],
+                grouping: None,
+                notifications: Default::default(),
+                apple_assets: Default::default(),
             },
         }
     }
 
+    /// Create a pass builder with a freshly generated ID
+    ///
+    /// The ID is the issuer ID followed by a unique serial from
+    /// [`generate_serial_number`](crate::apple::generate_serial_number),
+    /// which also serves as the Apple pass's `serialNumber`.
+    ///
+    /// ```
+    /// use porter::builder::PassBuilder;
+    ///
+    /// let pass = PassBuilder::with_generated_id("issuer_id", "issuer_id.class_001")?.build();
+    /// assert!(pass.id.starts_with("issuer_id."));
+    /// # Ok::<(), porter::PorterError>(())
+    /// ```
+    pub fn with_generated_id(
+        issuer_id: impl AsRef<str>,
+        class_id: impl Into<String>,
+    ) -> Result<Self> {
+        let id = format!(
+            "{}.{}",
+            issuer_id.as_ref(),
+            crate::apple::generate_serial_number()?
+        );
+        Ok(Self::new(id, class_id))
+    }
+
+    /// Create a pass builder pre-filled from a class
+    ///
+    /// The pass type, colors, logo and default fields are taken from the
+    /// class, so only what varies per pass needs to be set. Fields set later
+    /// with the same key replace the class default.
+    ///
+    /// ```
+    /// use porter::builder::PassBuilder;
+    /// use porter::models::{PassClass, PassType, ReviewStatus};
+    ///
+    /// let class = PassClass {
+    ///     id: "issuer_id.concert".to_string(),
+    ///     pass_type: PassType::EventTicket,
+    ///     issuer_name: "Example Events".to_string(),
+    ///     review_status: ReviewStatus::Approved,
+    ///     logo: None,
+    ///     background_color: Some("#4285F4".to_string()),
+    ///     foreground_color: None,
+    ///     fields: vec![],
+    /// };
+    ///
+    /// let pass = PassBuilder::from_class("issuer_id.ticket_001", &class)
+    ///     .title("Concert Ticket")
+    ///     .field("seat", "Seat", "A23")
+    ///     .build();
+    ///
+    /// assert_eq!(pass.class_id, "issuer_id.concert");
+    /// assert_eq!(pass.header.background_color.as_deref(), Some("#4285F4"));
+    /// ```
+    pub fn from_class(id: impl Into<String>, class: &PassClass) -> Self {
+        let mut builder = Self::new(id, class.id.clone()).pass_type(class.pass_type.clone());
+        builder.pass.header.logo = class.logo.clone();
+        builder.pass.header.background_color = class.background_color.clone();
+        builder.pass.header.foreground_color = class.foreground_color.clone();
+        builder.pass.fields = class.fields.clone();
+        builder
+    }
+
     /// Set the pass type
     pub fn pass_type(mut self, pass_type: PassType) -> Self {
         self.pass.pass_type = pass_type;
@@ -77,6 +153,117 @@ impl PassBuilder {
         self
     }
 
+    /// Upload logo image bytes to `host` and use the hosted URL as the logo
+    ///
+    /// The image must be PNG or JPEG. It is stored under a name derived from
+    /// its content, so repeated builds reuse the same URL. A PNG is also kept
+    /// as the `logo.png` of the pass's [`apple_assets`](Pass::apple_assets);
+    /// Apple bundles only take PNGs, so a JPEG logo is hosted only.
+    pub async fn logo_bytes(
+        mut self,
+        host: &dyn ImageHost,
+        bytes: &[u8],
+        alt_text: Option<String>,
+    ) -> Result<Self> {
+        let uri = host_image(host, bytes).await?;
+        if image_type(bytes)?.0 == "png" {
+            self.pass.apple_assets = std::mem::take(&mut self.pass.apple_assets).image(
+                AssetSlot::Logo,
+                1,
+                bytes.to_vec(),
+            )?;
+        }
+        Ok(self.logo(uri, alt_text))
+    }
+
+    /// Set the hero image, a wide banner on the front of the pass
+    pub fn hero_image(mut self, source_uri: impl Into<String>, alt_text: Option<String>) -> Self {
+        self.pass.header.hero_image = Some(Image {
+            source_uri: source_uri.into(),
+            alt_text,
+        });
+        self
+    }
+
+    /// Add an image to the back of the pass
+    ///
+    /// Google Wallet shows each image as an image module; Apple Wallet has
+    /// no equivalent and ignores them.
+    pub fn image(mut self, source_uri: impl Into<String>, alt_text: Option<String>) -> Self {
+        self.pass.images.push(Image {
+            source_uri: source_uri.into(),
+            alt_text,
+        });
+        self
+    }
+
+    /// Upload image bytes to `host` and add the hosted URL to the back of the pass
+    pub async fn image_bytes(
+        self,
+        host: &dyn ImageHost,
+        bytes: &[u8],
+        alt_text: Option<String>,
+    ) -> Result<Self> {
+        let uri = host_image(host, bytes).await?;
+        Ok(self.image(uri, alt_text))
+    }
+
+    /// Show the pass together with the other passes of a group
+    ///
+    /// Use the same `id` for all tickets of an order or event, and
+    /// `sort_index` to order them on Google Wallet.
+    pub fn grouping(mut self, id: impl Into<String>, sort_index: Option<i32>) -> Self {
+        self.pass.grouping = Some(Grouping {
+            id: id.into(),
+            sort_index,
+        });
+        self
+    }
+
+    /// Have Google Wallet remind the holder shortly before the pass expires
+    ///
+    /// Needs an end date, set with [`valid_until`](Self::valid_until).
+    pub fn notify_before_expiry(mut self) -> Self {
+        self.pass.notifications.expiry = true;
+        self
+    }
+
+    /// Have Google Wallet remind the holder shortly before the pass becomes valid
+    pub fn notify_when_upcoming(mut self) -> Self {
+        self.pass.notifications.upcoming = true;
+        self
+    }
+
+    /// Set the language the pass strings are written in, such as "fr-FR"
+    pub fn default_locale(mut self, locale: impl Into<String>) -> Self {
+        self.pass.default_locale = Some(locale.into());
+        self
+    }
+
+    /// Apply a brand theme
+    ///
+    /// Colors, images and default locale set in the theme replace those on
+    /// the pass; unset theme values leave the pass unchanged.
+    pub fn theme(mut self, theme: &PassTheme) -> Self {
+        let header = &mut self.pass.header;
+        if theme.background_color.is_some() {
+            header.background_color = theme.background_color.clone();
+        }
+        if theme.foreground_color.is_some() {
+            header.foreground_color = theme.foreground_color.clone();
+        }
+        if theme.logo.is_some() {
+            header.logo = theme.logo.clone();
+        }
+        if theme.hero_image.is_some() {
+            header.hero_image = theme.hero_image.clone();
+        }
+        if theme.default_locale.is_some() {
+            self.pass.default_locale = theme.default_locale.clone();
+        }
+        self
+    }
+
     /// Set background color (hex format like "#FF0000")
     pub fn background_color(mut self, color: impl Into<String>) -> Self {
         self.pass.header.background_color = Some(color.into());
@@ -95,10 +282,68 @@ impl PassBuilder {
             format,
             value: value.into(),
             alternate_text: None,
+            rotating: None,
         });
         self
     }
 
+    /// Add a rotating (TOTP) barcode, with `value` as the static fallback
+    ///
+    /// ```
+    /// use porter::builder::PassBuilder;
+    /// use porter::models::{BarcodeFormat, RotatingBarcode};
+    ///
+    /// let pass = PassBuilder::new("issuer_id.pass_001", "issuer_id.class_001")
+    ///     .rotating_barcode(
+    ///         BarcodeFormat::QrCode,
+    ///         "TICKET123",
+    ///         RotatingBarcode::totp("3132333435363738393031323334353637383930", 5),
+    ///     )
+    ///     .build();
+    /// ```
+    pub fn rotating_barcode(
+        mut self,
+        format: BarcodeFormat,
+        value: impl Into<String>,
+        rotating: RotatingBarcode,
+    ) -> Self {
+        self.pass.barcode = Some(Barcode {
+            format,
+            value: value.into(),
+            alternate_text: None,
+            rotating: Some(rotating),
+        });
+        self
+    }
+
+    /// Set the value Smart Tap terminals receive when the pass is tapped
+    ///
+    /// Only Google Wallet supports Smart Tap; the class must also have
+    /// Smart Tap enabled for the terminal's redemption issuer.
+    pub fn smart_tap_redemption_value(mut self, value: impl Into<String>) -> Self {
+        self.pass.smart_tap_redemption_value = Some(value.into());
+        self
+    }
+
+    /// Link the pass to the issuer's app
+    ///
+    /// ```
+    /// use porter::builder::PassBuilder;
+    /// use porter::models::AppLink;
+    ///
+    /// let pass = PassBuilder::new("issuer_id.pass_001", "issuer_id.class_001")
+    ///     .app_link(AppLink {
+    ///         title: Some("Open in app".to_string()),
+    ///         android_package: Some("com.example.app".to_string()),
+    ///         ..AppLink::new("https://example.com/tickets/001")
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn app_link(mut self, link: AppLink) -> Self {
+        self.pass.app_link = Some(link);
+        self
+    }
+
     /// Add a barcode with alternate text
     pub fn barcode_with_text(
         mut self,
@@ -110,6 +355,7 @@ impl PassBuilder {
             format,
             value: value.into(),
             alternate_text: Some(alternate_text.into()),
+            rotating: None,
         });
         self
     }
@@ -121,15 +367,35 @@ impl PassBuilder {
         label: impl Into<String>,
         value: impl Into<String>,
     ) -> Self {
-        self.pass.fields.push(PassField {
+        self.push_field(PassField {
             key: key.into(),
             label: label.into(),
             value: value.into(),
             text_alignment: None,
+            link: None,
+            format: None,
+            change_message: None,
         });
         self
     }
 
+    /// Set the seat assignment
+    ///
+    /// Each part of the seat becomes a field, replacing any previous seat.
+    pub fn seat(mut self, seat: Seat) -> Self {
+        for (key, label, value) in [
+            (SEAT_SECTION_KEY, "Section", seat.section),
+            (SEAT_ROW_KEY, "Row", seat.row),
+            (SEAT_NUMBER_KEY, "Seat", seat.number),
+        ] {
+            match value {
+                Some(value) => self = self.field(key, label, value),
+                None => self.pass.fields.retain(|f| f.key != key),
+            }
+        }
+        self
+    }
+
     /// Add a field with specific text alignment
     pub fn field_with_alignment(
         mut self,
@@ -138,15 +404,97 @@ impl PassBuilder {
         value: impl Into<String>,
         alignment: TextAlignment,
     ) -> Self {
-        self.pass.fields.push(PassField {
+        self.push_field(PassField {
             key: key.into(),
             label: label.into(),
             value: value.into(),
             text_alignment: Some(alignment),
+            link: None,
+            format: None,
+            change_message: None,
         });
         self
     }
 
+    /// Add a field that opens `link` when tapped
+    ///
+    /// Links can be web URLs or `tel:`, `mailto:` and `geo:` URIs. They are
+    /// shown in the Google links module and as an Apple attributed value.
+    pub fn link_field(
+        mut self,
+        key: impl Into<String>,
+        label: impl Into<String>,
+        value: impl Into<String>,
+        link: impl Into<String>,
+    ) -> Self {
+        self.push_field(PassField {
+            key: key.into(),
+            label: label.into(),
+            value: value.into(),
+            text_alignment: None,
+            link: Some(link.into()),
+            format: None,
+            change_message: None,
+        });
+        self
+    }
+
+    /// Add a field whose value is a date or number, formatted in the holder's locale
+    ///
+    /// Dates are given in RFC 3339 and numbers as decimal text, such as
+    /// `"12.50"`. Apple Wallet formats them natively; Google Wallet shows the
+    /// value as given.
+    pub fn formatted_field(
+        mut self,
+        key: impl Into<String>,
+        label: impl Into<String>,
+        value: impl Into<String>,
+        format: FieldFormat,
+    ) -> Self {
+        self.push_field(PassField {
+            key: key.into(),
+            label: label.into(),
+            value: value.into(),
+            text_alignment: None,
+            link: None,
+            format: Some(format),
+            change_message: None,
+        });
+        self
+    }
+
+    /// Add a field showing a date and time
+    pub fn date_field(
+        self,
+        key: impl Into<String>,
+        label: impl Into<String>,
+        value: chrono::DateTime<chrono::Utc>,
+        date_style: DateTimeStyle,
+        time_style: DateTimeStyle,
+    ) -> Self {
+        self.formatted_field(
+            key,
+            label,
+            value.to_rfc3339(),
+            FieldFormat::DateTime {
+                date_style,
+                time_style,
+            },
+        )
+    }
+
+    /// Notify the holder when the field `key` changes
+    ///
+    /// `message` is shown on the lock screen after an update, with `%@`
+    /// replaced by the new value, such as `"Your gate is now %@"`. Set it
+    /// after adding the field; unknown keys are ignored.
+    pub fn change_message(mut self, key: &str, message: impl Into<String>) -> Self {
+        if let Some(field) = self.pass.fields.iter_mut().find(|f| f.key == key) {
+            field.change_message = Some(message.into());
+        }
+        self
+    }
+
     /// Link another pass or offer
     pub fn link_object(mut self, object_id: impl Into<String>) -> Self {
         self.pass.linked_objects.push(object_id.into());
@@ -182,8 +530,75 @@ impl PassBuilder {
         self
     }
 
+    /// Set when the pass is surfaced on the lock screen
+    pub fn relevant_date(mut self, date: chrono::DateTime<chrono::Utc>) -> Self {
+        self.pass.relevance.relevant_date = Some(date);
+        self
+    }
+
+    /// Add a place where the pass is relevant
+    pub fn relevant_location(
+        mut self,
+        latitude: f64,
+        longitude: f64,
+        relevant_text: Option<String>,
+    ) -> Self {
+        self.pass.relevance.locations.push(RelevantLocation {
+            latitude,
+            longitude,
+            altitude: None,
+            relevant_text,
+        });
+        self
+    }
+
+    /// Add a Bluetooth beacon near which the pass is relevant
+    pub fn relevant_beacon(mut self, beacon: RelevantBeacon) -> Self {
+        self.pass.relevance.beacons.push(beacon);
+        self
+    }
+
+    /// Set how close, in meters, the holder must be to a location
+    pub fn max_distance(mut self, meters: f64) -> Self {
+        self.pass.relevance.max_distance = Some(meters);
+        self
+    }
+
+    /// Add a field, replacing any existing field with the same key
+    fn push_field(&mut self, field: PassField) {
+        match self.pass.fields.iter_mut().find(|f| f.key == field.key) {
+            Some(existing) => *existing = field,
+            None => self.pass.fields.push(field),
+        }
+    }
+
+    /// Add a "Valid for N more days" field derived from the validity end time
+    ///
+    /// The field is computed when the pass is built and can be kept current
+    /// with [`Pass::refresh_expiry_countdown`].
+    pub fn expiry_countdown(mut self) -> Self {
+        if !self
+            .pass
+            .fields
+            .iter()
+            .any(|f| f.key == EXPIRY_COUNTDOWN_KEY)
+        {
+            self.pass.fields.push(PassField {
+                key: EXPIRY_COUNTDOWN_KEY.to_string(),
+                label: "Validity".to_string(),
+                value: String::new(),
+                text_alignment: None,
+                link: None,
+                format: None,
+                change_message: None,
+            });
+        }
+        self
+    }
+
     /// Build the pass
-    pub fn build(self) -> Pass {
+    pub fn build(mut self) -> Pass {
+        self.pass.refresh_expiry_countdown(chrono::Utc::now());
         self.pass
     }
 }
@@ -224,4 +639,101 @@ mod tests {
         assert_eq!(pass.fields.len(), 3);
         assert!(pass.barcode.is_some());
     }
+
+    #[test]
+    fn test_builder_generated_id() {
+        let first = PassBuilder::with_generated_id("issuer", "issuer.class")
+            .unwrap()
+            .build();
+        let second = PassBuilder::with_generated_id("issuer", "issuer.class")
+            .unwrap()
+            .build();
+        assert!(first.id.starts_with("issuer."));
+        assert_eq!(first.id.len(), "issuer.".len() + 32);
+        assert_ne!(first.id, second.id);
+    }
+
+    #[test]
+    fn test_builder_from_class() {
+        let class = PassClass {
+            id: "event.concert".to_string(),
+            pass_type: PassType::EventTicket,
+            issuer_name: "Example Events".to_string(),
+            review_status: ReviewStatus::Approved,
+            logo: Some(Image {
+                source_uri: "https://example.com/logo.png".to_string(),
+                alt_text: None,
+            }),
+            background_color: Some("#FF5733".to_string()),
+            foreground_color: Some("#FFFFFF".to_string()),
+            fields: vec![
+                PassField {
+                    key: "venue".to_string(),
+                    label: "Venue".to_string(),
+                    value: "Main Hall".to_string(),
+                    text_alignment: None,
+                    link: None,
+                    format: None,
+                    change_message: None,
+                },
+                PassField {
+                    key: "seat".to_string(),
+                    label: "Seat".to_string(),
+                    value: "General admission".to_string(),
+                    text_alignment: None,
+                    link: None,
+                    format: None,
+                    change_message: None,
+                },
+            ],
+        };
+
+        let pass = PassBuilder::from_class("event.ticket001", &class)
+            .field("seat", "Seat", "A23")
+            .build();
+
+        assert_eq!(pass.class_id, "event.concert");
+        assert_eq!(pass.pass_type, PassType::EventTicket);
+        assert!(pass.header.logo.is_some());
+        assert_eq!(pass.header.foreground_color, Some("#FFFFFF".to_string()));
+        assert_eq!(pass.fields.len(), 2);
+        assert_eq!(pass.fields[1].value, "A23");
+    }
+
+    #[tokio::test]
+    async fn test_logo_bytes_hosts_and_embeds() {
+        struct Cdn;
+
+        #[async_trait::async_trait]
+        impl ImageHost for Cdn {
+            async fn upload(&self, name: &str, _: &str, _: &[u8]) -> Result<String> {
+                Ok(format!("https://cdn.example.com/{}", name))
+            }
+        }
+
+        let png = b"\x89PNG\r\n\x1a\nlogo".to_vec();
+        let pass = PassBuilder::new("issuer.pass", "issuer.class")
+            .logo_bytes(&Cdn, &png, None)
+            .await
+            .unwrap()
+            .build();
+
+        let object = crate::google::GenericObject::from(&pass);
+        let uri = object.logo.unwrap().source_uri.uri;
+        assert!(uri.starts_with("https://cdn.example.com/") && uri.ends_with(".png"));
+
+        let bundle = crate::apple::PkPassBundle::new(&serde_json::json!({ "formatVersion": 1 }))
+            .unwrap()
+            .assets(&pass.apple_assets)
+            .unwrap();
+        assert_eq!(bundle.get("logo.png"), Some(&png[..]));
+
+        let jpeg = PassBuilder::new("issuer.pass", "issuer.class")
+            .logo_bytes(&Cdn, &[0xFF, 0xD8, 0xFF, 0xE0], None)
+            .await
+            .unwrap()
+            .build();
+        assert!(jpeg.header.logo.is_some());
+        assert!(jpeg.apple_assets.file_names().is_empty());
+    }
 }
@@ -0,0 +1,391 @@
+//! Credential expiry monitoring
+//!
+//! [`GoogleWalletClient::check_credentials`](crate::google::GoogleWalletClient::check_credentials)
+//! looks up the certificate Google publishes for the configured service
+//! account key and reports its age and expiration against
+//! [`CredentialThresholds`], so rotation can happen before requests start
+//! failing. [`AppleWalletConfig::check_credentials`](crate::apple::AppleWalletConfig::check_credentials)
+//! does the same for the Pass Type ID certificate and its WWDR intermediate.
+
+use crate::error::{PorterError, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use ring::signature::{KeyPair, RsaKeyPair};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// When to warn about a credential
+#[derive(Debug, Clone)]
+pub struct CredentialThresholds {
+    /// Warn when a key is older than this
+    pub max_key_age: Duration,
+    /// Warn when a key or certificate expires within this
+    pub expiry_warning: Duration,
+}
+
+impl Default for CredentialThresholds {
+    fn default() -> Self {
+        Self {
+            max_key_age: Duration::days(90),
+            expiry_warning: Duration::days(30),
+        }
+    }
+}
+
+/// State of the configured credentials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialReport {
+    pub service_account_email: String,
+    /// Id of the service account key matching the configured private key
+    pub key_id: Option<String>,
+    pub key_created: Option<DateTime<Utc>>,
+    pub key_expires: Option<DateTime<Utc>>,
+    /// Apple signing certificates, if added with [`with_apple`](Self::with_apple)
+    pub apple: Option<AppleCredentialReport>,
+    /// Warnings about every credential in the report
+    pub warnings: Vec<CredentialWarning>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl CredentialReport {
+    /// Whether no warnings were raised
+    pub fn is_healthy(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Include the state of the Apple signing certificates
+    pub fn with_apple(mut self, apple: AppleCredentialReport) -> Self {
+        self.warnings.extend(apple.warnings.iter().cloned());
+        self.apple = Some(apple);
+        self
+    }
+}
+
+/// State of the Apple pass signing certificates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppleCredentialReport {
+    pub pass_type_identifier: String,
+    /// When the Pass Type ID certificate expires
+    pub certificate_expires: DateTime<Utc>,
+    /// Whole days until the certificate expires, negative once it has
+    pub certificate_days_remaining: i64,
+    /// When the WWDR intermediate expires
+    pub wwdr_expires: DateTime<Utc>,
+    pub wwdr_days_remaining: i64,
+    /// Whether the certificate was issued and signed by the WWDR intermediate
+    pub chain_valid: bool,
+    pub warnings: Vec<CredentialWarning>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl AppleCredentialReport {
+    /// Whether no warnings were raised
+    pub fn is_healthy(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// A problem found with a credential
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CredentialWarning {
+    /// The private key does not belong to the service account, or was deleted
+    KeyNotFound,
+    /// The key is older than the configured maximum age
+    KeyAge { days: i64 },
+    /// The key expires within the warning window
+    KeyExpiresSoon { expires: DateTime<Utc> },
+    /// The key has expired
+    KeyExpired { expires: DateTime<Utc> },
+    /// The Pass Type ID certificate expires within the warning window
+    CertificateExpiresSoon { expires: DateTime<Utc> },
+    /// The Pass Type ID certificate has expired
+    CertificateExpired { expires: DateTime<Utc> },
+    /// The WWDR intermediate expires within the warning window
+    WwdrExpiresSoon { expires: DateTime<Utc> },
+    /// The WWDR intermediate has expired
+    WwdrExpired { expires: DateTime<Utc> },
+    /// The Pass Type ID certificate was not issued by the WWDR intermediate
+    WwdrChainInvalid,
+}
+
+impl fmt::Display for CredentialWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyNotFound => write!(f, "service account key is not published for the account"),
+            Self::KeyAge { days } => write!(f, "service account key is {} days old", days),
+            Self::KeyExpiresSoon { expires } => {
+                write!(f, "service account key expires at {}", expires)
+            }
+            Self::KeyExpired { expires } => {
+                write!(f, "service account key expired at {}", expires)
+            }
+            Self::CertificateExpiresSoon { expires } => {
+                write!(f, "pass certificate expires at {}", expires)
+            }
+            Self::CertificateExpired { expires } => {
+                write!(f, "pass certificate expired at {}", expires)
+            }
+            Self::WwdrExpiresSoon { expires } => {
+                write!(f, "WWDR intermediate expires at {}", expires)
+            }
+            Self::WwdrExpired { expires } => write!(f, "WWDR intermediate expired at {}", expires),
+            Self::WwdrChainInvalid => {
+                write!(
+                    f,
+                    "pass certificate was not issued by the WWDR intermediate"
+                )
+            }
+        }
+    }
+}
+
+/// Certificate published for a service account key
+pub(crate) struct KeyCertificate {
+    pub key_id: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+/// Find the published certificate for `private_key_pem`
+///
+/// `certificates` maps key ids to PEM certificates, as returned by Google's
+/// x509 metadata endpoint for a service account.
+pub(crate) fn find_certificate(
+    private_key_pem: &str,
+    certificates: &HashMap<String, String>,
+) -> Result<Option<KeyCertificate>> {
+    let key = rsa_key_pair(private_key_pem)?;
+    let public_key = key.public_key().as_ref();
+
+    for (key_id, pem) in certificates {
+        let der = pem_body(pem)?;
+        let (_, certificate) = x509_parser::parse_x509_certificate(&der).map_err(|e| {
+            PorterError::ValidationError(format!("Invalid certificate {}: {}", key_id, e))
+        })?;
+        if certificate.public_key().subject_public_key.data.as_ref() != public_key {
+            continue;
+        }
+
+        let validity = certificate.validity();
+        return Ok(Some(KeyCertificate {
+            key_id: key_id.clone(),
+            not_before: time(validity.not_before),
+            not_after: time(validity.not_after),
+        }));
+    }
+    Ok(None)
+}
+
+fn time(t: x509_parser::time::ASN1Time) -> DateTime<Utc> {
+    DateTime::from_timestamp(t.timestamp(), 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Report the expiry of a Pass Type ID certificate and its WWDR chain as of `now`
+pub(crate) fn check_apple_certificates(
+    pass_type_identifier: &str,
+    certificate_pem: &str,
+    wwdr_pem: &str,
+    thresholds: &CredentialThresholds,
+    now: DateTime<Utc>,
+) -> Result<AppleCredentialReport> {
+    let certificate_der = pem_body(certificate_pem)?;
+    let wwdr_der = pem_body(wwdr_pem)?;
+    let parse = |der, name| {
+        x509_parser::parse_x509_certificate(der)
+            .map(|(_, certificate)| certificate)
+            .map_err(|e| PorterError::ConfigError(format!("Invalid {} certificate: {}", name, e)))
+    };
+    let certificate = parse(&certificate_der, "pass")?;
+    let wwdr = parse(&wwdr_der, "WWDR")?;
+
+    let certificate_expires = time(certificate.validity().not_after);
+    let wwdr_expires = time(wwdr.validity().not_after);
+    let chain_valid = certificate.issuer() == wwdr.subject()
+        && certificate
+            .verify_signature(Some(wwdr.public_key()))
+            .is_ok();
+
+    let mut warnings = vec![];
+    if certificate_expires <= now {
+        warnings.push(CredentialWarning::CertificateExpired {
+            expires: certificate_expires,
+        });
+    } else if certificate_expires - now <= thresholds.expiry_warning {
+        warnings.push(CredentialWarning::CertificateExpiresSoon {
+            expires: certificate_expires,
+        });
+    }
+    if wwdr_expires <= now {
+        warnings.push(CredentialWarning::WwdrExpired {
+            expires: wwdr_expires,
+        });
+    } else if wwdr_expires - now <= thresholds.expiry_warning {
+        warnings.push(CredentialWarning::WwdrExpiresSoon {
+            expires: wwdr_expires,
+        });
+    }
+    if !chain_valid {
+        warnings.push(CredentialWarning::WwdrChainInvalid);
+    }
+
+    Ok(AppleCredentialReport {
+        pass_type_identifier: pass_type_identifier.to_string(),
+        certificate_expires,
+        certificate_days_remaining: (certificate_expires - now).num_days(),
+        wwdr_expires,
+        wwdr_days_remaining: (wwdr_expires - now).num_days(),
+        chain_valid,
+        warnings,
+        checked_at: now,
+    })
+}
+
+/// Warnings for a key certificate as of `now`
+pub(crate) fn evaluate(
+    certificate: Option<&KeyCertificate>,
+    thresholds: &CredentialThresholds,
+    now: DateTime<Utc>,
+) -> Vec<CredentialWarning> {
+    let Some(certificate) = certificate else {
+        return vec![CredentialWarning::KeyNotFound];
+    };
+
+    let mut warnings = vec![];
+    let age = now - certificate.not_before;
+    if age > thresholds.max_key_age {
+        warnings.push(CredentialWarning::KeyAge {
+            days: age.num_days(),
+        });
+    }
+    if certificate.not_after <= now {
+        warnings.push(CredentialWarning::KeyExpired {
+            expires: certificate.not_after,
+        });
+    } else if certificate.not_after - now <= thresholds.expiry_warning {
+        warnings.push(CredentialWarning::KeyExpiresSoon {
+            expires: certificate.not_after,
+        });
+    }
+    warnings
+}
+
+/// Parse a PKCS#8 or PKCS#1 PEM RSA private key
+pub(crate) fn rsa_key_pair(private_key_pem: &str) -> Result<RsaKeyPair> {
+    let der = pem_body(private_key_pem)?;
+    RsaKeyPair::from_pkcs8(&der)
+        .or_else(|_| RsaKeyPair::from_der(&der))
+        .map_err(|e| PorterError::ConfigError(format!("Invalid private key: {}", e)))
+}
+
+/// Decode the base64 body of a PEM document
+pub(crate) fn pem_body(pem: &str) -> Result<Vec<u8>> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    STANDARD
+        .decode(body)
+        .map_err(|e| PorterError::ConfigError(format!("Invalid PEM: {}", e)))
+}
+
+/// Encode `der` as a PEM document labelled `label`
+#[cfg(feature = "apple-signing")]
+pub(crate) fn pem_encode(label: &str, der: &[u8]) -> String {
+    let body = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_thresholds() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let thresholds = CredentialThresholds::default();
+
+        let fresh = KeyCertificate {
+            key_id: "a".to_string(),
+            not_before: now - Duration::days(10),
+            not_after: now + Duration::days(365),
+        };
+        assert!(evaluate(Some(&fresh), &thresholds, now).is_empty());
+
+        let old = KeyCertificate {
+            key_id: "b".to_string(),
+            not_before: now - Duration::days(120),
+            not_after: now + Duration::days(5),
+        };
+        assert_eq!(
+            evaluate(Some(&old), &thresholds, now),
+            vec![
+                CredentialWarning::KeyAge { days: 120 },
+                CredentialWarning::KeyExpiresSoon {
+                    expires: old.not_after
+                },
+            ]
+        );
+
+        assert_eq!(
+            evaluate(None, &thresholds, now),
+            vec![CredentialWarning::KeyNotFound]
+        );
+    }
+
+    #[test]
+    fn test_apple_certificates() {
+        let certificate = include_str!("apple/testdata/pass_cert.pem");
+        let wwdr = include_str!("apple/testdata/wwdr.pem");
+        let thresholds = CredentialThresholds::default();
+
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let report =
+            check_apple_certificates("pass.com.example", certificate, wwdr, &thresholds, now)
+                .unwrap();
+        assert!(report.is_healthy());
+        assert!(report.chain_valid);
+        assert_eq!(
+            report.certificate_days_remaining,
+            (report.certificate_expires - now).num_days()
+        );
+
+        let late = report.certificate_expires - Duration::days(10);
+        let report =
+            check_apple_certificates("pass.com.example", certificate, wwdr, &thresholds, late)
+                .unwrap();
+        assert_eq!(report.certificate_days_remaining, 10);
+        assert_eq!(
+            report.warnings,
+            vec![
+                CredentialWarning::CertificateExpiresSoon {
+                    expires: report.certificate_expires
+                },
+                CredentialWarning::WwdrExpiresSoon {
+                    expires: report.wwdr_expires
+                },
+            ]
+        );
+
+        // The pass certificate is not its own issuer
+        let report = check_apple_certificates(
+            "pass.com.example",
+            certificate,
+            certificate,
+            &thresholds,
+            now,
+        )
+        .unwrap();
+        assert!(!report.chain_valid);
+        assert!(report
+            .warnings
+            .contains(&CredentialWarning::WwdrChainInvalid));
+    }
+}
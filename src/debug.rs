@@ -0,0 +1,322 @@
+//! Debugging of save links
+//!
+//! [`explain_jwt`] takes a save URL or JWT and lists everything Google is
+//! likely to reject it for: malformed segments, wrong `aud` or `typ`, clock
+//! skew, expiry, empty payloads and, with [`explain_jwt_with_config`], a
+//! signature or issuer that does not match the configured credentials.
+
+use crate::credentials::rsa_key_pair;
+use crate::google::types::save_token;
+use crate::google::GoogleWalletConfig;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey};
+use ring::signature::KeyPair;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Tolerated difference between the signing host's clock and Google's
+const CLOCK_SKEW_SECONDS: i64 = 300;
+
+/// Outcome of checking a JWT signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureCheck {
+    /// No key was available to check against
+    NotChecked,
+    Valid,
+    Invalid,
+}
+
+/// What a save JWT contains and why Google might reject it
+#[derive(Debug, Clone)]
+pub struct JwtExplanation {
+    pub header: Option<Value>,
+    pub claims: Option<Value>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Ids of the embedded objects and classes, keyed by payload field such as `genericObjects`
+    pub objects: BTreeMap<String, Vec<String>>,
+    pub signature: SignatureCheck,
+    /// Likely reasons for the JWT to be rejected
+    pub problems: Vec<String>,
+}
+
+impl JwtExplanation {
+    /// Whether no problems were found
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for JwtExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let claim = |name: &str| {
+            self.claims
+                .as_ref()
+                .and_then(|claims| claims.get(name))
+                .map(|value| match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .unwrap_or_else(|| "(missing)".to_string())
+        };
+
+        if self.claims.is_some() {
+            writeln!(f, "Issuer:    {}", claim("iss"))?;
+            writeln!(f, "Audience:  {}", claim("aud"))?;
+            writeln!(f, "Type:      {}", claim("typ"))?;
+            if let Some(issued_at) = self.issued_at {
+                writeln!(f, "Issued:    {}", issued_at)?;
+            }
+            if let Some(expires_at) = self.expires_at {
+                writeln!(f, "Expires:   {}", expires_at)?;
+            }
+            for (kind, ids) in &self.objects {
+                writeln!(f, "{}: {}", kind, ids.join(", "))?;
+            }
+        }
+        let signature = match self.signature {
+            SignatureCheck::NotChecked => "not checked",
+            SignatureCheck::Valid => "valid",
+            SignatureCheck::Invalid => "INVALID",
+        };
+        writeln!(f, "Signature: {}", signature)?;
+
+        if self.problems.is_empty() {
+            writeln!(f, "No problems found")
+        } else {
+            writeln!(f, "Problems:")?;
+            for problem in &self.problems {
+                writeln!(f, "  - {}", problem)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Explain a save URL or JWT without checking its signature
+///
+/// # Example
+///
+/// ```
+/// let explanation = porter::debug::explain_jwt("https://pay.google.com/gp/v/save/not-a-jwt");
+/// assert!(!explanation.is_ok());
+/// println!("{}", explanation);
+/// ```
+pub fn explain_jwt(token: &str) -> JwtExplanation {
+    explain(token, None, Utc::now())
+}
+
+/// Explain a save URL or JWT, checking it against the configured credentials
+///
+/// The signature is verified with the public half of the configured private
+/// key, `iss` is compared with the service account, and every embedded id
+/// must start with the issuer id.
+pub fn explain_jwt_with_config(token: &str, config: &GoogleWalletConfig) -> JwtExplanation {
+    explain(token, Some(config), Utc::now())
+}
+
+fn explain(token: &str, config: Option<&GoogleWalletConfig>, now: DateTime<Utc>) -> JwtExplanation {
+    let mut explanation = JwtExplanation {
+        header: None,
+        claims: None,
+        issued_at: None,
+        expires_at: None,
+        objects: BTreeMap::new(),
+        signature: SignatureCheck::NotChecked,
+        problems: vec![],
+    };
+    let problems = &mut explanation.problems;
+
+    let token = save_token(token);
+    let segments: Vec<&str> = token.split('.').collect();
+    if segments.len() != 3 {
+        problems.push(format!(
+            "not a JWT: expected 3 dot-separated segments, found {}",
+            segments.len()
+        ));
+        return explanation;
+    }
+
+    explanation.header = decode_segment(segments[0]);
+    match &explanation.header {
+        None => problems.push("header is not base64url-encoded JSON".to_string()),
+        Some(header) => {
+            let alg = header.get("alg").and_then(Value::as_str);
+            if alg != Some("RS256") {
+                problems.push(format!(
+                    "header alg is {:?}; Google requires RS256",
+                    alg.unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    let Some(claims) = decode_segment(segments[1]) else {
+        problems.push("payload is not base64url-encoded JSON".to_string());
+        return explanation;
+    };
+
+    let text = |name: &str| claims.get(name).and_then(Value::as_str);
+    if text("aud") != Some("google") {
+        problems.push(format!(
+            "aud is {:?}; it must be \"google\"",
+            text("aud").unwrap_or_default()
+        ));
+    }
+    if text("typ") != Some("savetowallet") {
+        problems.push(format!(
+            "typ is {:?}; it must be \"savetowallet\"",
+            text("typ").unwrap_or_default()
+        ));
+    }
+    if text("iss").unwrap_or_default().is_empty() {
+        problems.push("iss is missing; it must be the service account email".to_string());
+    }
+
+    let time = |name: &str| {
+        claims
+            .get(name)
+            .and_then(Value::as_i64)
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+    };
+    explanation.issued_at = time("iat");
+    explanation.expires_at = time("exp");
+    match explanation.issued_at {
+        None => problems.push("iat is missing or not a timestamp".to_string()),
+        Some(iat) if (iat - now).num_seconds() > CLOCK_SKEW_SECONDS => problems.push(format!(
+            "iat is {} seconds in the future; check the signing host's clock",
+            (iat - now).num_seconds()
+        )),
+        Some(_) => {}
+    }
+    if let Some(exp) = explanation.expires_at {
+        if exp <= now {
+            problems.push(format!("expired at {}", exp));
+        }
+    }
+
+    match claims.get("payload").and_then(Value::as_object) {
+        None => problems.push("payload claim is missing".to_string()),
+        Some(payload) => {
+            for (kind, entries) in payload {
+                let Some(entries) = entries.as_array() else {
+                    problems.push(format!("payload.{} is not a list", kind));
+                    continue;
+                };
+                let mut ids = vec![];
+                for (index, entry) in entries.iter().enumerate() {
+                    match entry.get("id").and_then(Value::as_str) {
+                        Some(id) => ids.push(id.to_string()),
+                        None => problems.push(format!("payload.{}[{}] has no id", kind, index)),
+                    }
+                }
+                explanation.objects.insert(kind.clone(), ids);
+            }
+            if explanation.objects.values().all(Vec::is_empty) {
+                problems.push("payload contains no objects or classes".to_string());
+            }
+        }
+    }
+
+    if let Some(config) = config {
+        if let Some(iss) = text("iss") {
+            if iss != config.service_account_email {
+                problems.push(format!(
+                    "iss is {} but the configured service account is {}",
+                    iss, config.service_account_email
+                ));
+            }
+        }
+        let prefix = format!("{}.", config.issuer_id);
+        for id in explanation.objects.values().flatten() {
+            if !id.starts_with(&prefix) {
+                problems.push(format!(
+                    "{} does not start with the issuer id {}",
+                    id, config.issuer_id
+                ));
+            }
+        }
+
+        match verify_signature(&segments, &config.private_key) {
+            Ok(true) => explanation.signature = SignatureCheck::Valid,
+            Ok(false) => {
+                explanation.signature = SignatureCheck::Invalid;
+                problems.push("signature does not match the configured private key".to_string());
+            }
+            Err(e) => problems.push(format!("signature could not be checked: {}", e)),
+        }
+    }
+
+    explanation.claims = Some(claims);
+    explanation
+}
+
+fn decode_segment(segment: &str) -> Option<Value> {
+    let bytes = URL_SAFE_NO_PAD.decode(segment.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Verify an RS256 signature with the public half of `private_key_pem`
+fn verify_signature(segments: &[&str], private_key_pem: &str) -> crate::error::Result<bool> {
+    let key = rsa_key_pair(private_key_pem)?;
+    let public_key = DecodingKey::from_rsa_der(key.public_key().as_ref());
+    let message = format!("{}.{}", segments[0], segments[1]);
+    Ok(jsonwebtoken::crypto::verify(
+        segments[2],
+        message.as_bytes(),
+        &public_key,
+        Algorithm::RS256,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt(header: &Value, claims: &Value) -> String {
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(header.to_string()),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        )
+    }
+
+    #[test]
+    fn test_explain_jwt_problems() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let header = serde_json::json!({"alg": "RS256", "typ": "JWT"});
+
+        let good = jwt(
+            &header,
+            &serde_json::json!({
+                "iss": "wallet@example.iam.gserviceaccount.com",
+                "aud": "google",
+                "typ": "savetowallet",
+                "iat": 1_800_000_000,
+                "payload": {"genericObjects": [{"id": "issuer.pass"}]}
+            }),
+        );
+        let explanation = explain(&good, None, now);
+        assert!(explanation.is_ok(), "{}", explanation);
+        assert_eq!(explanation.objects["genericObjects"], vec!["issuer.pass"]);
+
+        let bad = jwt(
+            &header,
+            &serde_json::json!({
+                "iss": "wallet@example.iam.gserviceaccount.com",
+                "aud": "https://oauth2.googleapis.com/token",
+                "typ": "savetowallet",
+                "iat": 1_800_003_600,
+                "exp": 1_799_999_000,
+                "payload": {"genericObjects": []}
+            }),
+        );
+        let explanation = explain(&bad, None, now);
+        assert_eq!(explanation.problems.len(), 4, "{}", explanation);
+        assert!(explanation.to_string().contains("aud is"));
+
+        assert!(!explain("opaque", None, now).is_ok());
+    }
+}
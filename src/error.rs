@@ -2,6 +2,7 @@ use thiserror::Error;
 
 /// Error types for Porter
 #[derive(Error, Debug)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error), uniffi(flat_error))]
 pub enum PorterError {
     #[error("HTTP request failed: {0}")]
     HttpError(#[from] reqwest::Error),
@@ -15,12 +16,24 @@ pub enum PorterError {
     #[error("JWT error: {0}")]
     JwtError(#[from] jsonwebtoken::errors::Error),
 
+    #[error("Signature verification failed: {0}")]
+    SignatureError(String),
+
     #[error("Invalid pass data: {0}")]
     ValidationError(String),
 
     #[error("API error: {status} - {message}")]
     ApiError { status: u16, message: String },
 
+    #[error("Pass transfer incomplete, new object {new_object_id} was created: {message}")]
+    TransferIncomplete {
+        new_object_id: String,
+        message: String,
+    },
+
+    #[error("Concurrent modification: {0}")]
+    ConcurrentModification(String),
+
     #[error("Pass not found: {0}")]
     NotFound(String),
 
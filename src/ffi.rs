@@ -0,0 +1,227 @@
+//! UniFFI bindings
+//!
+//! Exposes the builder, conversions, save links and `.pkpass` signing to
+//! Kotlin and Swift.
+//! Models cross the boundary as JSON in the same shape as their serde
+//! representation, so foreign code can use any JSON library to read them.
+//!
+//! Generate bindings with `uniffi-bindgen generate --library` against the
+//! `cdylib` built with `--features uniffi`.
+
+use crate::apple::signing;
+use crate::builder::PassBuilder;
+use crate::error::Result;
+use crate::google::{GenericObject, GoogleWalletClient, GoogleWalletConfig};
+use crate::models::{BarcodeFormat, Pass, PassState, PassType};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Fluent pass builder for foreign callers
+///
+/// Each method returns the same builder so calls can be chained.
+#[derive(uniffi::Object)]
+pub struct FfiPassBuilder {
+    inner: Mutex<Option<PassBuilder>>,
+}
+
+impl FfiPassBuilder {
+    fn update(self: Arc<Self>, f: impl FnOnce(PassBuilder) -> PassBuilder) -> Arc<Self> {
+        {
+            let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            *inner = inner.take().map(f);
+        }
+        self
+    }
+}
+
+#[uniffi::export]
+impl FfiPassBuilder {
+    #[uniffi::constructor]
+    pub fn new(id: String, class_id: String) -> Arc<Self> {
+        Arc::new(Self {
+            inner: Mutex::new(Some(PassBuilder::new(id, class_id))),
+        })
+    }
+
+    pub fn pass_type(self: Arc<Self>, pass_type: PassType) -> Arc<Self> {
+        self.update(|b| b.pass_type(pass_type))
+    }
+
+    pub fn title(self: Arc<Self>, title: String) -> Arc<Self> {
+        self.update(|b| b.title(title))
+    }
+
+    pub fn subtitle(self: Arc<Self>, subtitle: String) -> Arc<Self> {
+        self.update(|b| b.subtitle(subtitle))
+    }
+
+    pub fn background_color(self: Arc<Self>, color: String) -> Arc<Self> {
+        self.update(|b| b.background_color(color))
+    }
+
+    pub fn foreground_color(self: Arc<Self>, color: String) -> Arc<Self> {
+        self.update(|b| b.foreground_color(color))
+    }
+
+    pub fn barcode(
+        self: Arc<Self>,
+        format: BarcodeFormat,
+        value: String,
+        alternate_text: Option<String>,
+    ) -> Arc<Self> {
+        self.update(|b| match alternate_text {
+            Some(text) => b.barcode_with_text(format, value, text),
+            None => b.barcode(format, value),
+        })
+    }
+
+    pub fn field(self: Arc<Self>, key: String, label: String, value: String) -> Arc<Self> {
+        self.update(|b| b.field(key, label, value))
+    }
+
+    pub fn link_object(self: Arc<Self>, object_id: String) -> Arc<Self> {
+        self.update(|b| b.link_object(object_id))
+    }
+
+    pub fn state(self: Arc<Self>, state: PassState) -> Arc<Self> {
+        self.update(|b| b.state(state))
+    }
+
+    /// Build the pass as JSON
+    ///
+    /// The builder can only be built once.
+    pub fn build_json(&self) -> Result<String> {
+        let builder = self
+            .inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .ok_or_else(|| {
+                crate::PorterError::ValidationError("Pass builder was already built".to_string())
+            })?;
+        Ok(serde_json::to_string(&builder.build())?)
+    }
+}
+
+/// Convert unified pass JSON to Google Wallet GenericObject JSON
+#[uniffi::export]
+pub fn pass_to_google_object_json(pass_json: String) -> Result<String> {
+    let pass: Pass = serde_json::from_str(&pass_json)?;
+    Ok(serde_json::to_string(&GenericObject::from(&pass))?)
+}
+
+/// Convert Google Wallet GenericObject JSON to unified pass JSON
+#[uniffi::export]
+pub fn google_object_to_pass_json(object_json: String) -> Result<String> {
+    let object: GenericObject = serde_json::from_str(&object_json)?;
+    Ok(serde_json::to_string(&Pass::from(&object))?)
+}
+
+/// Build and sign a `.pkpass` archive
+///
+/// `pass_json` is the Apple `pass.json`; `assets` maps file names such as
+/// `icon.png` or `en.lproj/pass.strings` to their contents. The certificate,
+/// its private key and the WWDR intermediate are PEM encoded.
+#[uniffi::export]
+pub fn generate_pkpass(
+    pass_json: String,
+    assets: HashMap<String, Vec<u8>>,
+    certificate_pem: String,
+    private_key_pem: String,
+    wwdr_pem: String,
+) -> Result<Vec<u8>> {
+    signing::generate_pkpass(
+        &pass_json,
+        assets,
+        &certificate_pem,
+        &private_key_pem,
+        &wwdr_pem,
+    )
+}
+
+/// Google Wallet client for foreign callers
+#[derive(uniffi::Object)]
+pub struct FfiGoogleWalletClient {
+    inner: tokio::sync::Mutex<GoogleWalletClient>,
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+impl FfiGoogleWalletClient {
+    #[uniffi::constructor]
+    pub fn new(issuer_id: String, service_account_email: String, private_key: String) -> Arc<Self> {
+        Arc::new(Self {
+            inner: tokio::sync::Mutex::new(GoogleWalletClient::new(GoogleWalletConfig {
+                issuer_id,
+                service_account_email,
+                private_key,
+            })),
+        })
+    }
+
+    /// Create a pass (unified JSON) as a generic object, returning the created object JSON
+    pub async fn create_pass(&self, pass_json: String) -> Result<String> {
+        let pass: Pass = serde_json::from_str(&pass_json)?;
+        let created = self
+            .inner
+            .lock()
+            .await
+            .create_generic_object(&GenericObject::from(&pass))
+            .await?;
+        Ok(serde_json::to_string(&created)?)
+    }
+
+    /// Generate a signed save URL for a pass (unified JSON)
+    pub async fn save_url(&self, pass_json: String) -> Result<String> {
+        let pass: Pass = serde_json::from_str(&pass_json)?;
+        self.inner
+            .lock()
+            .await
+            .generate_save_url(&GenericObject::from(&pass))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_round_trip() {
+        let json = FfiPassBuilder::new("issuer.pass".to_string(), "issuer.class".to_string())
+            .title("Concert".to_string())
+            .field("seat".to_string(), "Seat".to_string(), "A23".to_string())
+            .build_json()
+            .unwrap();
+
+        let object: GenericObject =
+            serde_json::from_str(&pass_to_google_object_json(json).unwrap()).unwrap();
+        assert_eq!(object.id, "issuer.pass");
+        assert_eq!(object.text_modules_data.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_generate_pkpass() {
+        let pass = crate::apple::ApplePass::new(
+            "pass.com.example.ticket",
+            "001",
+            "ABCDE12345",
+            "Example Events",
+            "Concert ticket",
+            crate::apple::PassStyle::EventTicket(Default::default()),
+        );
+        let pkpass = generate_pkpass(
+            serde_json::to_string(&pass).unwrap(),
+            HashMap::from([("icon.png".to_string(), vec![1, 2, 3])]),
+            include_str!("apple/testdata/pass_cert.pem").to_string(),
+            include_str!("apple/testdata/pass_key.pem").to_string(),
+            include_str!("apple/testdata/wwdr.pem").to_string(),
+        )
+        .unwrap();
+
+        let bundle = crate::apple::PkPassBundle::read(&pkpass).unwrap();
+        assert_eq!(bundle.get("icon.png"), Some(&[1, 2, 3][..]));
+        assert!(bundle
+            .verify_signature(include_str!("apple/testdata/wwdr.pem"))
+            .is_ok());
+    }
+}
@@ -0,0 +1,323 @@
+//! Batched Wallet API requests
+//!
+//! Google accepts up to [`MAX_BATCH_SIZE`] calls in one `multipart/mixed`
+//! request to its batch endpoint. [`BatchRequest`] collects inserts and
+//! updates across resource types, and
+//! [`GoogleWalletClient::send_batch`](super::GoogleWalletClient::send_batch)
+//! sends them and returns one [`BatchItemResult`] per call.
+
+use crate::error::{PorterError, Result};
+use crate::google::types::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Most calls Google accepts in one batch
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// Path of API calls inside a batch, relative to the API host
+const API_PATH: &str = "/walletobjects/v1";
+
+/// A class or object that can be inserted or updated in a batch
+pub trait BatchResource: Serialize {
+    /// Collection the resource lives in, such as `genericObject`
+    const COLLECTION: &'static str;
+
+    /// Id of the resource, used as the path of updates
+    fn resource_id(&self) -> &str;
+}
+
+macro_rules! batch_resource {
+    ($($resource:ty => $collection:literal),* $(,)?) => {
+        $(
+            impl BatchResource for $resource {
+                const COLLECTION: &'static str = $collection;
+
+                fn resource_id(&self) -> &str {
+                    &self.id
+                }
+            }
+        )*
+    };
+}
+
+batch_resource! {
+    GenericClass => "genericClass",
+    GenericObject => "genericObject",
+    EventTicketObject => "eventTicketObject",
+    LoyaltyClass => "loyaltyClass",
+    LoyaltyObject => "loyaltyObject",
+    OfferClass => "offerClass",
+    OfferObject => "offerObject",
+    GiftCardClass => "giftCardClass",
+    GiftCardObject => "giftCardObject",
+    TransitClass => "transitClass",
+    TransitObject => "transitObject",
+    FlightClass => "flightClass",
+    FlightObject => "flightObject",
+}
+
+/// Inserts and updates sent together in one batch request
+///
+/// # Example
+///
+/// ```
+/// use porter::google::{BatchRequest, GenericObject, LoyaltyClass};
+///
+/// let class = LoyaltyClass {
+///     id: "issuer_id.rewards".to_string(),
+///     ..Default::default()
+/// };
+/// let object = GenericObject {
+///     id: "issuer_id.pass_001".to_string(),
+///     class_id: "issuer_id.class_001".to_string(),
+///     ..Default::default()
+/// };
+///
+/// let batch = BatchRequest::new().update(&class)?.insert(&object)?;
+/// assert_eq!(batch.len(), 2);
+/// # Ok::<(), porter::PorterError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BatchRequest {
+    pub(crate) items: Vec<BatchItem>,
+}
+
+/// One call of a batch
+#[derive(Debug, Clone)]
+pub(crate) struct BatchItem {
+    pub(crate) method: reqwest::Method,
+    /// Path relative to the API base, such as `/genericObject/{id}`
+    pub(crate) path: String,
+    pub(crate) body: Value,
+}
+
+impl BatchRequest {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a new class or object
+    pub fn insert<R: BatchResource>(self, resource: &R) -> Result<Self> {
+        self.push(
+            reqwest::Method::POST,
+            format!("/{}", R::COLLECTION),
+            resource,
+        )
+    }
+
+    /// Replace an existing class or object
+    pub fn update<R: BatchResource>(self, resource: &R) -> Result<Self> {
+        let path = format!("/{}/{}", R::COLLECTION, resource.resource_id());
+        self.push(reqwest::Method::PUT, path, resource)
+    }
+
+    /// Number of calls in the batch
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the batch has no calls
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn push(
+        mut self,
+        method: reqwest::Method,
+        path: String,
+        resource: &impl Serialize,
+    ) -> Result<Self> {
+        if self.items.len() >= MAX_BATCH_SIZE {
+            return Err(PorterError::ValidationError(format!(
+                "A batch can hold at most {} calls",
+                MAX_BATCH_SIZE
+            )));
+        }
+        self.items.push(BatchItem {
+            method,
+            path,
+            body: serde_json::to_value(resource)?,
+        });
+        Ok(self)
+    }
+
+    /// The `multipart/mixed` body of the batch, with parts separated by `boundary`
+    pub(crate) fn multipart_body(&self, boundary: &str) -> String {
+        let mut body = String::new();
+        for (index, item) in self.items.iter().enumerate() {
+            body.push_str(&format!(
+                "--{boundary}\r\n\
+                 Content-Type: application/http\r\n\
+                 Content-ID: <item-{index}>\r\n\
+                 \r\n\
+                 {method} {API_PATH}{path} HTTP/1.1\r\n\
+                 Content-Type: application/json\r\n\
+                 \r\n\
+                 {json}\r\n",
+                method = item.method,
+                path = item.path,
+                json = item.body,
+            ));
+        }
+        body.push_str(&format!("--{boundary}--\r\n"));
+        body
+    }
+}
+
+/// Outcome of one call of a batch
+#[derive(Debug, Clone)]
+pub struct BatchItemResult {
+    /// HTTP status of the call
+    pub status: u16,
+    /// Returned resource, or the error document when the call failed
+    pub body: Value,
+}
+
+impl BatchItemResult {
+    /// Whether the call succeeded
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The returned resource, or an [`PorterError::ApiError`] if the call failed
+    pub fn into_result<T: DeserializeOwned>(self) -> Result<T> {
+        if !self.is_success() {
+            return Err(PorterError::ApiError {
+                status: self.status,
+                message: self.body.to_string(),
+            });
+        }
+        Ok(serde_json::from_value(self.body)?)
+    }
+}
+
+/// Boundary of a `multipart/mixed` content type
+pub(crate) fn boundary(content_type: &str) -> Option<&str> {
+    content_type.split(';').find_map(|param| {
+        let value = param.trim().strip_prefix("boundary=")?;
+        Some(value.trim_matches('"'))
+    })
+}
+
+/// Split a batch response into the results of its calls, in request order
+pub(crate) fn parse_response(body: &str, boundary: &str) -> Result<Vec<BatchItemResult>> {
+    let body = body.replace("\r\n", "\n");
+    let delimiter = format!("--{}", boundary);
+    let mut results = vec![];
+
+    for part in body.split(delimiter.as_str()).skip(1) {
+        if part.starts_with("--") {
+            break;
+        }
+        let (headers, response) = part
+            .trim_start_matches('\n')
+            .split_once("\n\n")
+            .unwrap_or_default();
+        let index = headers.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if !name.eq_ignore_ascii_case("content-id") {
+                return None;
+            }
+            value
+                .trim()
+                .trim_matches(['<', '>'])
+                .rsplit('-')
+                .next()?
+                .parse::<usize>()
+                .ok()
+        });
+
+        let (head, json) = response.split_once("\n\n").unwrap_or((response, ""));
+        let status = head
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| PorterError::ApiError {
+                status: 0,
+                message: format!("Malformed batch response part: {}", head),
+            })?;
+        let json = json.trim();
+        let body = if json.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(json).unwrap_or_else(|_| Value::String(json.to_string()))
+        };
+
+        results.push((index, BatchItemResult { status, body }));
+    }
+
+    if results.iter().all(|(index, _)| index.is_some()) {
+        results.sort_by_key(|(index, _)| *index);
+    }
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_body() {
+        let object = GenericObject {
+            id: "issuer.a".to_string(),
+            class_id: "issuer.class".to_string(),
+            ..Default::default()
+        };
+        let batch = BatchRequest::new()
+            .insert(&object)
+            .unwrap()
+            .update(&object)
+            .unwrap();
+
+        let body = batch.multipart_body("b");
+        assert!(body.starts_with("--b\r\nContent-Type: application/http\r\n"));
+        assert!(body.contains(
+            "Content-ID: <item-0>\r\n\r\nPOST /walletobjects/v1/genericObject HTTP/1.1\r\n"
+        ));
+        assert!(body.contains("PUT /walletobjects/v1/genericObject/issuer.a HTTP/1.1\r\n"));
+        assert!(body.contains(r#"{"classId":"issuer.class","id":"issuer.a"}"#));
+        assert!(body.ends_with("--b--\r\n"));
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            boundary("multipart/mixed; boundary=batch_abc"),
+            Some("batch_abc")
+        );
+
+        let body = "--batch_abc\r\n\
+            Content-Type: application/http\r\n\
+            Content-ID: <response-item-1>\r\n\
+            \r\n\
+            HTTP/1.1 409 Conflict\r\n\
+            Content-Type: application/json\r\n\
+            \r\n\
+            {\"error\": {\"code\": 409}}\r\n\
+            --batch_abc\r\n\
+            Content-Type: application/http\r\n\
+            Content-ID: <response-item-0>\r\n\
+            \r\n\
+            HTTP/1.1 200 OK\r\n\
+            Content-Type: application/json\r\n\
+            \r\n\
+            {\"id\": \"issuer.a\", \"classId\": \"issuer.class\"}\r\n\
+            --batch_abc--\r\n";
+
+        let results = parse_response(body, "batch_abc").unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_success());
+        assert!(!results[1].is_success());
+
+        let mut results = results.into_iter();
+        let object: GenericObject = results.next().unwrap().into_result().unwrap();
+        assert_eq!(object.id, "issuer.a");
+        assert!(matches!(
+            results.next().unwrap().into_result::<GenericObject>(),
+            Err(PorterError::ApiError { status: 409, .. })
+        ));
+    }
+}
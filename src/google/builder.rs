@@ -0,0 +1,309 @@
+use crate::error::Result;
+use crate::google::types::*;
+use crate::image_host::{host_image, ImageHost};
+use crate::models::Pass;
+
+/// Builder for Google Wallet generic classes
+///
+/// # Example
+///
+/// ```
+/// use porter::google::GenericClassBuilder;
+///
+/// let class = GenericClassBuilder::new("issuer_id.class_001")
+///     .issuer_name("Example Events")
+///     .callback_url("https://example.com/wallet/callback")
+///     .enable_smart_tap(vec!["issuer_id".to_string()])
+///     .security_animation("FOIL_SHIMMER")
+///     .multiple_devices_and_holders_allowed_status("ONE_USER_ALL_DEVICES")
+///     .build();
+///
+/// assert_eq!(class.enable_smart_tap, Some(true));
+/// ```
+pub struct GenericClassBuilder {
+    class: GenericClass,
+}
+
+impl GenericClassBuilder {
+    /// Create a new class builder
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            class: GenericClass {
+                id: id.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Set the issuer name
+    pub fn issuer_name(mut self, name: impl Into<String>) -> Self {
+        self.class.issuer_name = Some(name.into());
+        self
+    }
+
+    /// Set the review status (DRAFT or UNDER_REVIEW)
+    pub fn review_status(mut self, status: impl Into<ReviewStatus>) -> Self {
+        self.class.review_status = Some(status.into());
+        self
+    }
+
+    /// Set how the class is laid out
+    pub fn class_template_info(mut self, template: ClassTemplateInfo) -> Self {
+        self.class.class_template_info = Some(template);
+        self
+    }
+
+    /// Receive save and delete callbacks at `url`
+    pub fn callback_url(mut self, url: impl Into<String>) -> Self {
+        self.class.callback_options = Some(CallbackOptions {
+            url: Some(url.into()),
+        });
+        self
+    }
+
+    /// Enable Smart Tap, redeemable by the given redemption issuers
+    pub fn enable_smart_tap(mut self, redemption_issuers: Vec<String>) -> Self {
+        self.class.enable_smart_tap = Some(true);
+        self.class.redemption_issuers = Some(redemption_issuers);
+        self
+    }
+
+    /// Show a security animation (FOIL_SHIMMER)
+    pub fn security_animation(mut self, animation_type: impl Into<String>) -> Self {
+        self.class.security_animation = Some(SecurityAnimation {
+            animation_type: Some(animation_type.into()),
+        });
+        self
+    }
+
+    /// Set whether objects can be saved by multiple holders or devices
+    pub fn multiple_devices_and_holders_allowed_status(
+        mut self,
+        status: impl Into<String>,
+    ) -> Self {
+        self.class.multiple_devices_and_holders_allowed_status = Some(status.into());
+        self
+    }
+
+    /// Set whether the device must be unlocked to view the pass
+    pub fn view_unlock_requirement(mut self, requirement: impl Into<String>) -> Self {
+        self.class.view_unlock_requirement = Some(requirement.into());
+        self
+    }
+
+    /// Build the class
+    pub fn build(self) -> GenericClass {
+        self.class
+    }
+}
+
+/// Builder for Google Wallet offer classes
+///
+/// # Example
+///
+/// ```
+/// use porter::google::OfferClassBuilder;
+///
+/// let class = OfferClassBuilder::new("issuer_id.offer_001", "20% off any t-shirt", "Adam's Apparel")
+///     .redemption_channel("BOTH")
+///     .fine_print("Excludes sale items")
+///     .help_uri("https://example.com/help", Some("Offer help".to_string()))
+///     .build();
+/// ```
+pub struct OfferClassBuilder {
+    class: OfferClass,
+}
+
+impl OfferClassBuilder {
+    /// Create a new offer class builder
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        provider: impl Into<String>,
+    ) -> Self {
+        Self {
+            class: OfferClass {
+                id: id.into(),
+                title: Some(title.into()),
+                provider: Some(provider.into()),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Start from a unified offer pass
+    ///
+    /// The class id, title, subtitle (as provider), logo (as title image) and
+    /// background color are taken from the pass, with title and provider
+    /// translations when the pass has them.
+    pub fn from_pass(pass: &Pass) -> Self {
+        let mut builder = Self::new(
+            pass.class_id.clone(),
+            pass.header.title.clone(),
+            pass.header.subtitle.clone().unwrap_or_default(),
+        );
+        builder.class.localized_title = translated(pass, &pass.header.title);
+        builder.class.localized_provider = pass
+            .header
+            .subtitle
+            .as_ref()
+            .and_then(|subtitle| translated(pass, subtitle));
+        builder.class.title_image = pass.header.logo.as_ref().and_then(|logo| logo.into());
+        builder.class.hex_background_color = pass.header.background_color.clone();
+        builder
+    }
+
+    /// Set the issuer name
+    pub fn issuer_name(mut self, name: impl Into<String>) -> Self {
+        self.class.issuer_name = Some(name.into());
+        self
+    }
+
+    /// Set the review status (DRAFT or UNDER_REVIEW)
+    pub fn review_status(mut self, status: impl Into<ReviewStatus>) -> Self {
+        self.class.review_status = Some(status.into());
+        self
+    }
+
+    /// Set where the offer can be redeemed (INSTORE, ONLINE, BOTH or TEMPORARY_PRICE_REDUCTION)
+    pub fn redemption_channel(mut self, channel: impl Into<String>) -> Self {
+        self.class.redemption_channel = Some(channel.into());
+        self
+    }
+
+    /// Set a short title, such as "20% off"
+    pub fn short_title(mut self, short_title: impl Into<String>) -> Self {
+        self.class.short_title = Some(short_title.into());
+        self
+    }
+
+    /// Set the offer details
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.class.details = Some(details.into());
+        self
+    }
+
+    /// Set the fine print
+    pub fn fine_print(mut self, fine_print: impl Into<String>) -> Self {
+        self.class.fine_print = Some(fine_print.into());
+        self
+    }
+
+    /// Set translated fine print
+    pub fn localized_fine_print(mut self, fine_print: LocalizedString) -> Self {
+        self.class.localized_fine_print = Some(fine_print);
+        self
+    }
+
+    /// Set the help link
+    pub fn help_uri(mut self, uri: impl Into<String>, description: Option<String>) -> Self {
+        self.class.help_uri = Some(Uri {
+            uri: uri.into(),
+            description,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Set the title image
+    pub fn title_image(mut self, uri: impl Into<String>) -> Self {
+        self.class.title_image = Some(Image {
+            source_uri: ImageUri {
+                uri: uri.into(),
+                description: None,
+            },
+            content_description: None,
+        });
+        self
+    }
+
+    /// Upload title image bytes to `host` and use the hosted URL as the title image
+    pub async fn title_image_bytes(self, host: &dyn ImageHost, bytes: &[u8]) -> Result<Self> {
+        let uri = host_image(host, bytes).await?;
+        Ok(self.title_image(uri))
+    }
+
+    /// Set background color (hex format like "#FF0000")
+    pub fn background_color(mut self, color: impl Into<String>) -> Self {
+        self.class.hex_background_color = Some(color.into());
+        self
+    }
+
+    /// Enable Smart Tap, redeemable by the given redemption issuers
+    pub fn enable_smart_tap(mut self, redemption_issuers: Vec<String>) -> Self {
+        self.class.enable_smart_tap = Some(true);
+        self.class.redemption_issuers = Some(redemption_issuers);
+        self
+    }
+
+    /// Show a security animation (FOIL_SHIMMER)
+    pub fn security_animation(mut self, animation_type: impl Into<String>) -> Self {
+        self.class.security_animation = Some(SecurityAnimation {
+            animation_type: Some(animation_type.into()),
+        });
+        self
+    }
+
+    /// Build the class
+    pub fn build(self) -> OfferClass {
+        self.class
+    }
+}
+
+/// The translations recorded on a pass for `value`, if any
+fn translated(pass: &Pass, value: &str) -> Option<LocalizedString> {
+    pass.localized(value)
+        .map(|_| crate::google::convert::localized_string(pass, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offer_class_from_pass() {
+        let pass = crate::PassBuilder::new("issuer.offer", "issuer.offer_class")
+            .pass_type(crate::models::PassType::Offer)
+            .title("20% off")
+            .subtitle("Adam's Apparel")
+            .logo("https://example.com/logo.png", None)
+            .build();
+
+        let class = OfferClassBuilder::from_pass(&pass)
+            .redemption_channel("INSTORE")
+            .enable_smart_tap(vec!["issuer".to_string()])
+            .security_animation("FOIL_SHIMMER")
+            .build();
+
+        assert_eq!(class.id, "issuer.offer_class");
+        assert_eq!(class.title.as_deref(), Some("20% off"));
+        assert_eq!(class.provider.as_deref(), Some("Adam's Apparel"));
+        assert!(class.title_image.is_some());
+        assert!(class.localized_title.is_none());
+        assert_eq!(class.enable_smart_tap, Some(true));
+        assert_eq!(class.redemption_issuers, Some(vec!["issuer".to_string()]));
+        assert_eq!(
+            class.security_animation.unwrap().animation_type.as_deref(),
+            Some("FOIL_SHIMMER")
+        );
+    }
+
+    #[test]
+    fn test_class_builder_serialization() {
+        let class = GenericClassBuilder::new("issuer.class")
+            .enable_smart_tap(vec!["issuer".to_string()])
+            .view_unlock_requirement("UNLOCK_REQUIRED_TO_VIEW")
+            .build();
+
+        let json = serde_json::to_value(&class).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "id": "issuer.class",
+                "enableSmartTap": true,
+                "redemptionIssuers": ["issuer"],
+                "viewUnlockRequirement": "UNLOCK_REQUIRED_TO_VIEW"
+            })
+        );
+    }
+}
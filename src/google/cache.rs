@@ -0,0 +1,133 @@
+//! Time-limited cache of GET responses
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Identity a response was read as: service account and delegated subject
+pub(crate) type Principal = (String, Option<String>);
+
+/// Cached copies of one resource, with the time each was stored
+type Readers = HashMap<Principal, (Instant, Value)>;
+
+/// Raw resources keyed by API path, such as `/genericObject/issuer.pass`,
+/// and by the principal that read them
+///
+/// A resource read by one principal is never served to another, since
+/// credentials may see different data. A write drops the resource for
+/// every principal.
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Readers>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The resource at `path` cached for `principal`, if it has not expired
+    pub(crate) fn get(&self, principal: &Principal, path: &str) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let readers = entries.get_mut(path)?;
+        match readers.get(principal) {
+            Some((stored, value)) if stored.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                readers.remove(principal);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(&self, principal: &Principal, path: &str, value: Value) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(path.to_string())
+            .or_default()
+            .insert(principal.clone(), (Instant::now(), value));
+    }
+
+    /// Forget the resource a request to `path` acts on, for every principal
+    ///
+    /// `/genericObject/issuer.pass/addMessage?x=y` invalidates
+    /// `/genericObject/issuer.pass`.
+    pub(crate) fn invalidate(&self, path: &str) {
+        let path = path.split('?').next().unwrap_or_default();
+        let resource: Vec<_> = path
+            .trim_start_matches('/')
+            .splitn(3, '/')
+            .take(2)
+            .collect();
+        let key = format!("/{}", resource.join("/"));
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&key);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_expiry_and_invalidation() {
+        let reader = ("wallet@example.com".to_string(), None);
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        cache.insert(&reader, "/genericObject/issuer.a", Value::from(1));
+        cache.insert(&reader, "/genericObject/issuer.b", Value::from(2));
+        assert_eq!(
+            cache.get(&reader, "/genericObject/issuer.a"),
+            Some(Value::from(1))
+        );
+
+        cache.invalidate("/genericObject/issuer.a/addMessage");
+        assert_eq!(cache.get(&reader, "/genericObject/issuer.a"), None);
+        assert_eq!(
+            cache.get(&reader, "/genericObject/issuer.b"),
+            Some(Value::from(2))
+        );
+
+        let expired = ResponseCache::new(Duration::ZERO);
+        expired.insert(&reader, "/genericClass/issuer.c", Value::from(3));
+        assert_eq!(expired.get(&reader, "/genericClass/issuer.c"), None);
+    }
+
+    #[test]
+    fn test_entries_are_per_principal() {
+        let owner = ("wallet@example.com".to_string(), None);
+        let delegate = (
+            "wallet@example.com".to_string(),
+            Some("user@example.com".to_string()),
+        );
+        let other = ("agency@example.com".to_string(), None);
+
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        cache.insert(&owner, "/genericObject/issuer.a", Value::from(1));
+        assert_eq!(cache.get(&delegate, "/genericObject/issuer.a"), None);
+        assert_eq!(cache.get(&other, "/genericObject/issuer.a"), None);
+
+        cache.insert(&delegate, "/genericObject/issuer.a", Value::from(2));
+        assert_eq!(
+            cache.get(&owner, "/genericObject/issuer.a"),
+            Some(Value::from(1))
+        );
+
+        cache.invalidate("/genericObject/issuer.a");
+        assert_eq!(cache.get(&owner, "/genericObject/issuer.a"), None);
+        assert_eq!(cache.get(&delegate, "/genericObject/issuer.a"), None);
+    }
+}
@@ -0,0 +1,389 @@
+use crate::error::{PorterError, Result};
+use reqwest::Client;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const GOOGLE_CALLBACK_KEYS_URL: &str = "https://pay.google.com/gp/m/issuer/keys";
+const SENDER_ID: &str = "GooglePayPasses";
+const ROOT_SENDER_ID: &str = "Google";
+const PROTOCOL_VERSION: &str = "ECv2SigningOnly";
+const DEFAULT_KEY_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// DER prefix of an X.509 SubjectPublicKeyInfo holding an uncompressed P-256 point
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// Signed message posted by Google to a class callback URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedCallback {
+    pub signature: String,
+    pub intermediate_signing_key: IntermediateSigningKey,
+    pub protocol_version: String,
+    pub signed_message: String,
+}
+
+/// Intermediate key used to sign the callback, signed by a Google root key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntermediateSigningKey {
+    pub signed_key: String,
+    pub signatures: Vec<String>,
+}
+
+/// Contents of `IntermediateSigningKey::signed_key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedKey {
+    key_value: String,
+    key_expiration: String,
+}
+
+/// Google root signing key, as published at the issuer keys endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleSigningKey {
+    /// Base64 encoded X.509 SubjectPublicKeyInfo of a P-256 public key
+    pub key_value: String,
+    pub protocol_version: String,
+    /// Expiration in milliseconds since the epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_expiration: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleSigningKeys {
+    keys: Vec<GoogleSigningKey>,
+}
+
+/// Type of event reported by a callback
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CallbackEventType {
+    #[serde(rename = "save")]
+    Save,
+    #[serde(rename = "del")]
+    Delete,
+}
+
+/// Verified callback payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallbackMessage {
+    pub class_id: String,
+    pub object_id: String,
+    pub exp_time_millis: i64,
+    pub event_type: CallbackEventType,
+    /// Unique per callback, used to detect duplicate deliveries
+    pub nonce: String,
+}
+
+/// Verifier for callbacks Google sends when passes are saved or deleted
+///
+/// Google's root signing keys are fetched on first use and cached.
+///
+/// # Example
+///
+/// ```no_run
+/// use porter::google::CallbackVerifier;
+///
+/// # async fn example(body: &str) -> porter::error::Result<()> {
+/// let mut verifier = CallbackVerifier::new("1234567890123456789");
+/// let message = verifier.verify(body).await?;
+/// println!("{:?} {}", message.event_type, message.object_id);
+/// # Ok(())
+/// # }
+/// ```
+pub struct CallbackVerifier {
+    issuer_id: String,
+    client: Client,
+    keys_url: String,
+    keys: Option<Vec<GoogleSigningKey>>,
+    keys_expiry: Option<SystemTime>,
+}
+
+impl CallbackVerifier {
+    /// Create a verifier for callbacks addressed to the given issuer
+    pub fn new(issuer_id: impl Into<String>) -> Self {
+        Self {
+            issuer_id: issuer_id.into(),
+            client: Client::new(),
+            keys_url: GOOGLE_CALLBACK_KEYS_URL.to_string(),
+            keys: None,
+            keys_expiry: None,
+        }
+    }
+
+    /// Create a verifier using a fixed set of root keys instead of fetching them
+    pub fn with_keys(issuer_id: impl Into<String>, keys: Vec<GoogleSigningKey>) -> Self {
+        Self {
+            keys: Some(keys),
+            ..Self::new(issuer_id)
+        }
+    }
+
+    /// Verify a callback body and return its typed message
+    pub async fn verify(&mut self, body: &str) -> Result<CallbackMessage> {
+        let keys = self.root_keys().await?;
+        verify_callback(body, &self.issuer_id, &keys, now_millis()?)
+    }
+
+    /// Get the root keys, refreshing the cache if necessary
+    async fn root_keys(&mut self) -> Result<Vec<GoogleSigningKey>> {
+        if let Some(keys) = &self.keys {
+            match self.keys_expiry {
+                Some(expiry) if SystemTime::now() >= expiry => {}
+                _ => return Ok(keys.clone()),
+            }
+        }
+
+        let response = self.client.get(&self.keys_url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PorterError::ApiError {
+                status: status.as_u16(),
+                message: response.text().await?,
+            });
+        }
+
+        let ttl = response
+            .headers()
+            .get(reqwest::header::CACHE_CONTROL)
+            .and_then(|value| value.to_str().ok())
+            .and_then(max_age)
+            .unwrap_or(DEFAULT_KEY_CACHE_TTL);
+
+        let keys: GoogleSigningKeys = response.json().await?;
+        self.keys = Some(keys.keys.clone());
+        self.keys_expiry = Some(SystemTime::now() + ttl);
+
+        Ok(keys.keys)
+    }
+}
+
+/// Verify a callback body against the given root keys
+fn verify_callback(
+    body: &str,
+    issuer_id: &str,
+    root_keys: &[GoogleSigningKey],
+    now_millis: i64,
+) -> Result<CallbackMessage> {
+    let callback: SignedCallback = serde_json::from_str(body)?;
+
+    if callback.protocol_version != PROTOCOL_VERSION {
+        return Err(PorterError::SignatureError(format!(
+            "Unsupported protocol version: {}",
+            callback.protocol_version
+        )));
+    }
+
+    // The intermediate key must be signed by one of the current root keys
+    let signed_key_bytes = length_value(&[
+        ROOT_SENDER_ID,
+        PROTOCOL_VERSION,
+        &callback.intermediate_signing_key.signed_key,
+    ]);
+    let root_verified = root_keys
+        .iter()
+        .filter(|key| key.protocol_version == PROTOCOL_VERSION)
+        .filter(|key| !is_expired(key.key_expiration.as_deref(), now_millis))
+        .any(|key| {
+            callback
+                .intermediate_signing_key
+                .signatures
+                .iter()
+                .any(|signature| verify_signature(&key.key_value, signature, &signed_key_bytes))
+        });
+    if !root_verified {
+        return Err(PorterError::SignatureError(
+            "Intermediate signing key is not signed by a Google root key".to_string(),
+        ));
+    }
+
+    let signed_key: SignedKey =
+        serde_json::from_str(&callback.intermediate_signing_key.signed_key)?;
+    if is_expired(Some(&signed_key.key_expiration), now_millis) {
+        return Err(PorterError::SignatureError(
+            "Intermediate signing key has expired".to_string(),
+        ));
+    }
+
+    // The message must be signed by the intermediate key and addressed to this issuer
+    let message_bytes = length_value(&[
+        SENDER_ID,
+        issuer_id,
+        PROTOCOL_VERSION,
+        &callback.signed_message,
+    ]);
+    if !verify_signature(&signed_key.key_value, &callback.signature, &message_bytes) {
+        return Err(PorterError::SignatureError(
+            "Callback signature does not match the intermediate signing key".to_string(),
+        ));
+    }
+
+    let message: CallbackMessage = serde_json::from_str(&callback.signed_message)?;
+    if message.exp_time_millis <= now_millis {
+        return Err(PorterError::SignatureError(
+            "Callback message has expired".to_string(),
+        ));
+    }
+
+    Ok(message)
+}
+
+/// Encode each part as a 4 byte little-endian length followed by its bytes
+fn length_value(parts: &[&str]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for part in parts {
+        bytes.extend_from_slice(&(part.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(part.as_bytes());
+    }
+    bytes
+}
+
+/// Verify a base64 DER ECDSA signature with a base64 SubjectPublicKeyInfo key
+fn verify_signature(key_value: &str, signature: &str, message: &[u8]) -> bool {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let (Ok(key), Ok(signature)) = (STANDARD.decode(key_value), STANDARD.decode(signature)) else {
+        return false;
+    };
+    let Some(point) = key.strip_prefix(P256_SPKI_PREFIX.as_slice()) else {
+        return false;
+    };
+
+    UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, point)
+        .verify(message, &signature)
+        .is_ok()
+}
+
+fn is_expired(expiration_millis: Option<&str>, now_millis: i64) -> bool {
+    match expiration_millis {
+        Some(expiration) => expiration
+            .parse::<i64>()
+            .map(|expiration| expiration <= now_millis)
+            .unwrap_or(true),
+        None => false,
+    }
+}
+
+fn max_age(cache_control: &str) -> Option<Duration> {
+    cache_control
+        .split(',')
+        .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+        .find_map(|seconds| seconds.parse().ok())
+        .map(Duration::from_secs)
+}
+
+fn now_millis() -> Result<i64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| PorterError::SignatureError(format!("Time error: {}", e)))?
+        .as_millis() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+    const NOW: i64 = 1_700_000_000_000;
+
+    fn key_pair() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap()
+    }
+
+    fn public_key(pair: &EcdsaKeyPair) -> String {
+        let mut spki = P256_SPKI_PREFIX.to_vec();
+        spki.extend_from_slice(pair.public_key().as_ref());
+        STANDARD.encode(spki)
+    }
+
+    fn sign(pair: &EcdsaKeyPair, message: &[u8]) -> String {
+        STANDARD.encode(pair.sign(&SystemRandom::new(), message).unwrap())
+    }
+
+    fn signed_callback(root: &EcdsaKeyPair, issuer_id: &str, exp_time_millis: i64) -> String {
+        let intermediate = key_pair();
+        let signed_key = serde_json::json!({
+            "keyValue": public_key(&intermediate),
+            "keyExpiration": (NOW + 60_000).to_string(),
+        })
+        .to_string();
+        let signed_message = serde_json::json!({
+            "classId": "issuer.class",
+            "objectId": "issuer.object",
+            "expTimeMillis": exp_time_millis,
+            "eventType": "save",
+            "nonce": "nonce-1",
+        })
+        .to_string();
+
+        let key_signature = sign(
+            root,
+            &length_value(&[ROOT_SENDER_ID, PROTOCOL_VERSION, &signed_key]),
+        );
+        let signature = sign(
+            &intermediate,
+            &length_value(&[SENDER_ID, issuer_id, PROTOCOL_VERSION, &signed_message]),
+        );
+
+        serde_json::json!({
+            "signature": signature,
+            "intermediateSigningKey": {
+                "signedKey": signed_key,
+                "signatures": [key_signature],
+            },
+            "protocolVersion": PROTOCOL_VERSION,
+            "signedMessage": signed_message,
+        })
+        .to_string()
+    }
+
+    fn root_keys(root: &EcdsaKeyPair) -> Vec<GoogleSigningKey> {
+        vec![GoogleSigningKey {
+            key_value: public_key(root),
+            protocol_version: PROTOCOL_VERSION.to_string(),
+            key_expiration: None,
+        }]
+    }
+
+    #[test]
+    fn test_verify_callback() {
+        let root = key_pair();
+        let body = signed_callback(&root, "1234", NOW + 1000);
+
+        let message = verify_callback(&body, "1234", &root_keys(&root), NOW).unwrap();
+        assert_eq!(message.object_id, "issuer.object");
+        assert_eq!(message.event_type, CallbackEventType::Save);
+    }
+
+    #[test]
+    fn test_verify_callback_rejects_other_issuer() {
+        let root = key_pair();
+        let body = signed_callback(&root, "1234", NOW + 1000);
+
+        assert!(verify_callback(&body, "5678", &root_keys(&root), NOW).is_err());
+    }
+
+    #[test]
+    fn test_verify_callback_rejects_unknown_root() {
+        let body = signed_callback(&key_pair(), "1234", NOW + 1000);
+
+        assert!(verify_callback(&body, "1234", &root_keys(&key_pair()), NOW).is_err());
+    }
+
+    #[test]
+    fn test_verify_callback_rejects_expired_message() {
+        let root = key_pair();
+        let body = signed_callback(&root, "1234", NOW - 1000);
+
+        assert!(verify_callback(&body, "1234", &root_keys(&root), NOW).is_err());
+    }
+}
@@ -1,14 +1,43 @@
+use crate::apple::{ApplePush, PushReport};
+use crate::audit::{AuditAction, AuditEvent, AuditSink};
+use crate::credentials::{self, CredentialReport, CredentialThresholds};
 use crate::error::{PorterError, Result};
+use crate::google::batch::{self, BatchItemResult, BatchRequest};
+use crate::google::cache::{Principal, ResponseCache};
+use crate::google::convert::DEFAULT_LANGUAGE;
+use crate::google::field_mask::FieldMask;
+use crate::google::jwt::{JwtSigner, SignBuffers};
+use crate::google::smart_tap_keys::SmartTapKeyRegistry;
 use crate::google::types::*;
+use crate::models::Pass;
+use crate::purge::{ApplePurge, ExpireReport, GooglePurgeReport, PurgeFailure, PurgeReport};
+use crate::shortener::LinkShortener;
+use crate::usage::UsageTracker;
 use async_trait::async_trait;
-use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const GOOGLE_WALLET_API_BASE: &str = "https://walletobjects.googleapis.com/walletobjects/v1";
+const GOOGLE_WALLET_BATCH_URL: &str = "https://walletobjects.googleapis.com/batch";
 const GOOGLE_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
 const SCOPE: &str = "https://www.googleapis.com/auth/wallet_object.issuer";
+const SERVICE_ACCOUNT_CERTS_URL: &str =
+    "https://www.googleapis.com/service_accounts/v1/metadata/x509";
+/// Save links carrying the JWT itself
+const SAVE_URL_PREFIX: &str = "https://pay.google.com/gp/v/save/";
+/// notifyPreference value that makes field updates notify holders
+const NOTIFY_ON_UPDATE: &str = "NOTIFY_ON_UPDATE";
+/// Local date-time format used by flight and transit times
+const LOCAL_DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+/// Times a points update is written before giving up on a contended object
+const POINTS_ATTEMPTS: usize = 3;
 
 /// Configuration for Google Wallet authentication
 #[derive(Clone)]
@@ -18,6 +47,17 @@ pub struct GoogleWalletConfig {
     pub private_key: String,
 }
 
+/// A service account key, identified by its key id
+///
+/// Set on a client with
+/// [`GoogleWalletClient::with_signing_key`](GoogleWalletClient::with_signing_key)
+/// to sign with a specific key and send its id as the JWT `kid` header.
+#[derive(Clone)]
+pub struct SigningKey {
+    pub key_id: String,
+    pub private_key: String,
+}
+
 /// JWT Claims for Google OAuth2
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
@@ -26,6 +66,8 @@ struct Claims {
     aud: String,
     exp: u64,
     iat: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub: Option<String>,
 }
 
 /// Token response from Google
@@ -37,12 +79,100 @@ struct TokenResponse {
     token_type: String,
 }
 
+/// Result of transferring a pass to a new holder
+#[derive(Debug, Clone)]
+pub struct PassTransfer {
+    /// The original object, now expired
+    pub original_id: String,
+    /// The newly minted object for the recipient
+    pub object: GenericObject,
+    /// Save URL to send to the recipient
+    pub save_url: String,
+}
+
+/// Result of an update that notifies the holders of a pass
+#[derive(Debug, Clone)]
+pub struct HolderUpdate<T> {
+    /// The updated Google Wallet object
+    pub object: T,
+    /// Notifications sent to the devices of the Apple passes, if any were given
+    pub apple: Option<PushReport>,
+}
+
+impl<T> HolderUpdate<T> {
+    /// Notify the devices of `apple` about the update of `object`
+    async fn notify(object: T, apple: Option<ApplePush<'_>>) -> Result<Self> {
+        let apple = match apple {
+            Some(apple) => Some(apple.run().await?),
+            None => None,
+        };
+        Ok(Self { object, apple })
+    }
+}
+
+/// Outcome of [`GoogleWalletClient::sync_class`]
+#[derive(Debug, Clone)]
+pub enum ClassSync {
+    /// The class did not exist and was created
+    Created(GenericClass),
+    /// The class had drifted and was patched
+    Updated {
+        class: GenericClass,
+        /// Top-level fields that differed
+        changed: Vec<String>,
+    },
+    /// The remote class already matched
+    Unchanged,
+}
+
+/// A mutation that was skipped in dry-run mode
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunRequest {
+    pub method: String,
+    pub path: String,
+    pub body: Option<Value>,
+}
+
+/// Service account email and delegated subject a token was issued for
+type TokenKey = (String, Option<String>);
+
+/// Private key PEM and key id a signer was parsed from
+type SignerKey = (String, Option<String>);
+
+/// Access token and the time it expires
+struct CachedToken {
+    access_token: String,
+    expiry: SystemTime,
+}
+
+/// Token of one principal, locked while it is refreshed
+type TokenSlot = Arc<tokio::sync::Mutex<Option<CachedToken>>>;
+
 /// Google Wallet API client
+///
+/// Clones share their access token, and only one of them exchanges a new
+/// token when it expires; the others wait for the result. Clone the client
+/// into concurrent tasks rather than creating a client per task.
+#[derive(Clone)]
 pub struct GoogleWalletClient {
     config: GoogleWalletConfig,
     client: Client,
-    access_token: Option<String>,
-    token_expiry: Option<SystemTime>,
+    api_base: String,
+    subject: Option<String>,
+    issuers: Arc<HashMap<String, GoogleWalletConfig>>,
+    tokens: Arc<std::sync::Mutex<HashMap<TokenKey, TokenSlot>>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    audit_actor: Option<String>,
+    link_shortener: Option<Arc<dyn LinkShortener>>,
+    usage_tracker: Option<Arc<UsageTracker>>,
+    dry_run: Option<Vec<DryRunRequest>>,
+    cache: Option<Arc<ResponseCache>>,
+    save_link_ttl: Option<Duration>,
+    save_link_origins: Vec<String>,
+    signing_keys: Vec<SigningKey>,
+    active_key: Option<String>,
+    strict: bool,
+    signers: Arc<std::sync::Mutex<HashMap<SignerKey, Arc<JwtSigner>>>>,
 }
 
 impl GoogleWalletClient {
@@ -51,43 +181,420 @@ impl GoogleWalletClient {
         Self {
             config,
             client: Client::new(),
-            access_token: None,
-            token_expiry: None,
+            api_base: GOOGLE_WALLET_API_BASE.to_string(),
+            subject: None,
+            issuers: Arc::new(HashMap::new()),
+            tokens: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            audit_sink: None,
+            audit_actor: None,
+            link_shortener: None,
+            usage_tracker: None,
+            dry_run: None,
+            cache: None,
+            save_link_ttl: None,
+            save_link_origins: vec![],
+            signing_keys: vec![],
+            active_key: None,
+            strict: false,
+            signers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Emit an audit event to `sink` for every successful mutation
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Attribute subsequent audit events to `actor` instead of the service account
+    pub fn set_audit_actor(&mut self, actor: impl Into<String>) {
+        self.audit_actor = Some(actor.into());
+    }
+
+    /// Shorten every generated save URL with `shortener`
+    pub fn with_link_shortener(mut self, shortener: Arc<dyn LinkShortener>) -> Self {
+        self.link_shortener = Some(shortener);
+        self
+    }
+
+    /// Expire generated save links `ttl` after they are created
+    ///
+    /// Sets the `exp` claim of every save JWT, so links leaked from emails
+    /// stop working. Use
+    /// [`generate_save_url_expiring`](Self::generate_save_url_expiring) to
+    /// choose a different lifetime for a single link.
+    pub fn with_save_link_ttl(mut self, ttl: Duration) -> Self {
+        self.save_link_ttl = Some(ttl);
+        self
+    }
+
+    /// Declare the sites allowed to show Add to Google Wallet buttons
+    ///
+    /// Sets the `origins` claim of every save JWT. Google rejects web button
+    /// integrations whose page origin is not listed, so each entry is a
+    /// scheme and host such as `https://example.com`.
+    pub fn with_save_link_origins<S: Into<String>>(
+        mut self,
+        origins: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.save_link_origins = origins.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Hold another key for the service account and sign with it
+    ///
+    /// Tokens and save JWTs for the configured service account are signed
+    /// with the most recently added key and carry its id in the `kid`
+    /// header. Earlier keys stay available to
+    /// [`use_signing_key`](Self::use_signing_key), so a rotation can be
+    /// rolled back while the old key is still valid:
+    ///
+    /// ```no_run
+    /// # use porter::google::{GoogleWalletClient, GoogleWalletConfig, SigningKey};
+    /// # fn example(config: GoogleWalletConfig, old_pem: String, new_pem: String) -> porter::error::Result<()> {
+    /// let mut client = GoogleWalletClient::new(config)
+    ///     .with_signing_key(SigningKey { key_id: "old-key-id".to_string(), private_key: old_pem })
+    ///     .with_signing_key(SigningKey { key_id: "new-key-id".to_string(), private_key: new_pem });
+    /// // Roll back if the new key is rejected
+    /// client.use_signing_key("old-key-id")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_signing_key(mut self, key: SigningKey) -> Self {
+        self.signing_keys.retain(|k| k.key_id != key.key_id);
+        self.active_key = Some(key.key_id.clone());
+        self.signing_keys.push(key);
+        self
+    }
+
+    /// Sign with a previously added key
+    pub fn use_signing_key(&mut self, key_id: &str) -> Result<()> {
+        if !self.signing_keys.iter().any(|k| k.key_id == key_id) {
+            return Err(PorterError::ConfigError(format!(
+                "No signing key with id {}",
+                key_id
+            )));
+        }
+        self.active_key = Some(key_id.to_string());
+        Ok(())
+    }
+
+    /// Ids of the held signing keys, in the order they were added
+    pub fn signing_key_ids(&self) -> Vec<&str> {
+        self.signing_keys
+            .iter()
+            .map(|k| k.key_id.as_str())
+            .collect()
+    }
+
+    /// Reject responses containing enum values Porter does not know
+    ///
+    /// By default, unknown states, barcode types and similar values are
+    /// passed through, and conversions to the unified model fall back to
+    /// defaults. In strict mode any such value in a response fails the
+    /// request with a [`PorterError::ValidationError`] naming the fields,
+    /// which surfaces API schema changes early, for example in staging.
+    pub fn with_strict_responses(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Count every Wallet API request in `tracker`
+    pub fn with_usage_tracker(mut self, tracker: Arc<UsageTracker>) -> Self {
+        self.usage_tracker = Some(tracker);
+        self
+    }
+
+    /// Report the age and expiration of the service account key
+    ///
+    /// Looks up the certificates Google publishes for the service account
+    /// and finds the one matching the configured private key. A key that is
+    /// not published (deleted, or belonging to another account) is reported
+    /// as a warning rather than an error. Add the Apple signing certificates
+    /// with [`CredentialReport::with_apple`].
+    pub async fn check_credentials(
+        &self,
+        thresholds: &CredentialThresholds,
+    ) -> Result<CredentialReport> {
+        let certificates: std::collections::HashMap<String, String> = self
+            .client
+            .get(format!(
+                "{}/{}",
+                SERVICE_ACCOUNT_CERTS_URL, self.config.service_account_email
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let certificate = credentials::find_certificate(&self.config.private_key, &certificates)?;
+        let now = chrono::Utc::now();
+        Ok(CredentialReport {
+            service_account_email: self.config.service_account_email.clone(),
+            key_id: certificate.as_ref().map(|c| c.key_id.clone()),
+            key_created: certificate.as_ref().map(|c| c.not_before),
+            key_expires: certificate.as_ref().map(|c| c.not_after),
+            apple: None,
+            warnings: credentials::evaluate(certificate.as_ref(), thresholds, now),
+            checked_at: now,
+        })
+    }
+
+    /// Check that the configured credentials can reach the issuer account
+    ///
+    /// Exchanges the service account key for an access token and reads the
+    /// issuer, so a misconfigured deployment fails at startup rather than on
+    /// the first user-facing request. Nothing is modified.
+    pub async fn verify_credentials(&mut self) -> Result<()> {
+        let issuer_id = self.config.issuer_id.clone();
+        match self.get_issuer(&issuer_id).await {
+            Ok(_) => Ok(()),
+            Err(PorterError::ApiError {
+                status: 401,
+                message,
+            })
+            | Err(PorterError::ApiError {
+                status: 403,
+                message,
+            }) => Err(PorterError::AuthError(format!(
+                "{} cannot access issuer {}: {}",
+                self.config.service_account_email, issuer_id, message
+            ))),
+            Err(PorterError::ApiError { status: 404, .. }) => Err(PorterError::ConfigError(
+                format!("Issuer {} does not exist", issuer_id),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// A view of this client that authenticates with other credentials
+    ///
+    /// Everything else (audit sink, usage tracking, cache and the token
+    /// cache) is shared, so scoping a call to another issuer account is
+    /// cheap and tokens are still reused across calls:
+    ///
+    /// ```no_run
+    /// # use porter::google::{GenericObject, GoogleWalletClient, GoogleWalletConfig};
+    /// # async fn example(client: GoogleWalletClient, agency: GoogleWalletConfig, object: GenericObject) -> porter::error::Result<()> {
+    /// client.for_credentials(agency).create_generic_object(&object).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_credentials(&self, config: GoogleWalletConfig) -> Self {
+        Self {
+            config,
+            subject: None,
+            issuers: Arc::new(HashMap::new()),
+            signing_keys: vec![],
+            active_key: None,
+            ..self.clone()
+        }
+    }
+
+    /// Also issue under another issuer account
+    ///
+    /// Requests for classes and objects whose id starts with
+    /// `"<issuer_id>."` authenticate with `config`, as do save links whose
+    /// first object belongs to that issuer. Everything else uses the
+    /// client's own configuration. Issuers may share a service account.
+    ///
+    /// ```no_run
+    /// # use porter::google::{GoogleWalletClient, GoogleWalletConfig};
+    /// # async fn example(primary: GoogleWalletConfig, brand: GoogleWalletConfig) -> porter::error::Result<()> {
+    /// let mut client = GoogleWalletClient::new(primary).with_issuer(brand);
+    /// // Authenticated with the brand's service account
+    /// let object = client.get_generic_object("3388000000022222228.pass_001").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_issuer(mut self, config: GoogleWalletConfig) -> Self {
+        Arc::make_mut(&mut self.issuers).insert(config.issuer_id.clone(), config);
+        self
+    }
+
+    /// Configuration of the additional issuer owning `id`, if any
+    fn issuer_for(&self, id: &str) -> Option<&GoogleWalletConfig> {
+        let (issuer_id, _) = id.split_once('.')?;
+        self.issuers.get(issuer_id)
+    }
+
+    /// Configuration of the additional issuer a request targets
+    ///
+    /// The issuer is taken from the id in `path`, then its `issuerId` or
+    /// `classId` query, and for inserts from the `id` (or `classId`) of the
+    /// submitted `body`. Save JWTs route by their first object.
+    fn route(&self, path: &str, body: Option<&Value>) -> Option<&GoogleWalletConfig> {
+        if self.issuers.is_empty() {
+            return None;
+        }
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let param = |name: &str| {
+            query
+                .split('&')
+                .find_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+        };
+        if let Some(issuer_id) = param("issuerId") {
+            return self.issuers.get(issuer_id);
+        }
+        if let Some(id) = path
+            .trim_start_matches('/')
+            .split('/')
+            .nth(1)
+            .or_else(|| param("classId"))
+        {
+            return self.issuer_for(id);
+        }
+
+        let body = body?;
+        if path == "/jwt" {
+            let jwt = body.get("jwt").and_then(Value::as_str)?;
+            let claims = JwtPayload::from_save_url(jwt).ok()?;
+            return self.issuer_for(first_object_id(&claims.payload)?);
+        }
+        ["id", "classId"]
+            .iter()
+            .find_map(|key| body.get(key).and_then(Value::as_str))
+            .and_then(|id| self.issuer_for(id))
+    }
+
+    /// A view of this client that acts on behalf of `subject`
+    ///
+    /// Sets the `sub` claim of the token request, for service accounts with
+    /// domain-wide delegation.
+    pub fn for_subject(&self, subject: impl Into<String>) -> Self {
+        Self {
+            subject: Some(subject.into()),
+            ..self.clone()
+        }
+    }
+
+    /// Cache generic objects and classes read by id for `ttl`
+    ///
+    /// [`get_generic_object`](Self::get_generic_object) and
+    /// [`get_generic_class`](Self::get_generic_class) serve cached copies,
+    /// and any write through this client (or its clones) drops the cached
+    /// copy of the resource it touches. Changes made elsewhere are not seen
+    /// until the entry expires. Entries are kept per service account and
+    /// subject, so a copy read under one identity is never served to
+    /// another. Helpers that read a resource before writing it back, such
+    /// as [`transfer_pass`](Self::transfer_pass), always read it fresh.
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.cache = Some(Arc::new(ResponseCache::new(ttl)));
+        self
+    }
+
+    /// Drop the cached copies of an object or class
+    pub fn invalidate_cached(&self, id: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(&format!("/genericObject/{}", id));
+            cache.invalidate(&format!("/genericClass/{}", id));
+        }
+    }
+
+    /// Drop every cached response
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
         }
     }
 
-    /// Generate a JWT for authentication
-    fn generate_jwt(&self) -> Result<String> {
+    /// Skip every mutating request, recording it instead
+    ///
+    /// Requests are still built and serialized, and reads still go to the
+    /// API, but POST, PUT and PATCH requests are recorded in
+    /// [`dry_run_requests`](Self::dry_run_requests) instead of being sent.
+    /// Writes return the submitted resource (for PATCH, the current resource
+    /// with the patch applied) and are not sent to the audit sink.
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = Some(vec![]);
+        self
+    }
+
+    /// Mutations skipped in dry-run mode, in the order they were added
+    pub fn dry_run_requests(&self) -> &[DryRunRequest] {
+        self.dry_run.as_deref().unwrap_or_default()
+    }
+
+    /// Generate a JWT for authentication as `config`
+    fn generate_jwt(&self, config: &GoogleWalletConfig) -> Result<String> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_err(|e| PorterError::AuthError(format!("Time error: {}", e)))?
             .as_secs();
 
         let claims = Claims {
-            iss: self.config.service_account_email.clone(),
+            iss: config.service_account_email.clone(),
             scope: SCOPE.to_string(),
             aud: GOOGLE_TOKEN_URI.to_string(),
             exp: now + 3600,
             iat: now,
+            sub: self.subject.clone(),
         };
 
-        let key = EncodingKey::from_rsa_pem(self.config.private_key.as_bytes())?;
-        let token = encode(&Header::new(Algorithm::RS256), &claims, &key)?;
+        self.sign(config, &claims)
+    }
+
+    /// Sign `claims` with the active key for `config`'s service account
+    ///
+    /// Held signing keys belong to the client's own service account, which
+    /// additional issuers may share; other accounts sign with their
+    /// configured key and no `kid`.
+    fn sign(&self, config: &GoogleWalletConfig, claims: &impl Serialize) -> Result<String> {
+        self.signer(config)?.sign(claims)
+    }
+
+    /// The parsed signing key for `config`, parsing it on first use
+    fn signer(&self, config: &GoogleWalletConfig) -> Result<Arc<JwtSigner>> {
+        let mut kid = None;
+        let mut private_key = &config.private_key;
+        if config.service_account_email == self.config.service_account_email {
+            if let Some(key) = self.active_signing_key() {
+                kid = Some(key.key_id.clone());
+                private_key = &key.private_key;
+            }
+        }
+
+        let mut signers = self.signers.lock().unwrap_or_else(|e| e.into_inner());
+        let cache_key = (private_key.clone(), kid);
+        if let Some(signer) = signers.get(&cache_key) {
+            return Ok(signer.clone());
+        }
+        let signer = Arc::new(JwtSigner::new(private_key, cache_key.1.as_deref())?);
+        signers.insert(cache_key, signer.clone());
+        Ok(signer)
+    }
 
-        Ok(token)
+    fn active_signing_key(&self) -> Option<&SigningKey> {
+        let active = self.active_key.as_deref()?;
+        self.signing_keys.iter().find(|k| k.key_id == active)
     }
 
-    /// Get an access token, refreshing if necessary
-    async fn get_access_token(&mut self) -> Result<String> {
+    /// Get an access token for `config`, refreshing if necessary
+    ///
+    /// The principal's token stays locked during the exchange, so concurrent
+    /// callers for it wait for a single refresh instead of each starting
+    /// one. Callers using other credentials or subjects are not held up.
+    async fn get_access_token(&self, config: &GoogleWalletConfig) -> Result<String> {
+        let key = (config.service_account_email.clone(), self.subject.clone());
+        let slot = self
+            .tokens
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_default()
+            .clone();
+        let mut cached = slot.lock().await;
+
         // Check if we have a valid token
-        if let (Some(token), Some(expiry)) = (&self.access_token, self.token_expiry) {
-            if SystemTime::now() < expiry - Duration::from_secs(300) {
-                return Ok(token.clone());
+        if let Some(token) = cached.as_ref() {
+            if SystemTime::now() < token.expiry - Duration::from_secs(300) {
+                return Ok(token.access_token.clone());
             }
         }
 
         // Generate new JWT
-        let jwt = self.generate_jwt()?;
+        let jwt = self.generate_jwt(config)?;
 
         // Exchange JWT for access token
         let params = [
@@ -111,38 +618,184 @@ impl GoogleWalletClient {
 
         let token_response: TokenResponse = response.json().await?;
 
-        self.access_token = Some(token_response.access_token.clone());
-        self.token_expiry =
-            Some(SystemTime::now() + Duration::from_secs(token_response.expires_in));
+        *cached = Some(CachedToken {
+            access_token: token_response.access_token.clone(),
+            expiry: SystemTime::now() + Duration::from_secs(token_response.expires_in),
+        });
 
         Ok(token_response.access_token)
     }
 
-    /// Make an authenticated request
+    /// Make an authenticated request, recording mutations to the audit sink
     async fn request<T: for<'de> Deserialize<'de>>(
         &mut self,
         method: reqwest::Method,
         path: &str,
         body: Option<&impl Serialize>,
     ) -> Result<T> {
-        let token = self.get_access_token().await?;
-        let url = format!("{}{}", GOOGLE_WALLET_API_BASE, path);
+        if self.dry_run.is_some() && method != reqwest::Method::GET {
+            let value = self.dry_run_request(method, path, body).await?;
+            return Ok(serde_json::from_value(value)?);
+        }
+
+        let Some(sink) = self.audit_sink.clone() else {
+            let value = self.send(method, path, body).await?;
+            return Ok(serde_json::from_value(value)?);
+        };
+
+        let submitted = body.map(serde_json::to_value).transpose()?;
+        let action = audit_action(&method, path, submitted.as_ref());
+
+        let before = match action {
+            Some(AuditAction::Update | AuditAction::Patch | AuditAction::Expire)
+                if sink.capture_before() =>
+            {
+                Some(self.send(reqwest::Method::GET, path, None::<&()>).await?)
+            }
+            _ => None,
+        };
+
+        let value = self.send(method, path, body).await?;
+
+        if let Some(action) = action {
+            let (resource_type, resource_id) = audit_resource(path, &value);
+            sink.record(AuditEvent {
+                actor: self
+                    .audit_actor
+                    .clone()
+                    .unwrap_or_else(|| self.config.service_account_email.clone()),
+                action,
+                resource_type,
+                resource_id,
+                timestamp: chrono::Utc::now(),
+                before,
+                after: Some(value.clone()),
+            })
+            .await;
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Service account and subject a GET of `path` is made as
+    fn principal(&self, path: &str) -> Principal {
+        let config = self.route(path, None).unwrap_or(&self.config);
+        (config.service_account_email.clone(), self.subject.clone())
+    }
+
+    /// GET a resource through the response cache, if one is configured
+    async fn cached_get<T: DeserializeOwned>(&mut self, path: &str) -> Result<T> {
+        let Some(cache) = self.cache.clone() else {
+            return self.request(reqwest::Method::GET, path, None::<&()>).await;
+        };
+        let principal = self.principal(path);
+        if let Some(value) = cache.get(&principal, path) {
+            return Ok(serde_json::from_value(value)?);
+        }
+
+        let value: Value = self
+            .request(reqwest::Method::GET, path, None::<&()>)
+            .await?;
+        cache.insert(&principal, path, value.clone());
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Record a mutation instead of sending it, and build a plausible response
+    async fn dry_run_request(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<Value> {
+        let body = body.map(serde_json::to_value).transpose()?;
+        if let Some(log) = &mut self.dry_run {
+            log.push(DryRunRequest {
+                method: method.to_string(),
+                path: path.to_string(),
+                body: body.clone(),
+            });
+        }
+
+        let resource_path = path.split('?').next().unwrap_or_default();
+        if resource_path == "/jwt" {
+            // Hand back a plain JWT link, which inserts nothing until it is opened
+            let jwt = body
+                .as_ref()
+                .and_then(|b| b.get("jwt"))
+                .and_then(Value::as_str);
+            return Ok(serde_json::json!({
+                "saveUri": format!("{}{}", SAVE_URL_PREFIX, jwt.unwrap_or_default())
+            }));
+        }
+        let segments = resource_path.trim_start_matches('/').split('/').count();
+        if segments > 2 {
+            // Actions such as addMessage return the resource they act on
+            let (parent, _) = resource_path.rsplit_once('/').unwrap_or_default();
+            return self.send(reqwest::Method::GET, parent, None::<&()>).await;
+        }
+
+        match (method, body) {
+            (reqwest::Method::PATCH, Some(patch)) => {
+                let mut current = self
+                    .send(reqwest::Method::GET, resource_path, None::<&()>)
+                    .await?;
+                merge_changes(&mut current, &Value::Object(Default::default()), &patch);
+                Ok(current)
+            }
+            (_, body) => Ok(body.unwrap_or_else(|| Value::Object(Default::default()))),
+        }
+    }
+
+    /// Send an authenticated request and return the raw JSON response
+    async fn send(
+        &mut self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<Value> {
+        if method != reqwest::Method::GET {
+            if let Some(cache) = &self.cache {
+                cache.invalidate(path);
+            }
+        }
+
+        let body = body.map(serde_json::to_value).transpose()?;
+        let config = self
+            .route(path, body.as_ref())
+            .unwrap_or(&self.config)
+            .clone();
+        let token = self.get_access_token(&config).await?;
+        let url = format!("{}{}", self.api_base, path);
 
         let mut request = self
             .client
-            .request(method, &url)
+            .request(method.clone(), &url)
             .bearer_auth(token)
             .header("Content-Type", "application/json");
 
-        if let Some(body) = body {
+        if let Some(body) = &body {
             request = request.json(body);
         }
 
         let response = request.send().await?;
         let status = response.status();
+        if let Some(tracker) = &self.usage_tracker {
+            tracker.record(&method, path, &response);
+        }
 
         if status.is_success() {
             let result = response.json().await?;
+            if self.strict {
+                let unknown = super::strict::unknown_enum_values(&result);
+                if !unknown.is_empty() {
+                    return Err(PorterError::ValidationError(format!(
+                        "Unknown values in response to {} {}: {}",
+                        method,
+                        path,
+                        unknown.join(", ")
+                    )));
+                }
+            }
             Ok(result)
         } else {
             let error_text = response.text().await?;
@@ -161,12 +814,8 @@ impl GoogleWalletClient {
 
     /// Get a generic class
     pub async fn get_generic_class(&mut self, class_id: &str) -> Result<GenericClass> {
-        self.request(
-            reqwest::Method::GET,
-            &format!("/genericClass/{}", class_id),
-            None::<&()>,
-        )
-        .await
+        self.cached_get(&format!("/genericClass/{}", class_id))
+            .await
     }
 
     /// Update a generic class
@@ -183,6 +832,40 @@ impl GoogleWalletClient {
         .await
     }
 
+    /// Bring a remote class in line with `class`, writing only what drifted
+    ///
+    /// The class is created if it does not exist. Otherwise every top-level
+    /// field of `class` that differs from the remote class is sent in a
+    /// single PATCH; fields only present remotely are left alone, and
+    /// nothing is written when there is no drift. `reviewStatus` is not
+    /// compared, since Google changes it during review, but it is included
+    /// whenever a patch is sent.
+    pub async fn sync_class(&mut self, class: &GenericClass) -> Result<ClassSync> {
+        let path = format!("/genericClass/{}", class.id);
+        let remote: Value = match self.request(reqwest::Method::GET, &path, None::<&()>).await {
+            Ok(remote) => remote,
+            Err(PorterError::ApiError { status: 404, .. }) => {
+                return Ok(ClassSync::Created(self.create_generic_class(class).await?));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let desired = serde_json::to_value(class)?;
+        let mut patch = class_drift(&remote, &desired);
+        if patch.is_empty() {
+            return Ok(ClassSync::Unchanged);
+        }
+
+        let changed = patch.keys().cloned().collect();
+        if let Some(status) = desired.get("reviewStatus") {
+            patch.insert("reviewStatus".to_string(), status.clone());
+        }
+        let class = self
+            .request(reqwest::Method::PATCH, &path, Some(&patch))
+            .await?;
+        Ok(ClassSync::Updated { class, changed })
+    }
+
     /// Create a generic object (pass)
     pub async fn create_generic_object(&mut self, object: &GenericObject) -> Result<GenericObject> {
         self.request(reqwest::Method::POST, "/genericObject", Some(object))
@@ -191,6 +874,15 @@ impl GoogleWalletClient {
 
     /// Get a generic object
     pub async fn get_generic_object(&mut self, object_id: &str) -> Result<GenericObject> {
+        self.cached_get(&format!("/genericObject/{}", object_id))
+            .await
+    }
+
+    /// Get a generic object from the API, bypassing the response cache
+    ///
+    /// Used before writes, so a cached copy that another process has since
+    /// changed is never written back.
+    async fn fetch_generic_object(&mut self, object_id: &str) -> Result<GenericObject> {
         self.request(
             reqwest::Method::GET,
             &format!("/genericObject/{}", object_id),
@@ -227,146 +919,2170 @@ impl GoogleWalletClient {
         .await
     }
 
-    /// List generic objects
-    pub async fn list_generic_objects(
-        &mut self,
-        class_id: Option<&str>,
-    ) -> Result<GenericObjectListResponse> {
-        let path = if let Some(class_id) = class_id {
-            format!("/genericObject?classId={}", class_id)
-        } else {
-            "/genericObject".to_string()
-        };
-
-        self.request(reqwest::Method::GET, &path, None::<&()>).await
-    }
-
-    /// Add a message to a generic object
-    pub async fn add_message_to_object(
+    /// Patch only the fields of a generic object named by `mask`
+    pub async fn patch_generic_object_masked(
         &mut self,
         object_id: &str,
-        message: &AddMessageRequest,
+        object: &GenericObject,
+        mask: &FieldMask,
     ) -> Result<GenericObject> {
-        self.request(
-            reqwest::Method::POST,
-            &format!("/genericObject/{}/addMessage", object_id),
-            Some(message),
-        )
-        .await
+        self.patch_masked(&format!("/genericObject/{}", object_id), object, mask)
+            .await
     }
 
-    /// Create an event ticket object
-    pub async fn create_event_ticket(
+    /// Patch only the fields of a generic class named by `mask`
+    pub async fn patch_generic_class_masked(
         &mut self,
-        ticket: &EventTicketObject,
-    ) -> Result<EventTicketObject> {
-        self.request(reqwest::Method::POST, "/eventTicketObject", Some(ticket))
+        class_id: &str,
+        class: &GenericClass,
+        mask: &FieldMask,
+    ) -> Result<GenericClass> {
+        self.patch_masked(&format!("/genericClass/{}", class_id), class, mask)
             .await
     }
 
-    /// Get an event ticket object
-    pub async fn get_event_ticket(&mut self, object_id: &str) -> Result<EventTicketObject> {
-        self.request(
-            reqwest::Method::GET,
-            &format!("/eventTicketObject/{}", object_id),
-            None::<&()>,
-        )
-        .await
-    }
-
-    /// Update an event ticket object
-    pub async fn update_event_ticket(
+    /// Patch only the fields of an event ticket object named by `mask`
+    pub async fn patch_event_ticket_masked(
         &mut self,
         object_id: &str,
         ticket: &EventTicketObject,
+        mask: &FieldMask,
     ) -> Result<EventTicketObject> {
-        self.request(
-            reqwest::Method::PUT,
-            &format!("/eventTicketObject/{}", object_id),
-            Some(ticket),
-        )
-        .await
+        self.patch_masked(&format!("/eventTicketObject/{}", object_id), ticket, mask)
+            .await
     }
 
-    /// Create a loyalty object
-    pub async fn create_loyalty_object(
+    /// Patch only the fields of a loyalty object named by `mask`
+    pub async fn patch_loyalty_object_masked(
         &mut self,
+        object_id: &str,
         loyalty: &LoyaltyObject,
+        mask: &FieldMask,
     ) -> Result<LoyaltyObject> {
-        self.request(reqwest::Method::POST, "/loyaltyObject", Some(loyalty))
+        self.patch_masked(&format!("/loyaltyObject/{}", object_id), loyalty, mask)
             .await
     }
 
-    /// Get a loyalty object
-    pub async fn get_loyalty_object(&mut self, object_id: &str) -> Result<LoyaltyObject> {
-        self.request(
-            reqwest::Method::GET,
-            &format!("/loyaltyObject/{}", object_id),
-            None::<&()>,
-        )
-        .await
+    /// Patch only the fields of a loyalty class named by `mask`
+    pub async fn patch_loyalty_class_masked(
+        &mut self,
+        class_id: &str,
+        class: &LoyaltyClass,
+        mask: &FieldMask,
+    ) -> Result<LoyaltyClass> {
+        self.patch_masked(&format!("/loyaltyClass/{}", class_id), class, mask)
+            .await
     }
 
-    /// Update a loyalty object
-    pub async fn update_loyalty_object(
+    /// Patch only the fields of an offer object named by `mask`
+    pub async fn patch_offer_object_masked(
         &mut self,
         object_id: &str,
-        loyalty: &LoyaltyObject,
-    ) -> Result<LoyaltyObject> {
-        self.request(
-            reqwest::Method::PUT,
-            &format!("/loyaltyObject/{}", object_id),
-            Some(loyalty),
-        )
+        offer: &OfferObject,
+        mask: &FieldMask,
+    ) -> Result<OfferObject> {
+        self.patch_masked(&format!("/offerObject/{}", object_id), offer, mask)
+            .await
+    }
+
+    /// Patch only the fields of an offer class named by `mask`
+    pub async fn patch_offer_class_masked(
+        &mut self,
+        class_id: &str,
+        class: &OfferClass,
+        mask: &FieldMask,
+    ) -> Result<OfferClass> {
+        self.patch_masked(&format!("/offerClass/{}", class_id), class, mask)
+            .await
+    }
+
+    /// Patch only the fields of a gift card object named by `mask`
+    pub async fn patch_gift_card_object_masked(
+        &mut self,
+        object_id: &str,
+        gift_card: &GiftCardObject,
+        mask: &FieldMask,
+    ) -> Result<GiftCardObject> {
+        self.patch_masked(&format!("/giftCardObject/{}", object_id), gift_card, mask)
+            .await
+    }
+
+    /// Patch only the fields of a gift card class named by `mask`
+    pub async fn patch_gift_card_class_masked(
+        &mut self,
+        class_id: &str,
+        class: &GiftCardClass,
+        mask: &FieldMask,
+    ) -> Result<GiftCardClass> {
+        self.patch_masked(&format!("/giftCardClass/{}", class_id), class, mask)
+            .await
+    }
+
+    /// Patch only the fields of a transit object named by `mask`
+    pub async fn patch_transit_object_masked(
+        &mut self,
+        object_id: &str,
+        ticket: &TransitObject,
+        mask: &FieldMask,
+    ) -> Result<TransitObject> {
+        self.patch_masked(&format!("/transitObject/{}", object_id), ticket, mask)
+            .await
+    }
+
+    /// Patch only the fields of a transit class named by `mask`
+    pub async fn patch_transit_class_masked(
+        &mut self,
+        class_id: &str,
+        class: &TransitClass,
+        mask: &FieldMask,
+    ) -> Result<TransitClass> {
+        self.patch_masked(&format!("/transitClass/{}", class_id), class, mask)
+            .await
+    }
+
+    /// Patch only the fields of a flight object named by `mask`
+    pub async fn patch_flight_object_masked(
+        &mut self,
+        object_id: &str,
+        boarding_pass: &FlightObject,
+        mask: &FieldMask,
+    ) -> Result<FlightObject> {
+        self.patch_masked(&format!("/flightObject/{}", object_id), boarding_pass, mask)
+            .await
+    }
+
+    /// Patch only the fields of a flight class named by `mask`
+    pub async fn patch_flight_class_masked(
+        &mut self,
+        class_id: &str,
+        class: &FlightClass,
+        mask: &FieldMask,
+    ) -> Result<FlightClass> {
+        self.patch_masked(&format!("/flightClass/{}", class_id), class, mask)
+            .await
+    }
+
+    /// PATCH the resource at `path` with only the fields of `resource` named by `mask`
+    async fn patch_masked<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        resource: &impl Serialize,
+        mask: &FieldMask,
+    ) -> Result<T> {
+        let body = mask.apply(resource)?;
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("{}?updateMask={}", path, mask),
+            Some(&body),
+        )
+        .await
+    }
+
+    /// List generic objects
+    pub async fn list_generic_objects(
+        &mut self,
+        class_id: Option<&str>,
+    ) -> Result<GenericObjectListResponse> {
+        let path = if let Some(class_id) = class_id {
+            format!("/genericObject?classId={}", class_id)
+        } else {
+            "/genericObject".to_string()
+        };
+
+        self.request(reqwest::Method::GET, &path, None::<&()>).await
+    }
+
+    /// Send the calls of a batch in one request
+    ///
+    /// Results are in the order the calls were added. A failed call does
+    /// not fail the others, so check each [`BatchItemResult`]. The batch is
+    /// sent with the credentials its first call routes to.
+    pub async fn send_batch(&mut self, batch: &BatchRequest) -> Result<Vec<BatchItemResult>> {
+        let Some(first) = batch.items.first() else {
+            return Ok(vec![]);
+        };
+
+        if self.dry_run.is_some() {
+            let mut results = vec![];
+            for item in &batch.items {
+                let body = self
+                    .dry_run_request(item.method.clone(), &item.path, Some(&item.body))
+                    .await?;
+                results.push(BatchItemResult { status: 200, body });
+            }
+            return Ok(results);
+        }
+
+        if let Some(cache) = &self.cache {
+            for item in &batch.items {
+                cache.invalidate(&item.path);
+            }
+        }
+
+        let config = self
+            .route(&first.path, Some(&first.body))
+            .unwrap_or(&self.config)
+            .clone();
+        let token = self.get_access_token(&config).await?;
+        let boundary = format!("batch_{}", random_id()?);
+        let response = self
+            .client
+            .post(GOOGLE_WALLET_BATCH_URL)
+            .bearer_auth(token)
+            .header(
+                "Content-Type",
+                format!("multipart/mixed; boundary={}", boundary),
+            )
+            .body(batch.multipart_body(&boundary))
+            .send()
+            .await?;
+        let status = response.status();
+        if let Some(tracker) = &self.usage_tracker {
+            tracker.record(&reqwest::Method::POST, "/batch", &response);
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let text = response.text().await?;
+        let response_boundary = batch::boundary(&content_type).filter(|_| status.is_success());
+        let Some(response_boundary) = response_boundary else {
+            return Err(PorterError::ApiError {
+                status: status.as_u16(),
+                message: text,
+            });
+        };
+
+        let results = batch::parse_response(&text, response_boundary)?;
+        if results.len() != batch.len() {
+            return Err(PorterError::ApiError {
+                status: status.as_u16(),
+                message: format!(
+                    "Batch of {} calls returned {} results",
+                    batch.len(),
+                    results.len()
+                ),
+            });
+        }
+
+        if let Some(sink) = self.audit_sink.clone() {
+            for (item, result) in batch.items.iter().zip(&results) {
+                let action = audit_action(&item.method, &item.path, Some(&item.body));
+                let Some(action) = action.filter(|_| result.is_success()) else {
+                    continue;
+                };
+                let (resource_type, resource_id) = audit_resource(&item.path, &result.body);
+                sink.record(AuditEvent {
+                    actor: self
+                        .audit_actor
+                        .clone()
+                        .unwrap_or_else(|| self.config.service_account_email.clone()),
+                    action,
+                    resource_type,
+                    resource_id,
+                    timestamp: chrono::Utc::now(),
+                    before: None,
+                    after: Some(result.body.clone()),
+                })
+                .await;
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Fetch one page of the generic objects of a class
+    ///
+    /// Pass the `next_page_token` of a page's pagination as `page_token` to
+    /// get the next page. Google picks the page size when `max_results` is
+    /// not set.
+    pub async fn list_generic_objects_page(
+        &mut self,
+        class_id: &str,
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<GenericObjectListResponse> {
+        let path = list_path(
+            "genericObject",
+            ListParent::Class(class_id),
+            max_results,
+            page_token,
+        )?;
+        self.request(reqwest::Method::GET, &path, None::<&()>).await
+    }
+
+    /// All generic objects of a class, following every page
+    pub async fn list_generic_objects_all(&mut self, class_id: &str) -> Result<Vec<GenericObject>> {
+        self.list_all("genericObject", ListParent::Class(class_id))
+            .await
+    }
+
+    /// All event tickets of a class, following every page
+    pub async fn list_event_tickets_all(
+        &mut self,
+        class_id: &str,
+    ) -> Result<Vec<EventTicketObject>> {
+        self.list_all("eventTicketObject", ListParent::Class(class_id))
+            .await
+    }
+
+    /// All loyalty objects of a class, following every page
+    pub async fn list_loyalty_objects_all(&mut self, class_id: &str) -> Result<Vec<LoyaltyObject>> {
+        self.list_all("loyaltyObject", ListParent::Class(class_id))
+            .await
+    }
+
+    /// All offer objects of a class, following every page
+    pub async fn list_offer_objects_all(&mut self, class_id: &str) -> Result<Vec<OfferObject>> {
+        self.list_all("offerObject", ListParent::Class(class_id))
+            .await
+    }
+
+    /// All gift card objects of a class, following every page
+    pub async fn list_gift_card_objects_all(
+        &mut self,
+        class_id: &str,
+    ) -> Result<Vec<GiftCardObject>> {
+        self.list_all("giftCardObject", ListParent::Class(class_id))
+            .await
+    }
+
+    /// All transit objects of a class, following every page
+    pub async fn list_transit_objects_all(&mut self, class_id: &str) -> Result<Vec<TransitObject>> {
+        self.list_all("transitObject", ListParent::Class(class_id))
+            .await
+    }
+
+    /// All flight objects of a class, following every page
+    pub async fn list_flight_objects_all(&mut self, class_id: &str) -> Result<Vec<FlightObject>> {
+        self.list_all("flightObject", ListParent::Class(class_id))
+            .await
+    }
+
+    /// Fetch one page of the generic classes of an issuer
+    pub async fn list_generic_classes(
+        &mut self,
+        issuer_id: &str,
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<GenericClassListResponse> {
+        let path = list_path(
+            "genericClass",
+            ListParent::Issuer(issuer_id),
+            max_results,
+            page_token,
+        )?;
+        self.request(reqwest::Method::GET, &path, None::<&()>).await
+    }
+
+    /// All generic classes of an issuer, following every page
+    pub async fn list_generic_classes_all(&mut self, issuer_id: &str) -> Result<Vec<GenericClass>> {
+        self.list_all("genericClass", ListParent::Issuer(issuer_id))
+            .await
+    }
+
+    /// All loyalty classes of an issuer, following every page
+    pub async fn list_loyalty_classes_all(&mut self, issuer_id: &str) -> Result<Vec<LoyaltyClass>> {
+        self.list_all("loyaltyClass", ListParent::Issuer(issuer_id))
+            .await
+    }
+
+    /// All offer classes of an issuer, following every page
+    pub async fn list_offer_classes_all(&mut self, issuer_id: &str) -> Result<Vec<OfferClass>> {
+        self.list_all("offerClass", ListParent::Issuer(issuer_id))
+            .await
+    }
+
+    /// All gift card classes of an issuer, following every page
+    pub async fn list_gift_card_classes_all(
+        &mut self,
+        issuer_id: &str,
+    ) -> Result<Vec<GiftCardClass>> {
+        self.list_all("giftCardClass", ListParent::Issuer(issuer_id))
+            .await
+    }
+
+    /// All transit classes of an issuer, following every page
+    pub async fn list_transit_classes_all(&mut self, issuer_id: &str) -> Result<Vec<TransitClass>> {
+        self.list_all("transitClass", ListParent::Issuer(issuer_id))
+            .await
+    }
+
+    /// All flight classes of an issuer, following every page
+    pub async fn list_flight_classes_all(&mut self, issuer_id: &str) -> Result<Vec<FlightClass>> {
+        self.list_all("flightClass", ListParent::Issuer(issuer_id))
+            .await
+    }
+
+    /// Stream the generic objects of a class, fetching each page when it is reached
+    ///
+    /// Unlike [`list_generic_objects_all`](Self::list_generic_objects_all),
+    /// only one page is held in memory at a time. The stream ends after the
+    /// first error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures_util::StreamExt;
+    /// # use porter::google::GoogleWalletClient;
+    ///
+    /// # async fn example(mut client: GoogleWalletClient) -> porter::Result<()> {
+    /// let mut objects = std::pin::pin!(client.stream_generic_objects("issuer_id.class_001"));
+    /// while let Some(object) = objects.next().await {
+    ///     println!("{}", object?.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_generic_objects<'a>(
+        &'a mut self,
+        class_id: &'a str,
+    ) -> impl Stream<Item = Result<GenericObject>> + 'a {
+        self.stream_resources("genericObject", ListParent::Class(class_id))
+    }
+
+    /// All resources in `collection` under `parent`, following every page
+    async fn list_all<T: DeserializeOwned>(
+        &mut self,
+        collection: &str,
+        parent: ListParent<'_>,
+    ) -> Result<Vec<T>> {
+        self.stream_resources(collection, parent)
+            .try_collect()
+            .await
+    }
+
+    /// Stream the resources in `collection` under `parent`, one page at a time
+    fn stream_resources<'a, T: DeserializeOwned + 'a>(
+        &'a mut self,
+        collection: &'a str,
+        parent: ListParent<'a>,
+    ) -> impl Stream<Item = Result<T>> + 'a {
+        let state = ListState {
+            buffer: VecDeque::new(),
+            page_token: None,
+            finished: false,
+        };
+        stream::unfold((self, state), move |(client, mut state)| async move {
+            loop {
+                if let Some(object) = state.buffer.pop_front() {
+                    return Some((Ok(object), (client, state)));
+                }
+                if state.finished {
+                    return None;
+                }
+
+                let page = match list_path(collection, parent, None, state.page_token.as_deref()) {
+                    Ok(path) => {
+                        client
+                            .request::<ListResponse<T>>(reqwest::Method::GET, &path, None::<&()>)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                match page {
+                    Ok(page) => {
+                        state.buffer.extend(page.resources.unwrap_or_default());
+                        state.page_token = page.pagination.and_then(|p| p.next_page_token);
+                        state.finished = state.page_token.is_none();
+                    }
+                    Err(e) => {
+                        state.finished = true;
+                        return Some((Err(e), (client, state)));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Add a message to a generic object
+    pub async fn add_message_to_object(
+        &mut self,
+        object_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<GenericObject> {
+        self.add_message(&format!("/genericObject/{}", object_id), message)
+            .await
+    }
+
+    /// Add a message to all objects of a generic class
+    pub async fn add_message_to_generic_class(
+        &mut self,
+        class_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<GenericClass> {
+        self.add_message(&format!("/genericClass/{}", class_id), message)
+            .await
+    }
+
+    /// Add a message to an event ticket object
+    pub async fn add_message_to_event_ticket(
+        &mut self,
+        object_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<EventTicketObject> {
+        self.add_message(&format!("/eventTicketObject/{}", object_id), message)
+            .await
+    }
+
+    /// Add a message to all objects of a loyalty class
+    pub async fn add_message_to_loyalty_class(
+        &mut self,
+        class_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<LoyaltyClass> {
+        self.add_message(&format!("/loyaltyClass/{}", class_id), message)
+            .await
+    }
+
+    /// Add a message to a loyalty object
+    pub async fn add_message_to_loyalty_object(
+        &mut self,
+        object_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<LoyaltyObject> {
+        self.add_message(&format!("/loyaltyObject/{}", object_id), message)
+            .await
+    }
+
+    /// Add a message to all objects of an offer class
+    pub async fn add_message_to_offer_class(
+        &mut self,
+        class_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<OfferClass> {
+        self.add_message(&format!("/offerClass/{}", class_id), message)
+            .await
+    }
+
+    /// Add a message to an offer object
+    pub async fn add_message_to_offer_object(
+        &mut self,
+        object_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<OfferObject> {
+        self.add_message(&format!("/offerObject/{}", object_id), message)
+            .await
+    }
+
+    /// Add a message to all objects of a gift card class
+    pub async fn add_message_to_gift_card_class(
+        &mut self,
+        class_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<GiftCardClass> {
+        self.add_message(&format!("/giftCardClass/{}", class_id), message)
+            .await
+    }
+
+    /// Add a message to a gift card object
+    pub async fn add_message_to_gift_card_object(
+        &mut self,
+        object_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<GiftCardObject> {
+        self.add_message(&format!("/giftCardObject/{}", object_id), message)
+            .await
+    }
+
+    /// Add a message to all objects of a transit class
+    pub async fn add_message_to_transit_class(
+        &mut self,
+        class_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<TransitClass> {
+        self.add_message(&format!("/transitClass/{}", class_id), message)
+            .await
+    }
+
+    /// Add a message to a transit object
+    pub async fn add_message_to_transit_object(
+        &mut self,
+        object_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<TransitObject> {
+        self.add_message(&format!("/transitObject/{}", object_id), message)
+            .await
+    }
+
+    /// Add a message to all objects of a flight class
+    pub async fn add_message_to_flight_class(
+        &mut self,
+        class_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<FlightClass> {
+        self.add_message(&format!("/flightClass/{}", class_id), message)
+            .await
+    }
+
+    /// Add a message to a flight object
+    pub async fn add_message_to_flight_object(
+        &mut self,
+        object_id: &str,
+        message: &AddMessageRequest,
+    ) -> Result<FlightObject> {
+        self.add_message(&format!("/flightObject/{}", object_id), message)
+            .await
+    }
+
+    /// Add a message to the class or object at `path`, returning the updated resource
+    async fn add_message<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        message: &AddMessageRequest,
+    ) -> Result<T> {
+        let response: Value = self
+            .request(
+                reqwest::Method::POST,
+                &format!("{}/addMessage", path),
+                Some(message),
+            )
+            .await?;
+        Ok(serde_json::from_value(added_message_resource(response))?)
+    }
+
+    /// Messages shown on a generic object, in the order they were added
+    pub async fn get_messages(&mut self, object_id: &str) -> Result<Vec<Message>> {
+        let object = self.get_generic_object(object_id).await?;
+        Ok(object.messages.unwrap_or_default())
+    }
+
+    /// Messages shown on all objects of a generic class, in the order they were added
+    pub async fn get_class_messages(&mut self, class_id: &str) -> Result<Vec<Message>> {
+        let class = self.get_generic_class(class_id).await?;
+        Ok(class.messages.unwrap_or_default())
+    }
+
+    /// Create an event ticket object
+    pub async fn create_event_ticket(
+        &mut self,
+        ticket: &EventTicketObject,
+    ) -> Result<EventTicketObject> {
+        self.request(reqwest::Method::POST, "/eventTicketObject", Some(ticket))
+            .await
+    }
+
+    /// Get an event ticket object
+    pub async fn get_event_ticket(&mut self, object_id: &str) -> Result<EventTicketObject> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/eventTicketObject/{}", object_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update an event ticket object
+    pub async fn update_event_ticket(
+        &mut self,
+        object_id: &str,
+        ticket: &EventTicketObject,
+    ) -> Result<EventTicketObject> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/eventTicketObject/{}", object_id),
+            Some(ticket),
+        )
+        .await
+    }
+
+    /// Create a loyalty class
+    pub async fn create_loyalty_class(&mut self, class: &LoyaltyClass) -> Result<LoyaltyClass> {
+        self.request(reqwest::Method::POST, "/loyaltyClass", Some(class))
+            .await
+    }
+
+    /// Get a loyalty class
+    pub async fn get_loyalty_class(&mut self, class_id: &str) -> Result<LoyaltyClass> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/loyaltyClass/{}", class_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a loyalty class
+    pub async fn update_loyalty_class(
+        &mut self,
+        class_id: &str,
+        class: &LoyaltyClass,
+    ) -> Result<LoyaltyClass> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/loyaltyClass/{}", class_id),
+            Some(class),
+        )
+        .await
+    }
+
+    /// Partially update a loyalty class, such as to change its tier labels
+    pub async fn patch_loyalty_class(
+        &mut self,
+        class_id: &str,
+        class: &LoyaltyClass,
+    ) -> Result<LoyaltyClass> {
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("/loyaltyClass/{}", class_id),
+            Some(class),
+        )
+        .await
+    }
+
+    /// Create a loyalty object
+    pub async fn create_loyalty_object(
+        &mut self,
+        loyalty: &LoyaltyObject,
+    ) -> Result<LoyaltyObject> {
+        self.request(reqwest::Method::POST, "/loyaltyObject", Some(loyalty))
+            .await
+    }
+
+    /// Get a loyalty object
+    pub async fn get_loyalty_object(&mut self, object_id: &str) -> Result<LoyaltyObject> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/loyaltyObject/{}", object_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a loyalty object
+    pub async fn update_loyalty_object(
+        &mut self,
+        object_id: &str,
+        loyalty: &LoyaltyObject,
+    ) -> Result<LoyaltyObject> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/loyaltyObject/{}", object_id),
+            Some(loyalty),
+        )
+        .await
+    }
+
+    /// Create an offer class
+    pub async fn create_offer_class(&mut self, class: &OfferClass) -> Result<OfferClass> {
+        self.request(reqwest::Method::POST, "/offerClass", Some(class))
+            .await
+    }
+
+    /// Get an offer class
+    pub async fn get_offer_class(&mut self, class_id: &str) -> Result<OfferClass> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/offerClass/{}", class_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update an offer class
+    pub async fn update_offer_class(
+        &mut self,
+        class_id: &str,
+        class: &OfferClass,
+    ) -> Result<OfferClass> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/offerClass/{}", class_id),
+            Some(class),
+        )
+        .await
+    }
+
+    /// Create an offer object
+    pub async fn create_offer_object(&mut self, offer: &OfferObject) -> Result<OfferObject> {
+        self.request(reqwest::Method::POST, "/offerObject", Some(offer))
+            .await
+    }
+
+    /// Get an offer object
+    pub async fn get_offer_object(&mut self, object_id: &str) -> Result<OfferObject> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/offerObject/{}", object_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update an offer object
+    pub async fn update_offer_object(
+        &mut self,
+        object_id: &str,
+        offer: &OfferObject,
+    ) -> Result<OfferObject> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/offerObject/{}", object_id),
+            Some(offer),
+        )
+        .await
+    }
+
+    /// Create a gift card class
+    pub async fn create_gift_card_class(&mut self, class: &GiftCardClass) -> Result<GiftCardClass> {
+        self.request(reqwest::Method::POST, "/giftCardClass", Some(class))
+            .await
+    }
+
+    /// Get a gift card class
+    pub async fn get_gift_card_class(&mut self, class_id: &str) -> Result<GiftCardClass> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/giftCardClass/{}", class_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a gift card class
+    pub async fn update_gift_card_class(
+        &mut self,
+        class_id: &str,
+        class: &GiftCardClass,
+    ) -> Result<GiftCardClass> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/giftCardClass/{}", class_id),
+            Some(class),
+        )
+        .await
+    }
+
+    /// Create a gift card object
+    pub async fn create_gift_card_object(
+        &mut self,
+        gift_card: &GiftCardObject,
+    ) -> Result<GiftCardObject> {
+        self.request(reqwest::Method::POST, "/giftCardObject", Some(gift_card))
+            .await
+    }
+
+    /// Get a gift card object
+    pub async fn get_gift_card_object(&mut self, object_id: &str) -> Result<GiftCardObject> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/giftCardObject/{}", object_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a gift card object
+    pub async fn update_gift_card_object(
+        &mut self,
+        object_id: &str,
+        gift_card: &GiftCardObject,
+    ) -> Result<GiftCardObject> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/giftCardObject/{}", object_id),
+            Some(gift_card),
+        )
+        .await
+    }
+
+    /// Create a transit class
+    pub async fn create_transit_class(&mut self, class: &TransitClass) -> Result<TransitClass> {
+        self.request(reqwest::Method::POST, "/transitClass", Some(class))
+            .await
+    }
+
+    /// Get a transit class
+    pub async fn get_transit_class(&mut self, class_id: &str) -> Result<TransitClass> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/transitClass/{}", class_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a transit class
+    pub async fn update_transit_class(
+        &mut self,
+        class_id: &str,
+        class: &TransitClass,
+    ) -> Result<TransitClass> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/transitClass/{}", class_id),
+            Some(class),
+        )
+        .await
+    }
+
+    /// Create a transit object
+    pub async fn create_transit_object(&mut self, ticket: &TransitObject) -> Result<TransitObject> {
+        self.request(reqwest::Method::POST, "/transitObject", Some(ticket))
+            .await
+    }
+
+    /// Get a transit object
+    pub async fn get_transit_object(&mut self, object_id: &str) -> Result<TransitObject> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/transitObject/{}", object_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a transit object
+    pub async fn update_transit_object(
+        &mut self,
+        object_id: &str,
+        ticket: &TransitObject,
+    ) -> Result<TransitObject> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/transitObject/{}", object_id),
+            Some(ticket),
+        )
+        .await
+    }
+
+    /// Create a flight class
+    pub async fn create_flight_class(&mut self, class: &FlightClass) -> Result<FlightClass> {
+        self.request(reqwest::Method::POST, "/flightClass", Some(class))
+            .await
+    }
+
+    /// Get a flight class
+    pub async fn get_flight_class(&mut self, class_id: &str) -> Result<FlightClass> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/flightClass/{}", class_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a flight class
+    pub async fn update_flight_class(
+        &mut self,
+        class_id: &str,
+        class: &FlightClass,
+    ) -> Result<FlightClass> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/flightClass/{}", class_id),
+            Some(class),
+        )
+        .await
+    }
+
+    /// Create a flight object (boarding pass)
+    pub async fn create_flight_object(
+        &mut self,
+        boarding_pass: &FlightObject,
+    ) -> Result<FlightObject> {
+        self.request(reqwest::Method::POST, "/flightObject", Some(boarding_pass))
+            .await
+    }
+
+    /// Get a flight object
+    pub async fn get_flight_object(&mut self, object_id: &str) -> Result<FlightObject> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/flightObject/{}", object_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update a flight object
+    pub async fn update_flight_object(
+        &mut self,
+        object_id: &str,
+        boarding_pass: &FlightObject,
+    ) -> Result<FlightObject> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/flightObject/{}", object_id),
+            Some(boarding_pass),
+        )
+        .await
+    }
+
+    /// Set the balance of a gift card object
+    ///
+    /// Only the balance and its update time are patched. With `notify`, the
+    /// holder gets a notification about the new balance.
+    pub async fn update_gift_card_balance(
+        &mut self,
+        object_id: &str,
+        balance: &Money,
+        notify: bool,
+    ) -> Result<GiftCardObject> {
+        let mut patch = serde_json::json!({
+            "balance": balance,
+            "balanceUpdateTime": { "date": chrono::Utc::now().to_rfc3339() },
+        });
+        if notify {
+            patch["notifyPreference"] = Value::from(NOTIFY_ON_UPDATE);
+        }
+
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("/giftCardObject/{}", object_id),
+            Some(&patch),
+        )
+        .await
+    }
+
+    /// Add `delta` (possibly negative) to the points balance of a loyalty object
+    ///
+    /// The Wallet API has no conditional writes, so the object is read back
+    /// after the PATCH. An update undone by a concurrent write is retried a
+    /// few times; one overtaken by another change fails with
+    /// [`PorterError::ConcurrentModification`] rather than risk applying
+    /// `delta` twice.
+    pub async fn add_points(&mut self, object_id: &str, delta: i32) -> Result<LoyaltyObject> {
+        self.update_points(object_id, |current| {
+            current.checked_add(delta).ok_or_else(|| {
+                PorterError::ValidationError(format!(
+                    "Adding {} points to {} overflows",
+                    delta, current
+                ))
+            })
+        })
+        .await
+    }
+
+    /// Set the points balance of a loyalty object
+    pub async fn set_points(&mut self, object_id: &str, value: i32) -> Result<LoyaltyObject> {
+        self.update_points(object_id, |_| Ok(value)).await
+    }
+
+    /// Set the points balance only if it is currently `expected`
+    ///
+    /// Fails with [`PorterError::ConcurrentModification`] if the balance is
+    /// something else when read, or changes while it is written.
+    pub async fn set_points_if(
+        &mut self,
+        object_id: &str,
+        expected: i32,
+        value: i32,
+    ) -> Result<LoyaltyObject> {
+        self.update_points(object_id, |current| {
+            if current != expected {
+                return Err(PorterError::ConcurrentModification(format!(
+                    "Points of {} changed from {} to {}",
+                    object_id, expected, current
+                )));
+            }
+            Ok(value)
+        })
+        .await
+    }
+
+    /// Compare-then-write the points balance of a loyalty object
+    ///
+    /// The Wallet API has no conditional writes, so the object is read
+    /// again after the PATCH to check the balance is the one written. If
+    /// the write was undone by a concurrent one (the balance is back to the
+    /// value read), the update is retried, up to [`POINTS_ATTEMPTS`] times.
+    /// If the balance is anything else, another writer changed it after
+    /// this one and retrying could apply the change twice, so it fails with
+    /// [`PorterError::ConcurrentModification`].
+    async fn update_points(
+        &mut self,
+        object_id: &str,
+        target: impl Fn(i32) -> Result<i32>,
+    ) -> Result<LoyaltyObject> {
+        for _ in 0..POINTS_ATTEMPTS {
+            let object = self.get_loyalty_object(object_id).await?;
+            let current = points_balance(&object);
+            let value = target(current)?;
+            self.write_points(object_id, object, value).await?;
+
+            let written = self.get_loyalty_object(object_id).await?;
+            let balance = points_balance(&written);
+            if balance == value {
+                return Ok(written);
+            }
+            if balance != current {
+                return Err(PorterError::ConcurrentModification(format!(
+                    "Points of {} changed to {} while writing {}",
+                    object_id, balance, value
+                )));
+            }
+        }
+        Err(PorterError::ConcurrentModification(format!(
+            "Points of {} were overwritten {} times",
+            object_id, POINTS_ATTEMPTS
+        )))
+    }
+
+    /// PATCH only the points of a loyalty object, keeping its label
+    async fn write_points(
+        &mut self,
+        object_id: &str,
+        object: LoyaltyObject,
+        value: i32,
+    ) -> Result<LoyaltyObject> {
+        let mut points = object.loyalty_points.unwrap_or(LoyaltyPoints {
+            label: "Points".to_string(),
+            balance: None,
+        });
+        points.balance = Some(LoyaltyPointsBalance {
+            string: None,
+            int: Some(value),
+            double: None,
+        });
+
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("/loyaltyObject/{}", object_id),
+            Some(&serde_json::json!({ "loyaltyPoints": points })),
+        )
+        .await
+    }
+
+    /// Recompute the expiry countdown field of a generic object
+    ///
+    /// Passes built with `PassBuilder::expiry_countdown` show how many days
+    /// they remain valid; call this periodically to keep that field current.
+    /// Nothing is written when the countdown has not changed.
+    pub async fn refresh_expiry_countdown(&mut self, object_id: &str) -> Result<bool> {
+        let now = chrono::Utc::now();
+        let path = format!("/genericObject/{}", object_id);
+        let raw: Value = self
+            .request(reqwest::Method::GET, &path, None::<&()>)
+            .await?;
+        let mut pass = Pass::from(&serde_json::from_value::<GenericObject>(raw.clone())?);
+        let before = serde_json::to_value(GenericObject::from(&pass))?;
+        if !pass.refresh_expiry_countdown(now) {
+            return Ok(false);
+        }
+        let after = serde_json::to_value(GenericObject::from(&pass))?;
+        let _: GenericObject = self.put_changes(&path, raw, &before, &after).await?;
+        Ok(true)
+    }
+
+    /// Move a flight to a new departure gate and notify holders
+    ///
+    /// Updates the origin gate of the class, which notifies everyone holding
+    /// a Google Wallet boarding pass for it, and adds a message describing
+    /// the change. Apple boarding passes are served by your web service:
+    /// update them there, then notify their devices with
+    /// [`ApnsClient::push_updates`](crate::apple::ApnsClient::push_updates).
+    pub async fn update_gate(&mut self, class_id: &str, new_gate: &str) -> Result<FlightClass> {
+        let class = self
+            .modify(
+                &format!("/flightClass/{}", class_id),
+                |class: &mut FlightClass| {
+                    class.origin.get_or_insert_with(Default::default).gate =
+                        Some(new_gate.to_string());
+                    class.notify_preference = Some(NOTIFY_ON_UPDATE.to_string());
+                },
+            )
+            .await?;
+
+        self.post_change_message(
+            &format!("/flightClass/{}", class_id),
+            "Gate change",
+            &format!("Your flight now departs from gate {}.", new_gate),
+        )
+        .await?;
+        Ok(class)
+    }
+
+    /// Record a new estimated departure time and notify holders
+    ///
+    /// `new_departure` is local time at the origin airport. The scheduled
+    /// departure is kept so Google Wallet shows the flight as delayed. Like
+    /// [`update_gate`](Self::update_gate), this notifies Google Wallet
+    /// holders only.
+    pub async fn delay_flight(
+        &mut self,
+        class_id: &str,
+        new_departure: chrono::NaiveDateTime,
+    ) -> Result<FlightClass> {
+        let class = self
+            .modify(
+                &format!("/flightClass/{}", class_id),
+                |class: &mut FlightClass| {
+                    class.local_estimated_or_actual_departure_date_time =
+                        Some(new_departure.format(LOCAL_DATE_TIME_FORMAT).to_string());
+                    class.notify_preference = Some(NOTIFY_ON_UPDATE.to_string());
+                },
+            )
+            .await?;
+
+        self.post_change_message(
+            &format!("/flightClass/{}", class_id),
+            "Flight delayed",
+            &format!(
+                "Your flight is now expected to depart at {}.",
+                new_departure.format("%H:%M on %b %-d")
+            ),
+        )
+        .await?;
+        Ok(class)
+    }
+
+    /// Move a transit ticket to a new boarding platform and notify the holder
+    ///
+    /// Google Wallet holders are notified through notifyPreference and a
+    /// change message. With `apple`, the devices holding the Apple version of
+    /// the ticket are notified once the Google object is written; update the
+    /// pass your web service serves, with a change message on its platform
+    /// field, before calling this.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use porter::apple::{ApnsClient, ApplePush, RegistrationStore};
+    ///
+    /// # async fn example(
+    /// #     client: &mut porter::google::GoogleWalletClient,
+    /// #     registrations: &dyn RegistrationStore,
+    /// #     apns: &ApnsClient,
+    /// # ) -> porter::error::Result<()> {
+    /// let update = client
+    ///     .update_platform(
+    ///         "issuer.ticket_001",
+    ///         "4",
+    ///         Some(ApplePush {
+    ///             pass_type_identifier: "pass.com.example.transit",
+    ///             serial_numbers: &["ticket_001"],
+    ///             registrations,
+    ///             apns,
+    ///         }),
+    ///     )
+    ///     .await?;
+    /// assert!(update.apple.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn update_platform(
+        &mut self,
+        object_id: &str,
+        new_platform: &str,
+        apple: Option<ApplePush<'_>>,
+    ) -> Result<HolderUpdate<TransitObject>> {
+        let object = self
+            .modify(
+                &format!("/transitObject/{}", object_id),
+                |object: &mut TransitObject| {
+                    object
+                        .ticket_leg
+                        .get_or_insert_with(Default::default)
+                        .platform = Some(new_platform.to_string());
+                    object.notify_preference = Some(NOTIFY_ON_UPDATE.to_string());
+                },
+            )
+            .await?;
+
+        self.post_change_message(
+            &format!("/transitObject/{}", object_id),
+            "Platform change",
+            &format!("Your service now departs from platform {}.", new_platform),
+        )
+        .await?;
+        HolderUpdate::notify(object, apple).await
+    }
+
+    /// Record a new departure time on a transit ticket and notify the holder
+    ///
+    /// With `apple`, Apple devices are notified too, as with
+    /// [`update_platform`](Self::update_platform).
+    pub async fn update_departure(
+        &mut self,
+        object_id: &str,
+        new_departure: chrono::NaiveDateTime,
+        apple: Option<ApplePush<'_>>,
+    ) -> Result<HolderUpdate<TransitObject>> {
+        let object = self
+            .modify(
+                &format!("/transitObject/{}", object_id),
+                |object: &mut TransitObject| {
+                    object
+                        .ticket_leg
+                        .get_or_insert_with(Default::default)
+                        .departure_date_time =
+                        Some(new_departure.format(LOCAL_DATE_TIME_FORMAT).to_string());
+                    object.notify_preference = Some(NOTIFY_ON_UPDATE.to_string());
+                },
+            )
+            .await?;
+
+        self.post_change_message(
+            &format!("/transitObject/{}", object_id),
+            "Departure time change",
+            &format!(
+                "Your service now departs at {}.",
+                new_departure.format("%H:%M on %b %-d")
+            ),
+        )
+        .await?;
+        HolderUpdate::notify(object, apple).await
+    }
+
+    /// Mark a transit ticket as cancelled and notify the holder
+    ///
+    /// The ticket is shown with a "Cancelled" status and made inactive so it
+    /// moves out of the holder's active passes. `reason` is used as the body
+    /// of the change message. With `apple`, Apple devices are notified too,
+    /// as with [`update_platform`](Self::update_platform); void the Apple
+    /// pass or give it a cancelled status first.
+    pub async fn cancel_trip(
+        &mut self,
+        object_id: &str,
+        reason: Option<&str>,
+        apple: Option<ApplePush<'_>>,
+    ) -> Result<HolderUpdate<TransitObject>> {
+        let object = self
+            .modify(
+                &format!("/transitObject/{}", object_id),
+                |object: &mut TransitObject| {
+                    object.state = Some(State::Inactive);
+                    object.custom_ticket_status = Some(LocalizedString {
+                        default_value: Some(TranslatedString {
+                            language: DEFAULT_LANGUAGE.to_string(),
+                            value: "Cancelled".to_string(),
+                        }),
+                        translated_values: None,
+                    });
+                    object.notify_preference = Some(NOTIFY_ON_UPDATE.to_string());
+                },
+            )
+            .await?;
+
+        self.post_change_message(
+            &format!("/transitObject/{}", object_id),
+            "Service cancelled",
+            reason.unwrap_or("This service has been cancelled."),
+        )
+        .await?;
+        HolderUpdate::notify(object, apple).await
+    }
+
+    /// Move an event ticket to a new seat and notify the holder
+    ///
+    /// Replaces the ticket's seat info, sets notifyPreference so the change
+    /// is pushed to the holder's device, and adds a message with the new seat.
+    /// With `apple`, the devices holding the Apple ticket are notified once
+    /// the Google object is written; update the pass your web service serves
+    /// first, for example with [`ApplePass::event_ticket`](crate::apple::ApplePass::event_ticket)
+    /// and a change message on the seat fields.
+    pub async fn reassign_seat(
+        &mut self,
+        object_id: &str,
+        new_seat: EventSeat,
+        apple: Option<ApplePush<'_>>,
+    ) -> Result<HolderUpdate<EventTicketObject>> {
+        let description = describe_seat(&new_seat);
+        let ticket = self
+            .modify_event_ticket(object_id, |ticket| {
+                ticket.seat_info = Some(new_seat);
+                ticket.notify_preference = Some(NOTIFY_ON_UPDATE.to_string());
+            })
+            .await?;
+
+        self.post_change_message(
+            &format!("/eventTicketObject/{}", object_id),
+            "Seat change",
+            &format!("Your seat has changed to {}.", description),
+        )
+        .await?;
+        HolderUpdate::notify(ticket, apple).await
+    }
+
+    /// Add a message describing a change to a class or object at `path`
+    async fn post_change_message(&mut self, path: &str, header: &str, body: &str) -> Result<()> {
+        let request = AddMessageRequest {
+            message: change_message(header, body),
+        };
+        let _: Value = self.add_message(path, &request).await?;
+        Ok(())
+    }
+
+    /// Fetch a generic object, change it, and PUT it back without losing unmodeled data
+    ///
+    /// Only fields the closure changed are written into the fetched document,
+    /// so server-side data Porter does not model (info modules, value added
+    /// modules, ...) survives the update.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example(client: &mut porter::google::GoogleWalletClient) -> porter::error::Result<()> {
+    /// client
+    ///     .modify_generic_object("issuer.pass_001", |object| {
+    ///         object.hex_background_color = Some("#000000".to_string());
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn modify_generic_object(
+        &mut self,
+        object_id: &str,
+        f: impl FnOnce(&mut GenericObject),
+    ) -> Result<GenericObject> {
+        self.modify(&format!("/genericObject/{}", object_id), f)
+            .await
+    }
+
+    /// Change a generic object through the unified [`Pass`] model
+    ///
+    /// Like [`modify_generic_object`](Self::modify_generic_object), only the
+    /// fields affected by the change are written back, so data the unified
+    /// model does not carry is kept.
+    pub async fn modify_pass(
+        &mut self,
+        object_id: &str,
+        f: impl FnOnce(&mut Pass),
+    ) -> Result<GenericObject> {
+        self.modify_raw(&format!("/genericObject/{}", object_id), |raw| {
+            let mut pass = Pass::from(&serde_json::from_value::<GenericObject>(raw)?);
+            let before = serde_json::to_value(GenericObject::from(&pass))?;
+            f(&mut pass);
+            let after = serde_json::to_value(GenericObject::from(&pass))?;
+            Ok((before, after))
+        })
+        .await
+    }
+
+    /// Fetch an event ticket object, change it, and PUT it back without losing unmodeled data
+    pub async fn modify_event_ticket(
+        &mut self,
+        object_id: &str,
+        f: impl FnOnce(&mut EventTicketObject),
+    ) -> Result<EventTicketObject> {
+        self.modify(&format!("/eventTicketObject/{}", object_id), f)
+            .await
+    }
+
+    /// Fetch a loyalty object, change it, and PUT it back without losing unmodeled data
+    pub async fn modify_loyalty_object(
+        &mut self,
+        object_id: &str,
+        f: impl FnOnce(&mut LoyaltyObject),
+    ) -> Result<LoyaltyObject> {
+        self.modify(&format!("/loyaltyObject/{}", object_id), f)
+            .await
+    }
+
+    /// Apply a change to the modeled form of a resource and PUT the merged document
+    async fn modify<T: Serialize + DeserializeOwned>(
+        &mut self,
+        path: &str,
+        f: impl FnOnce(&mut T),
+    ) -> Result<T> {
+        self.modify_raw(path, |raw| {
+            let mut object: T = serde_json::from_value(raw)?;
+            let before = serde_json::to_value(&object)?;
+            f(&mut object);
+            Ok((before, serde_json::to_value(&object)?))
+        })
+        .await
+    }
+
+    /// GET a resource, merge the difference between the `(before, after)`
+    /// documents produced by `change` into it, and PUT the result
+    async fn modify_raw<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        change: impl FnOnce(Value) -> Result<(Value, Value)>,
+    ) -> Result<T> {
+        let raw: Value = self
+            .request(reqwest::Method::GET, path, None::<&()>)
+            .await?;
+        let (before, after) = change(raw.clone())?;
+        self.put_changes(path, raw, &before, &after).await
+    }
+
+    /// Merge the difference between `before` and `after` into the fetched
+    /// `raw` resource, and PUT the result
+    async fn put_changes<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        mut raw: Value,
+        before: &Value,
+        after: &Value,
+    ) -> Result<T> {
+        merge_changes(&mut raw, before, after);
+        self.request(reqwest::Method::PUT, path, Some(&raw)).await
+    }
+
+    /// Get an issuer account
+    pub async fn get_issuer(&mut self, issuer_id: &str) -> Result<Issuer> {
+        self.request(
+            reqwest::Method::GET,
+            &format!("/issuer/{}", issuer_id),
+            None::<&()>,
+        )
+        .await
+    }
+
+    /// Update an issuer account
+    pub async fn update_issuer(&mut self, issuer_id: &str, issuer: &Issuer) -> Result<Issuer> {
+        self.request(
+            reqwest::Method::PUT,
+            &format!("/issuer/{}", issuer_id),
+            Some(issuer),
+        )
+        .await
+    }
+
+    /// Patch an issuer account (partial update)
+    pub async fn patch_issuer(&mut self, issuer_id: &str, issuer: &Issuer) -> Result<Issuer> {
+        self.request(
+            reqwest::Method::PATCH,
+            &format!("/issuer/{}", issuer_id),
+            Some(issuer),
+        )
+        .await
+    }
+
+    /// Upload the registry's active Smart Tap keys to the issuer
+    ///
+    /// Replaces the issuer's authentication keys with every non-retired
+    /// version so terminals can redeem passes issued under older keys.
+    pub async fn upload_smart_tap_keys(
+        &mut self,
+        issuer_id: &str,
+        keys: &SmartTapKeyRegistry,
+    ) -> Result<Issuer> {
+        let current = self.get_issuer(issuer_id).await?;
+        let merchant_id = current
+            .smart_tap_merchant_data
+            .and_then(|data| data.smart_tap_merchant_id);
+
+        let patch = Issuer {
+            smart_tap_merchant_data: Some(SmartTapMerchantData {
+                smart_tap_merchant_id: merchant_id,
+                authentication_keys: Some(keys.active_keys()),
+            }),
+            ..Default::default()
+        };
+
+        self.patch_issuer(issuer_id, &patch).await
+    }
+
+    /// Expire a holder's passes and remove their personal data
+    ///
+    /// Each object is expired and its header, subheader, barcode and text
+    /// modules are cleared with a PATCH of only those fields, so the rest of
+    /// the object and concurrent edits to it are left alone. With `apple`,
+    /// the holder's Apple passes are voided, their devices notified and
+    /// unregistered as well; see [`ApplePurge::run`]. Failures are collected
+    /// in the report rather than aborting the purge.
+    pub async fn purge_holder(
+        &mut self,
+        pass_ids: &[&str],
+        apple: Option<ApplePurge<'_>>,
+    ) -> PurgeReport {
+        let mut report = GooglePurgeReport::default();
+
+        let mask = holder_data_mask();
+        for pass_id in pass_ids {
+            let mut object = GenericObject {
+                id: pass_id.to_string(),
+                ..Default::default()
+            };
+            strip_holder_data(&mut object);
+            let result = self
+                .patch_generic_object_masked(pass_id, &object, &mask)
+                .await
+                .map(|_| ());
+
+            match result {
+                Ok(()) => report.purged.push(pass_id.to_string()),
+                Err(PorterError::ApiError { status: 404, .. }) => {
+                    report.not_found.push(pass_id.to_string())
+                }
+                Err(e) => report.failed.push(PurgeFailure {
+                    pass_id: pass_id.to_string(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        let apple = match apple {
+            Some(apple) => apple.run().await,
+            None => Default::default(),
+        };
+        PurgeReport {
+            google: report,
+            apple,
+            completed_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Expire every pass of a class that matches `filter`
+    ///
+    /// Pages through all objects of the class and expires the matching ones
+    /// that are not already expired, with up to `concurrency` (at least one)
+    /// requests in flight. Only `state` is patched, so concurrent edits to
+    /// other fields are not overwritten. Failures are collected in the
+    /// report rather than aborting the run, and the passes of each page are
+    /// reported in the order their requests finish.
+    pub async fn expire_all(
+        &mut self,
+        class_id: &str,
+        filter: impl Fn(&GenericObject) -> bool,
+        concurrency: usize,
+    ) -> Result<ExpireReport> {
+        let mut report = ExpireReport {
+            expired: vec![],
+            skipped: vec![],
+            failed: vec![],
+            completed_at: chrono::Utc::now(),
+        };
+
+        let mut page_token = None;
+        loop {
+            let page = self
+                .list_generic_objects_page(class_id, None, page_token.as_deref())
+                .await?;
+
+            let mut matching = vec![];
+            for object in page.resources.unwrap_or_default() {
+                if object.state == Some(State::Expired) || !filter(&object) {
+                    report.skipped.push(object.id);
+                } else {
+                    matching.push(object.id);
+                }
+            }
+
+            // Each request runs on a clone, which shares the token and cache
+            let mut client = self.clone();
+            client.dry_run = client.dry_run.as_ref().map(|_| vec![]);
+            let outcomes: Vec<_> = stream::iter(matching)
+                .map(|object_id| {
+                    let mut client = client.clone();
+                    async move {
+                        let result = client.expire_generic_object(&object_id).await;
+                        (object_id, result, client.dry_run)
+                    }
+                })
+                .buffer_unordered(concurrency.max(1))
+                .collect()
+                .await;
+
+            for (object_id, result, dry_run) in outcomes {
+                if let (Some(log), Some(requests)) = (&mut self.dry_run, dry_run) {
+                    log.extend(requests);
+                }
+                match result {
+                    Ok(()) => report.expired.push(object_id),
+                    Err(e) => report.failed.push(PurgeFailure {
+                        pass_id: object_id,
+                        error: e.to_string(),
+                    }),
+                }
+            }
+
+            page_token = page.pagination.and_then(|p| p.next_page_token);
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        report.completed_at = chrono::Utc::now();
+        Ok(report)
+    }
+
+    /// PATCH only the state of a generic object to expired
+    async fn expire_generic_object(&mut self, object_id: &str) -> Result<()> {
+        let patch = serde_json::json!({ "state": "EXPIRED" });
+        let _: Value = self
+            .request(
+                reqwest::Method::PATCH,
+                &format!("/genericObject/{}", object_id),
+                Some(&patch),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Transfer a pass to a new holder
+    ///
+    /// The original object's content is copied to a new object (with
+    /// `new_object_id`, or a random id under the issuer), the new object is
+    /// created, and only then is the original expired. Only its `state` is
+    /// patched, so nothing else on the original is overwritten. A save URL
+    /// for the recipient is returned along with the new object.
+    pub async fn transfer_pass(
+        &mut self,
+        object_id: &str,
+        new_object_id: Option<&str>,
+    ) -> Result<PassTransfer> {
+        let original = self.fetch_generic_object(object_id).await?;
+        if original.state == Some(State::Expired) {
+            return Err(PorterError::ValidationError(format!(
+                "Pass {} has already expired and cannot be transferred",
+                object_id
+            )));
+        }
+
+        let mut object = original.clone();
+        object.id = match new_object_id {
+            Some(id) => id.to_string(),
+            None => format!("{}.{}", self.config.issuer_id, random_id()?),
+        };
+        object.state = Some(State::Active);
+        let object = self.create_generic_object(&object).await?;
+
+        if let Err(e) = self.expire_generic_object(object_id).await {
+            return Err(PorterError::TransferIncomplete {
+                new_object_id: object.id,
+                message: e.to_string(),
+            });
+        }
+
+        let save_url = self.generate_save_url(&object).await?;
+
+        Ok(PassTransfer {
+            original_id: object_id.to_string(),
+            object,
+            save_url,
+        })
+    }
+
+    /// Generate a signed save-to-wallet JWT for the given objects, valid for `ttl`
+    fn generate_pass_jwt(
+        &self,
+        objects: &JwtObjectPayload,
+        ttl: Option<Duration>,
+    ) -> Result<String> {
+        let (signer, claims) = self.save_claims(objects, ttl, unix_now()?)?;
+        signer.sign(&claims)
+    }
+
+    /// Claims of a save JWT for `objects`, and the signer for their issuer
+    fn save_claims<'a>(
+        &'a self,
+        objects: &'a JwtObjectPayload,
+        ttl: Option<Duration>,
+        now: i64,
+    ) -> Result<(Arc<JwtSigner>, JwtPayload<'a>)> {
+        if objects.is_empty() {
+            return Err(PorterError::ValidationError(
+                "Save link payload contains no objects".to_string(),
+            ));
+        }
+
+        for origin in &self.save_link_origins {
+            validate_origin(origin)?;
+        }
+
+        let config = first_object_id(objects)
+            .and_then(|id| self.issuer_for(id))
+            .unwrap_or(&self.config);
+
+        let claims = JwtPayload {
+            iss: Cow::Borrowed(&config.service_account_email),
+            aud: Cow::Borrowed("google"),
+            typ: Cow::Borrowed("savetowallet"),
+            iat: now,
+            exp: ttl.map(|ttl| now.saturating_add(ttl.as_secs() as i64)),
+            origins: (!self.save_link_origins.is_empty())
+                .then_some(Cow::Borrowed(&self.save_link_origins[..])),
+            payload: Cow::Borrowed(objects),
+        };
+        Ok((self.signer(config)?, claims))
+    }
+
+    /// Sign save URLs for many payloads without calling the API
+    ///
+    /// Each payload becomes a `https://pay.google.com/gp/v/save/<jwt>` link
+    /// carrying its objects, which Google inserts when the link is opened.
+    /// Signing is spread over the available cores, each signing key is
+    /// parsed once, and serialization buffers are reused, so this suits
+    /// issuing hundreds of thousands of links for an on-sale. Links are
+    /// returned in payload order, expire after the
+    /// [`with_save_link_ttl`](Self::with_save_link_ttl) lifetime if set, and
+    /// are not shortened.
+    pub fn sign_save_urls(&self, payloads: &[JwtObjectPayload]) -> Result<Vec<String>> {
+        let now = unix_now()?;
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = payloads.len() / threads + 1;
+
+        let sign_chunk = |chunk: &[JwtObjectPayload]| -> Result<Vec<String>> {
+            let mut buffers = SignBuffers::default();
+            chunk
+                .iter()
+                .map(|objects| {
+                    let (signer, claims) = self.save_claims(objects, self.save_link_ttl, now)?;
+                    let jwt = signer.sign_with(&claims, &mut buffers)?;
+                    Ok(format!("{}{}", SAVE_URL_PREFIX, jwt))
+                })
+                .collect()
+        };
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = payloads
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || sign_chunk(chunk)))
+                .collect();
+            let mut urls = Vec::with_capacity(payloads.len());
+            for handle in handles {
+                let chunk = handle.join().map_err(|_| {
+                    PorterError::SignatureError("Signing thread panicked".to_string())
+                })?;
+                urls.extend(chunk?);
+            }
+            Ok(urls)
+        })
+    }
+
+    /// Sign a save link for `objects` locally, without calling the API
+    ///
+    /// The objects are embedded in the returned
+    /// `https://pay.google.com/gp/v/save/<jwt>` link. Full objects are
+    /// inserted by Google when the link is opened. Objects built with
+    /// `reference` carry only an id and class. They point at objects that
+    /// were already inserted, and keep the link short enough for browsers.
+    /// The link expires after the
+    /// [`with_save_link_ttl`](Self::with_save_link_ttl) lifetime if set, and
+    /// is not shortened.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use porter::google::{GenericObject, GoogleWalletClient, JwtObjectPayload};
+    ///
+    /// # fn example(client: &GoogleWalletClient) -> porter::error::Result<()> {
+    /// let link = client.generate_save_link(&JwtObjectPayload {
+    ///     generic_objects: Some(vec![GenericObject::reference(
+    ///         "issuer_id.pass_001",
+    ///         "issuer_id.class_001",
+    ///     )]),
+    ///     ..Default::default()
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_save_link(&self, objects: &JwtObjectPayload) -> Result<String> {
+        let jwt = self.generate_pass_jwt(objects, self.save_link_ttl)?;
+        Ok(format!("{}{}", SAVE_URL_PREFIX, jwt))
+    }
+
+    /// Generate a save URL for a generic pass object
+    ///
+    /// This creates a JWT and calls the Google Wallet API to get a save URL
+    /// that can be used to add the pass to a user's wallet. If a link
+    /// shortener is configured, the shortened link is returned instead.
+    pub async fn generate_save_url(&mut self, object: &GenericObject) -> Result<String> {
+        self.generate_save_url_for(&JwtObjectPayload {
+            generic_objects: Some(vec![object.clone()]),
+            ..Default::default()
+        })
         .await
     }
 
-    /// Generate a JWT for a pass object
-    fn generate_pass_jwt(&self, objects: &[GenericObject]) -> Result<String> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| PorterError::AuthError(format!("Time error: {}", e)))?
-            .as_secs() as i64;
+    /// Generate a single save URL for several objects, possibly of mixed types
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use porter::google::{EventTicketObject, GoogleWalletClient, JwtObjectPayload};
+    ///
+    /// # async fn example(client: &mut GoogleWalletClient, tickets: Vec<EventTicketObject>) -> porter::error::Result<()> {
+    /// let save_url = client
+    ///     .generate_save_url_for(&JwtObjectPayload {
+    ///         event_ticket_objects: Some(tickets),
+    ///         ..Default::default()
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn generate_save_url_for(&mut self, objects: &JwtObjectPayload) -> Result<String> {
+        self.save_url(objects, self.save_link_ttl).await
+    }
+
+    /// Generate a save URL that expires `ttl` from now
+    ///
+    /// Overrides the lifetime set with
+    /// [`with_save_link_ttl`](Self::with_save_link_ttl) for this link only.
+    pub async fn generate_save_url_expiring(
+        &mut self,
+        objects: &JwtObjectPayload,
+        ttl: Duration,
+    ) -> Result<String> {
+        self.save_url(objects, Some(ttl)).await
+    }
+
+    async fn save_url(
+        &mut self,
+        objects: &JwtObjectPayload,
+        ttl: Option<Duration>,
+    ) -> Result<String> {
+        let jwt = self.generate_pass_jwt(objects, ttl)?;
+
+        let jwt_resource = JwtResource { jwt };
+
+        let response: JwtInsertResponse = self
+            .request(reqwest::Method::POST, "/jwt", Some(&jwt_resource))
+            .await?;
+
+        let save_uri = response.save_uri.ok_or_else(|| PorterError::ApiError {
+            status: 500,
+            message: "No save URI returned from API".to_string(),
+        })?;
+
+        match &self.link_shortener {
+            Some(shortener) => shortener.shorten(&save_uri).await,
+            None => Ok(save_uri),
+        }
+    }
+}
+
+/// Seconds since the Unix epoch
+fn unix_now() -> Result<i64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| PorterError::AuthError(format!("Time error: {}", e)))?
+        .as_secs() as i64)
+}
+
+/// Check that `origin` is a bare http(s) origin, as the `origins` claim requires
+fn validate_origin(origin: &str) -> Result<()> {
+    let valid = reqwest::Url::parse(origin).is_ok_and(|url| {
+        matches!(url.scheme(), "http" | "https")
+            && url.host().is_some()
+            && url.path() == "/"
+            && url.query().is_none()
+            && url.fragment().is_none()
+            && !origin.ends_with('/')
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(PorterError::ValidationError(format!(
+            "Invalid save link origin {:?}, expected a scheme and host such as https://example.com",
+            origin
+        )))
+    }
+}
+
+/// Id of the first object in a save payload
+fn first_object_id(objects: &JwtObjectPayload) -> Option<&str> {
+    let generic = objects.generic_objects.iter().flatten().map(|o| &o.id);
+    let tickets = objects.event_ticket_objects.iter().flatten().map(|o| &o.id);
+    let loyalty = objects.loyalty_objects.iter().flatten().map(|o| &o.id);
+    let offers = objects.offer_objects.iter().flatten().map(|o| &o.id);
+    let gift_cards = objects.gift_card_objects.iter().flatten().map(|o| &o.id);
+    let transit = objects.transit_objects.iter().flatten().map(|o| &o.id);
+    let flights = objects.flight_objects.iter().flatten().map(|o| &o.id);
+    generic
+        .chain(tickets)
+        .chain(loyalty)
+        .chain(offers)
+        .chain(gift_cards)
+        .chain(transit)
+        .chain(flights)
+        .next()
+        .map(String::as_str)
+}
 
-        let payload = JwtPayload {
-            iss: self.config.service_account_email.clone(),
-            aud: "google".to_string(),
-            typ: "savetowallet".to_string(),
-            iat: now,
-            origins: None,
-            payload: JwtObjectPayload {
-                generic_objects: Some(objects.to_vec()),
-                event_ticket_objects: None,
-                loyalty_objects: None,
-            },
-        };
+/// Write the fields that differ between `before` and `after` into `raw`
+///
+/// Objects are merged key by key so unmodeled keys in `raw` are kept; any
+/// other changed value (including arrays) replaces the raw value.
+fn merge_changes(raw: &mut Value, before: &Value, after: &Value) {
+    if before == after {
+        return;
+    }
 
-        let key = EncodingKey::from_rsa_pem(self.config.private_key.as_bytes())?;
-        let token = encode(&Header::new(Algorithm::RS256), &payload, &key)?;
+    let (Value::Object(raw_map), Value::Object(before_map), Value::Object(after_map)) =
+        (&mut *raw, before, after)
+    else {
+        *raw = after.clone();
+        return;
+    };
 
-        Ok(token)
+    for key in before_map.keys() {
+        if !after_map.contains_key(key) {
+            raw_map.remove(key);
+        }
+    }
+    for (key, new) in after_map {
+        match (before_map.get(key), raw_map.get_mut(key)) {
+            (Some(old), _) if old == new => {}
+            (Some(old), Some(existing)) => merge_changes(existing, old, new),
+            _ => {
+                raw_map.insert(key.clone(), new.clone());
+            }
+        }
     }
+}
 
-    /// Generate a save URL for a generic pass object
-    ///
-    /// This creates a JWT and calls the Google Wallet API to get a save URL
-    /// that can be used to add the pass to a user's wallet.
-    pub async fn generate_save_url(&mut self, object: &GenericObject) -> Result<String> {
-        let jwt = self.generate_pass_jwt(std::slice::from_ref(object))?;
+/// Top-level fields of `desired` that differ from `remote`, ignoring reviewStatus
+fn class_drift(remote: &Value, desired: &Value) -> serde_json::Map<String, Value> {
+    let Value::Object(desired) = desired else {
+        return Default::default();
+    };
+    desired
+        .iter()
+        .filter(|(key, _)| key.as_str() != "reviewStatus")
+        .filter(|(key, value)| !remote.get(key.as_str()).is_some_and(|r| contains(r, value)))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
 
-        let jwt_resource = JwtResource { jwt };
+/// Whether `remote` has every value in `desired`, allowing extra object keys
+fn contains(remote: &Value, desired: &Value) -> bool {
+    match (remote, desired) {
+        (Value::Object(remote), Value::Object(desired)) => desired
+            .iter()
+            .all(|(key, value)| remote.get(key).is_some_and(|r| contains(r, value))),
+        (Value::Array(remote), Value::Array(desired)) => {
+            remote.len() == desired.len() && remote.iter().zip(desired).all(|(r, d)| contains(r, d))
+        }
+        _ => remote == desired,
+    }
+}
 
-        let response: JwtInsertResponse = self
-            .request(reqwest::Method::POST, "/jwt", Some(&jwt_resource))
-            .await?;
+/// Progress through the pages of a list
+struct ListState<T> {
+    /// Objects fetched but not yet yielded
+    buffer: VecDeque<T>,
+    page_token: Option<String>,
+    /// Whether the last page has been fetched
+    finished: bool,
+}
 
-        response.save_uri.ok_or_else(|| PorterError::ApiError {
-            status: 500,
-            message: "No save URI returned from API".to_string(),
-        })
+/// What a list is filtered by: objects by class, classes by issuer
+#[derive(Clone, Copy)]
+enum ListParent<'a> {
+    Class(&'a str),
+    Issuer(&'a str),
+}
+
+/// Path of one page of the resources in `collection`
+fn list_path(
+    collection: &str,
+    parent: ListParent,
+    max_results: Option<u32>,
+    page_token: Option<&str>,
+) -> Result<String> {
+    let max_results = max_results.map(|max| max.to_string());
+    let mut params = vec![match parent {
+        ListParent::Class(class_id) => ("classId", class_id),
+        ListParent::Issuer(issuer_id) => ("issuerId", issuer_id),
+    }];
+    if let Some(max_results) = &max_results {
+        params.push(("maxResults", max_results));
+    }
+    if let Some(token) = page_token {
+        params.push(("token", token));
+    }
+    let url =
+        reqwest::Url::parse_with_params(&format!("https://localhost/{}", collection), &params)
+            .map_err(|e| PorterError::ValidationError(e.to_string()))?;
+    Ok(format!(
+        "{}?{}",
+        url.path(),
+        url.query().unwrap_or_default()
+    ))
+}
+
+/// Random 128-bit hex identifier for minted objects
+fn random_id() -> Result<String> {
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    let mut bytes = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| PorterError::ConfigError("System random source unavailable".to_string()))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Expire an object and clear the fields that carry holder data
+fn strip_holder_data(object: &mut GenericObject) {
+    object.state = Some(State::Expired);
+    object.header = None;
+    object.subheader = None;
+    object.barcode = None;
+    object.text_modules_data = None;
+}
+
+/// The fields [`strip_holder_data`] changes
+fn holder_data_mask() -> FieldMask {
+    FieldMask::new()
+        .field("state")
+        .field("header")
+        .field("subheader")
+        .field("barcode")
+        .field("textModulesData")
+}
+
+/// Work out which audit action (if any) a request represents
+fn audit_action(
+    method: &reqwest::Method,
+    path: &str,
+    submitted: Option<&Value>,
+) -> Option<AuditAction> {
+    if path.starts_with("/jwt") {
+        return None;
+    }
+
+    let expires = submitted
+        .and_then(|body| body.get("state"))
+        .and_then(Value::as_str)
+        .is_some_and(|state| state.eq_ignore_ascii_case("EXPIRED"));
+
+    match *method {
+        reqwest::Method::POST if path.ends_with("/addMessage") => Some(AuditAction::AddMessage),
+        reqwest::Method::POST => Some(AuditAction::Create),
+        reqwest::Method::PUT | reqwest::Method::PATCH if expires => Some(AuditAction::Expire),
+        reqwest::Method::PUT => Some(AuditAction::Update),
+        reqwest::Method::PATCH => Some(AuditAction::Patch),
+        _ => None,
+    }
+}
+
+/// The resource in an addMessage response
+///
+/// The API wraps it as `{"resource": ...}`, while dry runs return it bare.
+fn added_message_resource(mut response: Value) -> Value {
+    match response.get_mut("resource") {
+        Some(resource) => resource.take(),
+        None => response,
     }
 }
 
+/// Resource type and id of a request path such as `/genericObject/{id}/addMessage`
+fn audit_resource(path: &str, response: &Value) -> (String, String) {
+    let path = path.split('?').next().unwrap_or_default();
+    let mut segments = path.trim_start_matches('/').split('/');
+    let resource_type = segments.next().unwrap_or_default().to_string();
+    let resource_id = match segments.next() {
+        Some(id) => id.to_string(),
+        None => response
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    };
+    (resource_type, resource_id)
+}
+
+/// Integer points balance of a loyalty object (0 when unset)
+fn points_balance(object: &LoyaltyObject) -> i32 {
+    object
+        .loyalty_points
+        .as_ref()
+        .and_then(|points| points.balance.as_ref())
+        .and_then(|balance| balance.int)
+        .unwrap_or(0)
+}
+
 /// Trait for pass operations (can be implemented for other platforms)
 #[async_trait]
 pub trait PassClient {
@@ -374,6 +3090,10 @@ pub trait PassClient {
     async fn get_pass(&mut self, pass_id: &str) -> Result<GenericObject>;
     async fn update_pass(&mut self, pass_id: &str, pass: &GenericObject) -> Result<GenericObject>;
     async fn delete_pass(&mut self, pass_id: &str) -> Result<()>;
+    /// Add to the points balance of a loyalty pass, returning the new balance
+    async fn add_points(&mut self, pass_id: &str, delta: i32) -> Result<i32>;
+    /// Set the points balance of a loyalty pass
+    async fn set_points(&mut self, pass_id: &str, value: i32) -> Result<i32>;
 }
 
 #[async_trait]
@@ -392,9 +3112,1198 @@ impl PassClient for GoogleWalletClient {
 
     async fn delete_pass(&mut self, pass_id: &str) -> Result<()> {
         // Google Wallet doesn't support deletion, so we'll mark as expired instead
-        let mut pass = self.get_generic_object(pass_id).await?;
-        pass.state = Some("EXPIRED".to_string());
+        let mut pass = self.fetch_generic_object(pass_id).await?;
+        pass.state = Some(State::Expired);
         self.update_generic_object(pass_id, &pass).await?;
         Ok(())
     }
+
+    async fn add_points(&mut self, pass_id: &str, delta: i32) -> Result<i32> {
+        let object = GoogleWalletClient::add_points(self, pass_id, delta).await?;
+        Ok(points_balance(&object))
+    }
+
+    async fn set_points(&mut self, pass_id: &str, value: i32) -> Result<i32> {
+        let object = GoogleWalletClient::set_points(self, pass_id, value).await?;
+        Ok(points_balance(&object))
+    }
+}
+
+/// Human-readable seat location, such as "section Main Floor, row A, seat 23"
+fn describe_seat(seat: &EventSeat) -> String {
+    [
+        ("section", &seat.section),
+        ("row", &seat.row),
+        ("seat", &seat.seat),
+    ]
+    .iter()
+    .filter_map(|(label, value)| {
+        let value = value.as_ref()?.default_value.as_ref()?;
+        Some(format!("{} {}", label, value.value))
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// A text message telling holders what changed on their pass
+fn change_message(header: &str, body: &str) -> Message {
+    Message {
+        header: Some(header.to_string()),
+        body: Some(body.to_string()),
+        message_type: Some("TEXT".to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{serve, Received};
+    use serde_json::json;
+
+    const TEST_KEY: &str = include_str!("../apple/testdata/pass_key.pem");
+
+    /// A client for the test service account, without a signing key
+    fn test_client() -> GoogleWalletClient {
+        GoogleWalletClient::new(test_config())
+    }
+
+    fn test_config() -> GoogleWalletConfig {
+        GoogleWalletConfig {
+            issuer_id: "issuer".to_string(),
+            service_account_email: "wallet@example.iam.gserviceaccount.com".to_string(),
+            private_key: String::new(),
+        }
+    }
+
+    /// A client with a valid token that talks to a local server answering
+    /// `(method, path)` with `(status, body)`, and 404 for anything else
+    async fn mock_client(routes: Vec<(&str, &str, u16, Value)>) -> (GoogleWalletClient, Received) {
+        let (base, received) = serve(routes).await;
+        let mut client = GoogleWalletClient::new(GoogleWalletConfig {
+            private_key: TEST_KEY.to_string(),
+            ..test_config()
+        });
+        client.api_base = base;
+        cache_token(&client, None);
+        (client, received)
+    }
+
+    /// Give the client a valid token for the test service account and `subject`
+    fn cache_token(client: &GoogleWalletClient, subject: Option<&str>) -> TokenSlot {
+        let slot = TokenSlot::new(tokio::sync::Mutex::new(Some(CachedToken {
+            access_token: "token".to_string(),
+            expiry: SystemTime::now() + Duration::from_secs(3600),
+        })));
+        client.tokens.lock().unwrap().insert(
+            (
+                "wallet@example.iam.gserviceaccount.com".to_string(),
+                subject.map(str::to_string),
+            ),
+            slot.clone(),
+        );
+        slot
+    }
+
+    #[test]
+    fn test_audit_action() {
+        let expired = json!({ "state": "EXPIRED" });
+
+        assert_eq!(
+            audit_action(&reqwest::Method::POST, "/genericObject", None),
+            Some(AuditAction::Create)
+        );
+        assert_eq!(
+            audit_action(&reqwest::Method::PUT, "/genericObject/a", Some(&expired)),
+            Some(AuditAction::Expire)
+        );
+        assert_eq!(
+            audit_action(&reqwest::Method::POST, "/genericObject/a/addMessage", None),
+            Some(AuditAction::AddMessage)
+        );
+        assert_eq!(audit_action(&reqwest::Method::POST, "/jwt", None), None);
+        assert_eq!(
+            audit_action(&reqwest::Method::GET, "/genericObject/a", None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_merge_changes_keeps_unmodeled_fields() {
+        let mut raw = json!({
+            "id": "a",
+            "state": "ACTIVE",
+            "header": { "defaultValue": { "value": "Old" }, "kind": "walletobjects#localizedString" },
+            "barcode": { "type": "QR_CODE", "value": "1" },
+            "rotatingBarcode": { "type": "QR_CODE" },
+            "appLinkData": { "androidAppLinkInfo": {} }
+        });
+        let before = json!({
+            "id": "a",
+            "state": "ACTIVE",
+            "header": { "defaultValue": { "value": "Old" } },
+            "barcode": { "type": "QR_CODE", "value": "1" }
+        });
+        let after = json!({
+            "id": "a",
+            "state": "ACTIVE",
+            "header": { "defaultValue": { "value": "New" } },
+            "hexBackgroundColor": "#000000"
+        });
+
+        merge_changes(&mut raw, &before, &after);
+
+        assert_eq!(
+            raw,
+            json!({
+                "id": "a",
+                "state": "ACTIVE",
+                "header": { "defaultValue": { "value": "New" }, "kind": "walletobjects#localizedString" },
+                "hexBackgroundColor": "#000000",
+                "rotatingBarcode": { "type": "QR_CODE" },
+                "appLinkData": { "androidAppLinkInfo": {} }
+            })
+        );
+    }
+
+    #[test]
+    fn test_change_message() {
+        let message = change_message("Gate change", "Your flight now departs from gate B12.");
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "header": "Gate change",
+                "body": "Your flight now departs from gate B12.",
+                "messageType": "TEXT"
+            })
+        );
+    }
+
+    #[test]
+    fn test_describe_seat() {
+        let value = |value: &str| {
+            Some(LocalizedString {
+                default_value: Some(TranslatedString {
+                    language: "en-US".to_string(),
+                    value: value.to_string(),
+                }),
+                translated_values: None,
+            })
+        };
+        let seat = EventSeat {
+            seat: value("23"),
+            row: value("A"),
+            section: None,
+        };
+        assert_eq!(describe_seat(&seat), "row A, seat 23");
+    }
+
+    #[test]
+    fn test_points_balance() {
+        let mut object = LoyaltyObject {
+            id: "a".to_string(),
+            class_id: "b".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(points_balance(&object), 0);
+
+        object.loyalty_points = Some(LoyaltyPoints {
+            label: "Points".to_string(),
+            balance: Some(LoyaltyPointsBalance {
+                string: None,
+                int: Some(120),
+                double: None,
+            }),
+        });
+        assert_eq!(points_balance(&object), 120);
+    }
+
+    #[tokio::test]
+    async fn test_clones_share_access_token() {
+        let client = test_client();
+        let clone = client.clone();
+
+        cache_token(&client, None);
+        assert_eq!(
+            clone.get_access_token(&clone.config).await.unwrap(),
+            "token"
+        );
+        // A delegated view needs its own token
+        let delegated = clone.for_subject("agent@example.com");
+        assert!(delegated.get_access_token(&delegated.config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_does_not_block_other_principals() {
+        let client = test_client();
+        // Hold the service account's own token as an exchange in flight would
+        let refreshing = cache_token(&client, None);
+        let _refresh = refreshing.lock().await;
+        cache_token(&client, Some("agent@example.com"));
+
+        let delegated = client.for_subject("agent@example.com");
+        let token = tokio::time::timeout(
+            Duration::from_secs(1),
+            delegated.get_access_token(&delegated.config),
+        )
+        .await;
+        assert_eq!(token.unwrap().unwrap(), "token");
+    }
+
+    #[test]
+    fn test_class_drift() {
+        let remote = json!({
+            "id": "issuer.class",
+            "reviewStatus": "APPROVED",
+            "callbackOptions": { "url": "https://example.com/a", "updateRequestUrl": "x" },
+            "enableSmartTap": true
+        });
+
+        let same = json!({
+            "id": "issuer.class",
+            "reviewStatus": "UNDER_REVIEW",
+            "callbackOptions": { "url": "https://example.com/a" }
+        });
+        assert!(class_drift(&remote, &same).is_empty());
+
+        let drifted = json!({
+            "id": "issuer.class",
+            "callbackOptions": { "url": "https://example.com/b" },
+            "viewUnlockRequirement": "UNLOCK_REQUIRED_TO_VIEW"
+        });
+        let patch = class_drift(&remote, &drifted);
+        let keys: Vec<_> = patch.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["callbackOptions", "viewUnlockRequirement"]);
+    }
+
+    #[test]
+    fn test_issuer_routing() {
+        let config = |issuer: &str| GoogleWalletConfig {
+            issuer_id: issuer.to_string(),
+            service_account_email: format!("{}@example.iam.gserviceaccount.com", issuer),
+            private_key: String::new(),
+        };
+        let client = GoogleWalletClient::new(config("1111")).with_issuer(config("2222"));
+
+        let issuer = |path| client.route(path, None).map(|c| c.issuer_id.as_str());
+        assert_eq!(issuer("/genericObject/2222.pass"), Some("2222"));
+        assert_eq!(issuer("/genericObject/2222.pass/addMessage"), Some("2222"));
+        assert_eq!(issuer("/genericObject?classId=2222.class"), Some("2222"));
+        assert_eq!(issuer("/genericClass?issuerId=2222"), Some("2222"));
+        assert_eq!(issuer("/genericObject/1111.pass"), None);
+        assert_eq!(issuer("/genericObject"), None);
+
+        assert!(client
+            .for_credentials(config("3333"))
+            .route("/genericObject/2222.pass", None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_insert_routes_by_body() {
+        let config = |issuer: &str| GoogleWalletConfig {
+            issuer_id: issuer.to_string(),
+            service_account_email: format!("{}@example.iam.gserviceaccount.com", issuer),
+            private_key: TEST_KEY.to_string(),
+        };
+        let client = GoogleWalletClient::new(config("1111")).with_issuer(config("2222"));
+        let issuer = |path, body: Value| {
+            client
+                .route(path, Some(&body))
+                .map(|c| c.issuer_id.as_str())
+        };
+
+        let object = GenericObject::reference("2222.pass", "2222.class");
+        let insert = serde_json::to_value(&object).unwrap();
+        assert_eq!(issuer("/genericObject", insert.clone()), Some("2222"));
+        assert_eq!(
+            issuer("/genericClass", json!({ "id": "2222.class" })),
+            Some("2222")
+        );
+        assert_eq!(issuer("/genericObject", json!({ "id": "1111.pass" })), None);
+        // The path wins over the body
+        assert_eq!(issuer("/genericObject/1111.pass", insert), None);
+
+        let jwt = client
+            .generate_pass_jwt(
+                &JwtObjectPayload {
+                    generic_objects: Some(vec![object]),
+                    ..Default::default()
+                },
+                None,
+            )
+            .unwrap();
+        assert_eq!(issuer("/jwt", json!({ "jwt": jwt })), Some("2222"));
+    }
+
+    #[test]
+    fn test_signing_key_rotation() {
+        let key = |id: &str| SigningKey {
+            key_id: id.to_string(),
+            private_key: format!("pem-{}", id),
+        };
+        let mut client = test_client();
+        assert!(client.active_signing_key().is_none());
+
+        client = client
+            .with_signing_key(key("old"))
+            .with_signing_key(key("new"));
+        assert_eq!(client.signing_key_ids(), vec!["old", "new"]);
+        assert_eq!(client.active_signing_key().unwrap().key_id, "new");
+
+        client.use_signing_key("old").unwrap();
+        assert_eq!(client.active_signing_key().unwrap().private_key, "pem-old");
+        assert!(client.use_signing_key("missing").is_err());
+    }
+
+    #[test]
+    fn test_generate_save_link() {
+        let client = GoogleWalletClient::new(GoogleWalletConfig {
+            private_key: TEST_KEY.to_string(),
+            ..test_config()
+        })
+        .with_save_link_ttl(Duration::from_secs(3600));
+
+        let link = client
+            .generate_save_link(&JwtObjectPayload {
+                generic_objects: Some(vec![GenericObject {
+                    hex_background_color: Some("#000000".to_string()),
+                    ..GenericObject::reference("issuer.a", "issuer.class")
+                }]),
+                flight_objects: Some(vec![FlightObject::reference("issuer.b", "issuer.flight")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(link.starts_with(SAVE_URL_PREFIX));
+
+        let claims = JwtPayload::from_save_url(&link).unwrap();
+        assert_eq!(claims.iss, "wallet@example.iam.gserviceaccount.com");
+        assert_eq!(claims.exp, Some(claims.iat + 3600));
+        assert_eq!(
+            serde_json::to_value(&claims.payload).unwrap(),
+            json!({
+                "genericObjects": [
+                    { "id": "issuer.a", "classId": "issuer.class", "hexBackgroundColor": "#000000" }
+                ],
+                "flightObjects": [{ "id": "issuer.b", "classId": "issuer.flight" }]
+            })
+        );
+
+        assert!(client
+            .generate_save_link(&JwtObjectPayload::default())
+            .is_err());
+        assert!(claims.origins.is_none());
+
+        let objects = JwtObjectPayload {
+            generic_objects: Some(vec![GenericObject::reference("issuer.a", "issuer.class")]),
+            ..Default::default()
+        };
+        let client =
+            client.with_save_link_origins(["https://example.com", "http://localhost:8080"]);
+        let link = client.generate_save_link(&objects).unwrap();
+        assert_eq!(
+            JwtPayload::from_save_url(&link)
+                .unwrap()
+                .origins
+                .unwrap()
+                .into_owned(),
+            ["https://example.com", "http://localhost:8080"]
+        );
+
+        for origin in [
+            "example.com",
+            "https://example.com/wallet",
+            "https://example.com/",
+        ] {
+            let client = client.clone().with_save_link_origins([origin]);
+            assert!(client.generate_save_link(&objects).is_err(), "{}", origin);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_skips_mutations() {
+        let mut client = test_client().with_dry_run();
+
+        let object = GenericObject {
+            id: "issuer.pass".to_string(),
+            class_id: "issuer.class".to_string(),
+            ..Default::default()
+        };
+        let created = client.create_generic_object(&object).await.unwrap();
+        assert_eq!(created.id, "issuer.pass");
+
+        let requests = client.dry_run_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/genericObject");
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_after_error() {
+        use futures_util::StreamExt;
+
+        let mut client = test_client();
+
+        let results: Vec<_> = client
+            .stream_generic_objects("issuer.class")
+            .collect()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[test]
+    fn test_list_path() {
+        assert_eq!(
+            list_path(
+                "genericObject",
+                ListParent::Class("issuer.class"),
+                None,
+                None
+            )
+            .unwrap(),
+            "/genericObject?classId=issuer.class"
+        );
+        assert_eq!(
+            list_path(
+                "flightObject",
+                ListParent::Class("issuer.ba 212"),
+                Some(50),
+                Some("next&page")
+            )
+            .unwrap(),
+            "/flightObject?classId=issuer.ba+212&maxResults=50&token=next%26page"
+        );
+        assert_eq!(
+            list_path("loyaltyClass", ListParent::Issuer("3388"), None, None).unwrap(),
+            "/loyaltyClass?issuerId=3388"
+        );
+    }
+
+    #[test]
+    fn test_added_message_resource() {
+        let object = json!({ "id": "issuer.a", "classId": "issuer.class" });
+        assert_eq!(
+            added_message_resource(json!({ "resource": object.clone() })),
+            object
+        );
+        assert_eq!(added_message_resource(object.clone()), object);
+    }
+
+    #[tokio::test]
+    async fn test_send_batch_dry_run() {
+        let mut client = test_client().with_dry_run();
+
+        let class = LoyaltyClass {
+            id: "issuer.rewards".to_string(),
+            ..Default::default()
+        };
+        let object = GenericObject {
+            id: "issuer.a".to_string(),
+            class_id: "issuer.class".to_string(),
+            ..Default::default()
+        };
+        let batch = BatchRequest::new()
+            .update(&class)
+            .unwrap()
+            .insert(&object)
+            .unwrap();
+
+        let results = client.send_batch(&batch).await.unwrap();
+        assert!(results.iter().all(BatchItemResult::is_success));
+        let created: GenericObject = results[1].clone().into_result().unwrap();
+        assert_eq!(created.id, "issuer.a");
+
+        let requests = client.dry_run_requests();
+        assert_eq!(requests[0].method, "PUT");
+        assert_eq!(requests[0].path, "/loyaltyClass/issuer.rewards");
+        assert_eq!(requests[1].method, "POST");
+        assert_eq!(requests[1].path, "/genericObject");
+
+        assert!(client
+            .send_batch(&BatchRequest::new())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_messages() {
+        let mut client = test_client().with_cache(Duration::from_secs(60));
+
+        let cache = client.cache.clone().unwrap();
+        let principal = client.principal("/genericObject/issuer.a");
+        cache.insert(
+            &principal,
+            "/genericObject/issuer.a",
+            json!({
+                "id": "issuer.a",
+                "classId": "issuer.class",
+                "messages": [
+                    { "id": "gate", "header": "Gate change", "body": "Now boarding at B12.", "messageType": "TEXT_AND_NOTIFY" }
+                ]
+            }),
+        );
+        cache.insert(
+            &principal,
+            "/genericClass/issuer.class",
+            json!({ "id": "issuer.class" }),
+        );
+
+        let messages = client.get_messages("issuer.a").await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].header.as_deref(), Some("Gate change"));
+        assert_eq!(messages[0].message_type.as_deref(), Some("TEXT_AND_NOTIFY"));
+        assert!(client
+            .get_class_messages("issuer.class")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_loyalty_class_requests() {
+        let mut client = test_client().with_dry_run();
+
+        let class = LoyaltyClass {
+            id: "issuer.rewards".to_string(),
+            program_name: Some("Example Rewards".to_string()),
+            rewards_tier: Some("Gold".to_string()),
+            rewards_tier_label: Some("Tier".to_string()),
+            account_id_label: Some("Member ID".to_string()),
+            ..Default::default()
+        };
+        let created = client.create_loyalty_class(&class).await.unwrap();
+        assert_eq!(created.program_name.as_deref(), Some("Example Rewards"));
+        client
+            .update_loyalty_class(&class.id, &class)
+            .await
+            .unwrap();
+
+        let requests = client.dry_run_requests();
+        assert_eq!(requests[0].path, "/loyaltyClass");
+        assert_eq!(
+            requests[0].body.as_ref().unwrap()["rewardsTierLabel"],
+            "Tier"
+        );
+        assert_eq!(requests[1].method, "PUT");
+        assert_eq!(requests[1].path, "/loyaltyClass/issuer.rewards");
+    }
+
+    #[tokio::test]
+    async fn test_offer_requests() {
+        let mut client = test_client().with_dry_run();
+
+        let class = OfferClass {
+            id: "issuer.offer_class".to_string(),
+            title: Some("20% off any t-shirt".to_string()),
+            provider: Some("Adam's Apparel".to_string()),
+            redemption_channel: Some("INSTORE".to_string()),
+            ..Default::default()
+        };
+        client.create_offer_class(&class).await.unwrap();
+        let offer = OfferObject {
+            id: "issuer.offer".to_string(),
+            class_id: class.id.clone(),
+            ..Default::default()
+        };
+        client.create_offer_object(&offer).await.unwrap();
+        client.update_offer_object(&offer.id, &offer).await.unwrap();
+
+        let paths: Vec<_> = client
+            .dry_run_requests()
+            .iter()
+            .map(|request| (request.method.as_str(), request.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("POST", "/offerClass"),
+                ("POST", "/offerObject"),
+                ("PUT", "/offerObject/issuer.offer"),
+            ]
+        );
+
+        let payload = JwtObjectPayload {
+            offer_objects: Some(vec![offer]),
+            ..Default::default()
+        };
+        assert!(!payload.is_empty());
+        assert_eq!(first_object_id(&payload), Some("issuer.offer"));
+    }
+
+    #[tokio::test]
+    async fn test_gift_card_requests() {
+        let mut client = test_client().with_dry_run();
+
+        let class = GiftCardClass {
+            id: "issuer.gift_class".to_string(),
+            merchant_name: Some("Example Store".to_string()),
+            pin_label: Some("PIN".to_string()),
+            ..Default::default()
+        };
+        client.create_gift_card_class(&class).await.unwrap();
+        let gift_card = GiftCardObject {
+            id: "issuer.gift".to_string(),
+            class_id: class.id.clone(),
+            card_number: Some("6011 0000 0000 1234".to_string()),
+            pin: Some("1234".to_string()),
+            balance: Some(Money::new(25_000_000, "USD")),
+            ..Default::default()
+        };
+        client.create_gift_card_object(&gift_card).await.unwrap();
+        client
+            .update_gift_card_object(&gift_card.id, &gift_card)
+            .await
+            .unwrap();
+
+        let paths: Vec<_> = client
+            .dry_run_requests()
+            .iter()
+            .map(|request| (request.method.as_str(), request.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("POST", "/giftCardClass"),
+                ("POST", "/giftCardObject"),
+                ("PUT", "/giftCardObject/issuer.gift"),
+            ]
+        );
+
+        let payload = JwtObjectPayload {
+            gift_card_objects: Some(vec![gift_card]),
+            ..Default::default()
+        };
+        assert_eq!(first_object_id(&payload), Some("issuer.gift"));
+    }
+
+    #[tokio::test]
+    async fn test_transit_requests() {
+        let mut client = test_client().with_dry_run();
+
+        let class = TransitClass {
+            id: "issuer.rail".to_string(),
+            transit_type: Some("RAIL".to_string()),
+            ..Default::default()
+        };
+        client.create_transit_class(&class).await.unwrap();
+        let ticket = TransitObject {
+            id: "issuer.ticket".to_string(),
+            class_id: class.id.clone(),
+            trip_type: Some("ROUND_TRIP".to_string()),
+            concession_category: Some("CHILD".to_string()),
+            ticket_legs: Some(vec![
+                TicketLeg {
+                    origin_station_code: Some("LDS".to_string()),
+                    destination_station_code: Some("YRK".to_string()),
+                    ..Default::default()
+                },
+                TicketLeg {
+                    origin_station_code: Some("YRK".to_string()),
+                    destination_station_code: Some("LDS".to_string()),
+                    ..Default::default()
+                },
+            ]),
+            purchase_details: Some(PurchaseDetails {
+                ticket_cost: Some(TicketCost {
+                    purchase_price: Some(Money::new(12_500_000, "GBP")),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        client.create_transit_object(&ticket).await.unwrap();
+
+        let requests = client.dry_run_requests();
+        assert_eq!(requests[0].path, "/transitClass");
+        assert_eq!(requests[1].path, "/transitObject");
+        let body = requests[1].body.as_ref().unwrap();
+        assert_eq!(body["concessionCategory"], "CHILD");
+        assert_eq!(body["ticketLegs"][1]["originStationCode"], "YRK");
+        assert_eq!(
+            body["purchaseDetails"]["ticketCost"]["purchasePrice"]["micros"],
+            "12500000"
+        );
+
+        let payload = JwtObjectPayload {
+            transit_objects: Some(vec![ticket]),
+            ..Default::default()
+        };
+        assert_eq!(first_object_id(&payload), Some("issuer.ticket"));
+    }
+
+    #[test]
+    fn test_random_id() {
+        let id = random_id().unwrap();
+
+        assert_eq!(id.len(), 32);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(id, random_id().unwrap());
+    }
+
+    #[test]
+    fn test_strip_holder_data() {
+        let mut object = GenericObject {
+            id: "issuer.pass".to_string(),
+            class_id: "issuer.class".to_string(),
+            state: Some(State::Active),
+            header: Some(LocalizedString {
+                default_value: Some(TranslatedString {
+                    language: "en-US".to_string(),
+                    value: "Jane Doe".to_string(),
+                }),
+                translated_values: None,
+            }),
+            hex_background_color: Some("#000000".to_string()),
+            ..Default::default()
+        };
+
+        strip_holder_data(&mut object);
+
+        assert_eq!(object.state, Some(State::Expired));
+        assert!(object.header.is_none());
+        assert_eq!(object.hex_background_color, Some("#000000".to_string()));
+    }
+
+    #[test]
+    fn test_audit_resource() {
+        let created = json!({ "id": "issuer.new" });
+
+        assert_eq!(
+            audit_resource("/genericObject", &created),
+            ("genericObject".to_string(), "issuer.new".to_string())
+        );
+        assert_eq!(
+            audit_resource("/loyaltyObject/issuer.a/addMessage", &created),
+            ("loyaltyObject".to_string(), "issuer.a".to_string())
+        );
+        assert_eq!(
+            audit_resource("/offerObject/issuer.b?updateMask=state", &created),
+            ("offerObject".to_string(), "issuer.b".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transfer_ignores_stale_cache() {
+        let current = json!({
+            "id": "issuer.a",
+            "classId": "issuer.class",
+            "state": "ACTIVE",
+            "header": { "defaultValue": { "language": "en-US", "value": "Row B" } }
+        });
+        let (client, received) = mock_client(vec![
+            ("GET", "/genericObject/issuer.a", 200, current),
+            (
+                "POST",
+                "/genericObject",
+                200,
+                json!({ "id": "issuer.b", "classId": "issuer.class" }),
+            ),
+            (
+                "PATCH",
+                "/genericObject/issuer.a",
+                200,
+                json!({ "id": "issuer.a", "classId": "issuer.class" }),
+            ),
+            ("POST", "/jwt", 200, json!({ "saveUri": SAVE_URL_PREFIX })),
+        ])
+        .await;
+        let mut client = client.with_cache(Duration::from_secs(60));
+        client.cache.clone().unwrap().insert(
+            &client.principal("/genericObject/issuer.a"),
+            "/genericObject/issuer.a",
+            json!({
+                "id": "issuer.a",
+                "classId": "issuer.class",
+                "state": "ACTIVE",
+                "header": { "defaultValue": { "language": "en-US", "value": "Row A" } }
+            }),
+        );
+
+        client
+            .transfer_pass("issuer.a", Some("issuer.b"))
+            .await
+            .unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received[0].method, "GET");
+        let created = received[1].body.as_ref().unwrap();
+        assert_eq!(created["id"], "issuer.b");
+        assert_eq!(created["header"]["defaultValue"]["value"], "Row B");
+        assert_eq!(received[2].method, "PATCH");
+        assert_eq!(received[2].body, Some(json!({ "state": "EXPIRED" })));
+    }
+
+    #[tokio::test]
+    async fn test_purge_patches_holder_fields() {
+        let (mut client, received) = mock_client(vec![(
+            "PATCH",
+            "/genericObject/issuer.a?updateMask=state,header,subheader,barcode,textModulesData",
+            200,
+            json!({ "id": "issuer.a", "classId": "issuer.class", "state": "EXPIRED" }),
+        )])
+        .await;
+
+        let report = client.purge_holder(&["issuer.a", "issuer.b"], None).await;
+        assert_eq!(report.google.purged, vec!["issuer.a"]);
+        assert_eq!(report.google.not_found, vec!["issuer.b"]);
+
+        let received = received.lock().unwrap();
+        assert!(received.iter().all(|request| request.method == "PATCH"));
+        assert_eq!(
+            received[1].path,
+            "/genericObject/issuer.b?updateMask=state,header,subheader,barcode,textModulesData"
+        );
+        assert_eq!(
+            received[0].body,
+            Some(json!({
+                "state": "EXPIRED",
+                "header": null,
+                "subheader": null,
+                "barcode": null,
+                "textModulesData": null
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expire_all_report() {
+        let (mut client, received) = mock_client(vec![
+            (
+                "GET",
+                "/genericObject?classId=issuer.class",
+                200,
+                json!({
+                    "resources": [
+                        { "id": "issuer.a", "classId": "issuer.class", "state": "ACTIVE" },
+                        { "id": "issuer.b", "classId": "issuer.class", "state": "EXPIRED" },
+                        { "id": "issuer.c", "classId": "issuer.class", "state": "ACTIVE" },
+                        { "id": "issuer.d", "classId": "issuer.class", "state": "ACTIVE" }
+                    ]
+                }),
+            ),
+            (
+                "PATCH",
+                "/genericObject/issuer.a",
+                200,
+                json!({ "id": "issuer.a", "classId": "issuer.class", "state": "EXPIRED" }),
+            ),
+        ])
+        .await;
+
+        let report = client
+            .expire_all("issuer.class", |object| object.id != "issuer.c", 4)
+            .await
+            .unwrap();
+        assert_eq!(report.expired, vec!["issuer.a"]);
+        assert_eq!(report.skipped, vec!["issuer.b", "issuer.c"]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].pass_id, "issuer.d");
+        assert!(!report.is_complete());
+
+        let patched = received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.method == "PATCH")
+            .count();
+        assert_eq!(patched, 2);
+    }
+
+    fn loyalty_object(id: &str, points: i32) -> Value {
+        json!({
+            "id": id,
+            "classId": "issuer.rewards",
+            "loyaltyPoints": { "label": "Stars", "balance": { "int": points } }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_add_points_verifies_write() {
+        let (mut client, received) = mock_client(vec![
+            (
+                "GET",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 100),
+            ),
+            (
+                "GET",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 125),
+            ),
+            (
+                "PATCH",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 125),
+            ),
+        ])
+        .await;
+
+        let object = client.add_points("issuer.a", 25).await.unwrap();
+        assert_eq!(points_balance(&object), 125);
+
+        let received = received.lock().unwrap();
+        let methods: Vec<_> = received.iter().map(|r| r.method.as_str()).collect();
+        assert_eq!(methods, vec!["GET", "PATCH", "GET"]);
+        assert_eq!(
+            received[1].body,
+            Some(json!({ "loyaltyPoints": { "label": "Stars", "balance": { "int": 125 } } }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_points_changed_during_write() {
+        let (mut client, received) = mock_client(vec![
+            // A stale writer puts the balance back after the first PATCH,
+            // so the update is retried against the fresh read
+            (
+                "GET",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 100),
+            ),
+            (
+                "GET",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 100),
+            ),
+            (
+                "GET",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 100),
+            ),
+            (
+                "GET",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 125),
+            ),
+            (
+                "PATCH",
+                "/loyaltyObject/issuer.a",
+                200,
+                loyalty_object("issuer.a", 125),
+            ),
+            // Another writer adds 10 after this one
+            (
+                "GET",
+                "/loyaltyObject/issuer.b",
+                200,
+                loyalty_object("issuer.b", 100),
+            ),
+            (
+                "GET",
+                "/loyaltyObject/issuer.b",
+                200,
+                loyalty_object("issuer.b", 135),
+            ),
+            (
+                "PATCH",
+                "/loyaltyObject/issuer.b",
+                200,
+                loyalty_object("issuer.b", 125),
+            ),
+        ])
+        .await;
+
+        let object = client.add_points("issuer.a", 25).await.unwrap();
+        assert_eq!(points_balance(&object), 125);
+        let patches = received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == "PATCH")
+            .count();
+        assert_eq!(patches, 2);
+
+        assert!(matches!(
+            client.add_points("issuer.b", 25).await,
+            Err(PorterError::ConcurrentModification(_))
+        ));
+        assert!(matches!(
+            client.set_points_if("issuer.b", 100, 150).await,
+            Err(PorterError::ConcurrentModification(_))
+        ));
+    }
+
+    const APPLE_PASS_TYPE: &str = "pass.com.example.ticket";
+
+    /// An APNs client for `server`, and a store with `serial_number`
+    /// registered to the device with push token `token-1`
+    async fn apple_holder(
+        server: &str,
+        serial_number: &str,
+    ) -> (
+        crate::apple::ApnsClient,
+        crate::apple::MemoryRegistrationStore,
+    ) {
+        use crate::apple::RegistrationStore;
+        use crate::apple::{ApnsClient, ApnsConfig, MemoryRegistrationStore, PassRegistration};
+
+        let apns = ApnsClient::new(ApnsConfig {
+            key_id: "ABC123DEFG".to_string(),
+            team_id: "ABCDE12345".to_string(),
+            private_key: include_str!("../apple/testdata/apns_key.p8").to_string(),
+            topic: APPLE_PASS_TYPE.to_string(),
+        })
+        .with_server(server);
+        let registrations = MemoryRegistrationStore::new();
+        registrations
+            .register(
+                &PassRegistration {
+                    device_library_identifier: "phone".to_string(),
+                    pass_type_identifier: APPLE_PASS_TYPE.to_string(),
+                    serial_number: serial_number.to_string(),
+                },
+                "token-1",
+            )
+            .await
+            .unwrap();
+        (apns, registrations)
+    }
+
+    /// Method and path of each request the mock server received
+    fn request_lines(received: &Received) -> Vec<String> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| format!("{} {}", r.method, r.path))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_trip_notifies_apple() {
+        let ticket = json!({ "id": "issuer.r1", "classId": "issuer.route" });
+        let (mut client, received) = mock_client(vec![
+            ("GET", "/transitObject/issuer.r1", 200, ticket.clone()),
+            ("PUT", "/transitObject/issuer.r1", 200, ticket.clone()),
+            (
+                "POST",
+                "/transitObject/issuer.r1/addMessage",
+                200,
+                json!({}),
+            ),
+            ("POST", "/3/device/token-1", 200, json!({})),
+        ])
+        .await;
+        let (apns, registrations) = apple_holder(&client.api_base.clone(), "r1").await;
+
+        let update = client
+            .cancel_trip(
+                "issuer.r1",
+                None,
+                Some(ApplePush {
+                    pass_type_identifier: APPLE_PASS_TYPE,
+                    serial_numbers: &["r1"],
+                    registrations: &registrations,
+                    apns: &apns,
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(update.object.id, "issuer.r1");
+        assert_eq!(update.apple.unwrap().notified, vec!["token-1"]);
+        assert_eq!(
+            request_lines(&received),
+            vec![
+                "GET /transitObject/issuer.r1",
+                "PUT /transitObject/issuer.r1",
+                "POST /transitObject/issuer.r1/addMessage",
+                "POST /3/device/token-1",
+            ]
+        );
+
+        let update = client
+            .update_platform("issuer.r1", "4", None)
+            .await
+            .unwrap();
+        assert!(update.apple.is_none());
+        assert_eq!(request_lines(&received).len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_reassign_seat_notifies_apple() {
+        let ticket = json!({ "id": "issuer.t1", "classId": "issuer.concert" });
+        let (mut client, received) = mock_client(vec![
+            ("GET", "/eventTicketObject/issuer.t1", 200, ticket.clone()),
+            ("PUT", "/eventTicketObject/issuer.t1", 200, ticket.clone()),
+            (
+                "POST",
+                "/eventTicketObject/issuer.t1/addMessage",
+                200,
+                json!({}),
+            ),
+            ("POST", "/3/device/token-1", 200, json!({})),
+        ])
+        .await;
+        let (apns, registrations) = apple_holder(&client.api_base.clone(), "t1").await;
+
+        let seat = crate::models::Seat {
+            row: Some("F".to_string()),
+            ..Default::default()
+        };
+        let update = client
+            .reassign_seat(
+                "issuer.t1",
+                EventSeat::from(&seat),
+                Some(ApplePush {
+                    pass_type_identifier: APPLE_PASS_TYPE,
+                    serial_numbers: &["t1"],
+                    registrations: &registrations,
+                    apns: &apns,
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(update.object.id, "issuer.t1");
+        assert_eq!(update.apple.unwrap().notified, vec!["token-1"]);
+        assert_eq!(
+            request_lines(&received),
+            vec![
+                "GET /eventTicketObject/issuer.t1",
+                "PUT /eventTicketObject/issuer.t1",
+                "POST /eventTicketObject/issuer.t1/addMessage",
+                "POST /3/device/token-1",
+            ]
+        );
+        let put = received.lock().unwrap()[1].body.clone().unwrap();
+        assert_eq!(put["seatInfo"]["row"]["defaultValue"]["value"], "F");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_expiry_countdown_reads_once() {
+        let mut pass = crate::PassBuilder::new("issuer.a", "issuer.class")
+            .valid_until(chrono::Utc::now() + chrono::Duration::days(10))
+            .expiry_countdown()
+            .build();
+        for field in &mut pass.fields {
+            if field.key == crate::models::EXPIRY_COUNTDOWN_KEY {
+                field.value = "Valid for 99 more days".to_string();
+            }
+        }
+        let object = serde_json::to_value(GenericObject::from(&pass)).unwrap();
+        let (mut client, received) = mock_client(vec![
+            ("GET", "/genericObject/issuer.a", 200, object.clone()),
+            ("PUT", "/genericObject/issuer.a", 200, object),
+        ])
+        .await;
+
+        assert!(client.refresh_expiry_countdown("issuer.a").await.unwrap());
+        assert_eq!(
+            request_lines(&received),
+            vec!["GET /genericObject/issuer.a", "PUT /genericObject/issuer.a"]
+        );
+        let put = received.lock().unwrap()[1].body.clone().unwrap();
+        assert!(!put.to_string().contains("99 more days"));
+    }
 }
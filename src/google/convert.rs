@@ -1,7 +1,69 @@
+use crate::error::{PorterError, Result};
+use crate::google::strict::normalize;
 use crate::google::types::{
-    Barcode as GoogleBarcode, GenericObject, LocalizedString, TextModuleData, TranslatedString,
+    AppLinkData, AppLinkInfo, AppTarget, Barcode as GoogleBarcode, BoardingAndSeatingInfo,
+    DateTime as GoogleDateTime, ExpiryNotification, FlightObject, GenericObject, GroupingInfo,
+    ImageModuleData, LinksModuleData, LocalizedString, Notifications, ReservationInfo,
+    RotatingBarcode as GoogleRotatingBarcode, State, TextModuleData,
+    TimeInterval as GoogleTimeInterval, TotpDetails, TotpParameters, TranslatedString,
+    UpcomingNotification, Uri,
 };
-use crate::models::{Barcode, BarcodeFormat, Pass, PassState};
+use crate::models::{
+    AppLink, Barcode, BarcodeFormat, Grouping, LocalizedValue, Pass, PassNotifications, PassState,
+    RotatingBarcode, TimeInterval, TotpParameter,
+};
+
+pub(crate) const DEFAULT_LANGUAGE: &str = "en-US";
+
+/// Build a LocalizedString carrying any translations recorded on the pass
+pub(crate) fn localized_string(pass: &Pass, value: &str) -> LocalizedString {
+    let translated_values = pass.localized(value).map(|localized| {
+        localized
+            .translations
+            .iter()
+            .map(|(language, value)| TranslatedString {
+                language: language.clone(),
+                value: value.clone(),
+            })
+            .collect()
+    });
+
+    LocalizedString {
+        default_value: Some(TranslatedString {
+            language: pass
+                .default_locale
+                .as_deref()
+                .unwrap_or(DEFAULT_LANGUAGE)
+                .to_string(),
+            value: value.to_string(),
+        }),
+        translated_values,
+    }
+}
+
+/// Record the translations of a LocalizedString on a pass
+fn collect_translations(
+    localized: Option<&LocalizedString>,
+    translations: &mut Vec<LocalizedValue>,
+) {
+    let Some(localized) = localized else { return };
+    let (Some(default), Some(translated)) =
+        (&localized.default_value, &localized.translated_values)
+    else {
+        return;
+    };
+    if translated.is_empty() || translations.iter().any(|t| t.value == default.value) {
+        return;
+    }
+
+    translations.push(LocalizedValue {
+        value: default.value.clone(),
+        translations: translated
+            .iter()
+            .map(|t| (t.language.clone(), t.value.clone()))
+            .collect(),
+    });
+}
 
 /// Convert a unified Pass model to a Google Wallet GenericObject
 impl From<Pass> for GenericObject {
@@ -13,82 +75,138 @@ impl From<Pass> for GenericObject {
 impl From<&Pass> for GenericObject {
     fn from(pass: &Pass) -> Self {
         let barcode = pass.barcode.as_ref().map(|b| GoogleBarcode {
-            barcode_type: match b.format {
-                BarcodeFormat::QrCode => "QR_CODE",
-                BarcodeFormat::Pdf417 => "PDF_417",
-                BarcodeFormat::Aztec => "AZTEC",
-                BarcodeFormat::Code128 => "CODE_128",
-            }
-            .to_string(),
+            barcode_type: barcode_type(&b.format).to_string(),
             value: b.value.clone(),
             alternate_text: b.alternate_text.clone(),
         });
+        let rotating_barcode = pass.barcode.as_ref().and_then(|b| {
+            let rotating = b.rotating.as_ref()?;
+            Some(GoogleRotatingBarcode {
+                barcode_type: barcode_type(&b.format).to_string(),
+                render_encoding: None,
+                value_pattern: rotating.value_pattern.clone(),
+                totp_details: Some(TotpDetails {
+                    period_millis: i64::from(rotating.period_seconds) * 1000,
+                    algorithm: "TOTP_SHA1".to_string(),
+                    parameters: rotating
+                        .parameters
+                        .iter()
+                        .map(|p| TotpParameters {
+                            key: p.key.clone(),
+                            value_length: i32::from(p.value_length),
+                        })
+                        .collect(),
+                }),
+                alternate_text: b.alternate_text.clone(),
+                show_code_text: None,
+            })
+        });
 
-        let state = Some(
-            match pass.state {
-                PassState::Active => "ACTIVE",
-                PassState::Inactive => "INACTIVE",
-                PassState::Expired => "EXPIRED",
-                PassState::Completed => "COMPLETED",
-            }
-            .to_string(),
-        );
-
-        let card_title = Some(LocalizedString {
-            default_value: Some(TranslatedString {
-                language: "en-US".to_string(),
-                value: pass.header.title.clone(),
-            }),
-            translated_values: None,
+        let state = Some(match pass.state {
+            PassState::Active => State::Active,
+            PassState::Inactive => State::Inactive,
+            PassState::Expired => State::Expired,
+            PassState::Completed => State::Completed,
         });
 
+        let card_title = Some(localized_string(pass, &pass.header.title));
+
         let header = pass
             .header
             .subtitle
             .as_ref()
-            .map(|subtitle| LocalizedString {
-                default_value: Some(TranslatedString {
-                    language: "en-US".to_string(),
-                    value: subtitle.clone(),
-                }),
-                translated_values: None,
-            });
+            .map(|subtitle| localized_string(pass, subtitle));
+
+        let (linked, plain): (Vec<_>, Vec<_>) =
+            pass.fields.iter().partition(|field| field.link.is_some());
 
-        let text_modules_data = if pass.fields.is_empty() {
+        let text_modules_data = if plain.is_empty() {
             None
         } else {
             Some(
-                pass.fields
-                    .iter()
+                plain
+                    .into_iter()
                     .map(|field| TextModuleData {
                         id: Some(field.key.clone()),
                         header: Some(field.label.clone()),
                         body: Some(field.value.clone()),
-                        localized_header: None,
-                        localized_body: None,
+                        localized_header: pass
+                            .localized(&field.label)
+                            .map(|_| localized_string(pass, &field.label)),
+                        localized_body: pass
+                            .localized(&field.value)
+                            .map(|_| localized_string(pass, &field.value)),
                     })
                     .collect(),
             )
         };
 
+        let links_module_data = if linked.is_empty() {
+            None
+        } else {
+            Some(LinksModuleData {
+                uris: Some(
+                    linked
+                        .into_iter()
+                        .map(|field| Uri {
+                            uri: field.link.clone().unwrap_or_default(),
+                            description: Some(field.value.clone()),
+                            localized_description: pass
+                                .localized(&field.value)
+                                .map(|_| localized_string(pass, &field.value)),
+                            id: Some(field.key.clone()),
+                        })
+                        .collect(),
+                ),
+            })
+        };
+
         GenericObject {
             id: pass.id.clone(),
             class_id: pass.class_id.clone(),
             state,
             barcode,
+            rotating_barcode,
+            smart_tap_redemption_value: pass.smart_tap_redemption_value.clone(),
+            app_link_data: pass.app_link.as_ref().map(|link| app_link_data(pass, link)),
             card_title,
             header,
             subheader: None,
             logo: pass.header.logo.as_ref().and_then(|l| l.into()),
             hex_background_color: pass.header.background_color.clone(),
-            hero_image: None,
-            valid_time_interval: None,
+            hero_image: pass.header.hero_image.as_ref().and_then(|i| i.into()),
+            valid_time_interval: pass.valid_time_interval.as_ref().map(|interval| {
+                GoogleTimeInterval {
+                    start: Some(interval.start.into()),
+                    end: interval.end.map(Into::into),
+                }
+            }),
             linked_offer_ids: if pass.linked_objects.is_empty() {
                 None
             } else {
                 Some(pass.linked_objects.clone())
             },
             text_modules_data,
+            links_module_data,
+            grouping_info: pass.grouping.as_ref().map(|grouping| GroupingInfo {
+                grouping_id: Some(grouping.id.clone()),
+                sort_index: grouping.sort_index,
+            }),
+            notifications: notifications(&pass.notifications),
+            messages: None,
+            image_modules_data: if pass.images.is_empty() {
+                None
+            } else {
+                Some(
+                    pass.images
+                        .iter()
+                        .map(|image| ImageModuleData {
+                            main_image: image.into(),
+                            id: None,
+                        })
+                        .collect(),
+                )
+            },
         }
     }
 }
@@ -102,30 +220,44 @@ impl From<GenericObject> for Pass {
 
 impl From<&GenericObject> for Pass {
     fn from(object: &GenericObject) -> Self {
-        let barcode = object.barcode.as_ref().map(|b| {
-            let format = match b.barcode_type.as_str() {
-                "QR_CODE" => BarcodeFormat::QrCode,
-                "PDF_417" => BarcodeFormat::Pdf417,
-                "AZTEC" => BarcodeFormat::Aztec,
-                "CODE_128" => BarcodeFormat::Code128,
-                _ => BarcodeFormat::QrCode, // default
-            };
-
-            Barcode {
-                format,
+        let rotating = object.rotating_barcode.as_ref().map(|r| RotatingBarcode {
+            value_pattern: r.value_pattern.clone(),
+            period_seconds: r
+                .totp_details
+                .as_ref()
+                .map_or(0, |t| (t.period_millis / 1000) as u32),
+            parameters: r
+                .totp_details
+                .iter()
+                .flat_map(|t| &t.parameters)
+                .map(|p| TotpParameter {
+                    key: p.key.clone(),
+                    value_length: p.value_length as u8,
+                })
+                .collect(),
+        });
+        let barcode = match (&object.barcode, &object.rotating_barcode) {
+            (Some(b), _) => Some(Barcode {
+                format: barcode_format(&b.barcode_type).unwrap_or(BarcodeFormat::QrCode),
                 value: b.value.clone(),
                 alternate_text: b.alternate_text.clone(),
-            }
-        });
-
-        let state = match object.state.as_deref() {
-            Some("ACTIVE") => PassState::Active,
-            Some("INACTIVE") => PassState::Inactive,
-            Some("EXPIRED") => PassState::Expired,
-            Some("COMPLETED") => PassState::Completed,
-            _ => PassState::Active, // default
+                rotating,
+            }),
+            (None, Some(r)) => Some(Barcode {
+                format: barcode_format(&r.barcode_type).unwrap_or(BarcodeFormat::QrCode),
+                value: String::new(),
+                alternate_text: r.alternate_text.clone(),
+                rotating,
+            }),
+            (None, None) => None,
         };
 
+        let state = object
+            .state
+            .as_ref()
+            .and_then(pass_state)
+            .unwrap_or(PassState::Active);
+
         let title = object
             .card_title
             .as_ref()
@@ -139,7 +271,7 @@ impl From<&GenericObject> for Pass {
             .and_then(|h| h.default_value.as_ref())
             .map(|v| v.value.clone());
 
-        let fields = object
+        let mut fields: Vec<_> = object
             .text_modules_data
             .as_ref()
             .map(|modules| {
@@ -150,10 +282,40 @@ impl From<&GenericObject> for Pass {
                         label: module.header.clone().unwrap_or_default(),
                         value: module.body.clone().unwrap_or_default(),
                         text_alignment: None,
+                        link: None,
+                        format: None,
+                        change_message: None,
                     })
                     .collect()
             })
             .unwrap_or_default();
+        let links = object
+            .links_module_data
+            .as_ref()
+            .and_then(|l| l.uris.as_ref());
+        fields.extend(links.into_iter().flatten().map(|uri| {
+            let text = uri.description.clone().unwrap_or_else(|| uri.uri.clone());
+            crate::models::PassField {
+                key: uri.id.clone().unwrap_or_default(),
+                label: text.clone(),
+                value: text,
+                text_alignment: None,
+                link: Some(uri.uri.clone()),
+                format: None,
+                change_message: None,
+            }
+        }));
+
+        let mut translations = Vec::new();
+        collect_translations(object.card_title.as_ref(), &mut translations);
+        collect_translations(object.header.as_ref(), &mut translations);
+        for module in object.text_modules_data.iter().flatten() {
+            collect_translations(module.localized_header.as_ref(), &mut translations);
+            collect_translations(module.localized_body.as_ref(), &mut translations);
+        }
+        for uri in links.into_iter().flatten() {
+            collect_translations(uri.localized_description.as_ref(), &mut translations);
+        }
 
         Pass {
             id: object.id.clone(),
@@ -165,21 +327,207 @@ impl From<&GenericObject> for Pass {
                 logo: object.logo.as_ref().and_then(|l| l.into()),
                 background_color: object.hex_background_color.clone(),
                 foreground_color: None,
+                hero_image: object.hero_image.as_ref().and_then(|i| i.into()),
             },
             barcode,
             fields,
             linked_objects: object.linked_offer_ids.clone().unwrap_or_default(),
             state,
-            valid_time_interval: None,
+            valid_time_interval: object.valid_time_interval.as_ref().and_then(time_interval),
             updated_at: None,
+            translations,
+            default_locale: object
+                .card_title
+                .as_ref()
+                .and_then(|title| title.default_value.as_ref())
+                .map(|value| value.language.clone())
+                .filter(|language| language != DEFAULT_LANGUAGE),
+            relevance: Default::default(),
+            smart_tap_redemption_value: object.smart_tap_redemption_value.clone(),
+            app_link: object.app_link_data.as_ref().and_then(app_link),
+            images: object
+                .image_modules_data
+                .iter()
+                .flatten()
+                .filter_map(|module| module.main_image.as_ref())
+                .filter_map(|image| image.into())
+                .collect(),
+            grouping: object.grouping_info.as_ref().and_then(|info| {
+                Some(Grouping {
+                    id: info.grouping_id.clone()?,
+                    sort_index: info.sort_index,
+                })
+            }),
+            notifications: PassNotifications {
+                expiry: object
+                    .notifications
+                    .as_ref()
+                    .and_then(|n| n.expiry_notification.as_ref())
+                    .and_then(|n| n.enable_notification)
+                    .unwrap_or_default(),
+                upcoming: object
+                    .notifications
+                    .as_ref()
+                    .and_then(|n| n.upcoming_notification.as_ref())
+                    .and_then(|n| n.enable_notification)
+                    .unwrap_or_default(),
+            },
+            apple_assets: Default::default(),
+        }
+    }
+}
+
+impl Pass {
+    /// Convert a Google Wallet GenericObject, rejecting values the unified model cannot hold
+    ///
+    /// The `From` conversion falls back to a QR code and an active state for
+    /// barcode types and states it does not recognize. This fails with a
+    /// [`PorterError::ValidationError`] instead.
+    pub fn from_google_strict(object: &GenericObject) -> Result<Pass> {
+        let mut unknown = vec![];
+        if let Some(barcode) = &object.barcode {
+            if barcode_format(&barcode.barcode_type).is_none() {
+                unknown.push(format!("barcode type {}", barcode.barcode_type));
+            }
+        }
+        if let Some(state) = &object.state {
+            if pass_state(state).is_none() {
+                unknown.push(format!("state {}", state));
+            }
+        }
+
+        if unknown.is_empty() {
+            Ok(Pass::from(object))
+        } else {
+            Err(PorterError::ValidationError(format!(
+                "{} has unsupported {}",
+                object.id,
+                unknown.join(", ")
+            )))
         }
     }
 }
 
+/// The Google barcode type for a unified format
+fn barcode_type(format: &BarcodeFormat) -> &'static str {
+    match format {
+        BarcodeFormat::QrCode => "QR_CODE",
+        BarcodeFormat::Pdf417 => "PDF_417",
+        BarcodeFormat::Aztec => "AZTEC",
+        BarcodeFormat::Code128 => "CODE_128",
+    }
+}
+
+/// The unified barcode format for a Google barcode type or one of its aliases
+fn barcode_format(barcode_type: &str) -> Option<BarcodeFormat> {
+    match normalize(barcode_type).as_str() {
+        "qrcode" => Some(BarcodeFormat::QrCode),
+        "pdf417" => Some(BarcodeFormat::Pdf417),
+        "aztec" => Some(BarcodeFormat::Aztec),
+        "code128" => Some(BarcodeFormat::Code128),
+        _ => None,
+    }
+}
+
+/// The unified state for a Google object state
+fn pass_state(state: &State) -> Option<PassState> {
+    match state {
+        State::Active => Some(PassState::Active),
+        State::Inactive => Some(PassState::Inactive),
+        State::Expired => Some(PassState::Expired),
+        State::Completed => Some(PassState::Completed),
+        _ => None,
+    }
+}
+
+/// The unified interval for a Google one, starting at the epoch when only
+/// the end is set
+fn time_interval(interval: &GoogleTimeInterval) -> Option<TimeInterval> {
+    let utc = |date: &GoogleDateTime| date.date.with_timezone(&chrono::Utc);
+    match (&interval.start, &interval.end) {
+        (None, None) => None,
+        (start, end) => Some(TimeInterval {
+            start: start.as_ref().map_or(chrono::DateTime::UNIX_EPOCH, utc),
+            end: end.as_ref().map(utc),
+        }),
+    }
+}
+
+/// Google notification settings for the unified ones, if any are enabled
+fn notifications(notifications: &PassNotifications) -> Option<Notifications> {
+    if *notifications == PassNotifications::default() {
+        return None;
+    }
+    Some(Notifications {
+        expiry_notification: notifications.expiry.then_some(ExpiryNotification {
+            enable_notification: Some(true),
+        }),
+        upcoming_notification: notifications.upcoming.then_some(UpcomingNotification {
+            enable_notification: Some(true),
+        }),
+    })
+}
+
+/// Google app link data for a unified app link, with a web link when the
+/// target is a web address
+fn app_link_data(pass: &Pass, link: &AppLink) -> AppLinkData {
+    let info = |package_name: Option<String>| AppLinkInfo {
+        title: link
+            .title
+            .as_ref()
+            .map(|title| localized_string(pass, title)),
+        app_target: Some(AppTarget {
+            target_uri: Some(Uri {
+                uri: link.uri.clone(),
+                ..Default::default()
+            }),
+            package_name,
+        }),
+        ..Default::default()
+    };
+    let is_web = link.uri.starts_with("https://") || link.uri.starts_with("http://");
+
+    AppLinkData {
+        android_app_link_info: Some(info(link.android_package.clone())),
+        ios_app_link_info: Some(info(None)),
+        web_app_link_info: is_web.then(|| info(None)),
+    }
+}
+
+/// The unified app link for Google app link data, preferring the Android target
+fn app_link(data: &AppLinkData) -> Option<AppLink> {
+    let infos = [
+        &data.android_app_link_info,
+        &data.ios_app_link_info,
+        &data.web_app_link_info,
+    ];
+    let uri = infos.iter().find_map(|info| {
+        let target = info.as_ref()?.app_target.as_ref()?;
+        Some(target.target_uri.as_ref()?.uri.clone())
+    })?;
+
+    Some(AppLink {
+        uri,
+        title: infos.iter().find_map(|info| {
+            let title = info.as_ref()?.title.as_ref()?;
+            Some(title.default_value.as_ref()?.value.clone())
+        }),
+        android_package: data
+            .android_app_link_info
+            .as_ref()
+            .and_then(|info| info.app_target.as_ref())
+            .and_then(|target| target.package_name.clone()),
+        app_store_id: None,
+    })
+}
+
 impl From<&crate::models::Image> for Option<crate::google::types::Image> {
     fn from(image: &crate::models::Image) -> Self {
         Some(crate::google::types::Image {
-            source_uri: super::ImageUri { uri: image.source_uri.clone(), description: image.alt_text.clone() },
+            source_uri: super::ImageUri {
+                uri: image.source_uri.clone(),
+                description: image.alt_text.clone(),
+            },
             content_description: None,
         })
     }
@@ -194,6 +542,115 @@ impl From<&crate::google::types::Image> for Option<crate::models::Image> {
     }
 }
 
+impl From<&crate::models::Seat> for super::EventSeat {
+    fn from(seat: &crate::models::Seat) -> Self {
+        let localized = |value: &Option<String>| {
+            value.as_ref().map(|value| LocalizedString {
+                default_value: Some(TranslatedString {
+                    language: DEFAULT_LANGUAGE.to_string(),
+                    value: value.clone(),
+                }),
+                translated_values: None,
+            })
+        };
+        super::EventSeat {
+            seat: localized(&seat.number),
+            row: localized(&seat.row),
+            section: localized(&seat.section),
+        }
+    }
+}
+
+/// Pass field keys read into a FlightObject's boarding and seating info
+const BOARDING_FIELD_KEYS: &[&str] = &[
+    "boarding_group",
+    "boarding_position",
+    "sequence_number",
+    "seat",
+    "seat_class",
+];
+/// Pass field keys read into a FlightObject's reservation info
+const RESERVATION_FIELD_KEYS: &[&str] = &["confirmation_code", "eticket_number"];
+
+/// Convert a unified flight Pass to a Google Wallet boarding pass
+///
+/// Fields with the keys `passenger`, `boarding_group`, `boarding_position`,
+/// `sequence_number`, `seat`, `seat_class`, `confirmation_code` and
+/// `eticket_number` fill the passenger, boarding and reservation details;
+/// other fields become text and link modules as for a generic object.
+impl From<&Pass> for FlightObject {
+    fn from(pass: &Pass) -> Self {
+        let value = |key: &str| {
+            pass.fields
+                .iter()
+                .find(|field| field.key == key)
+                .map(|field| field.value.clone())
+        };
+
+        let has_boarding = BOARDING_FIELD_KEYS.iter().any(|key| value(key).is_some());
+        let has_reservation = RESERVATION_FIELD_KEYS
+            .iter()
+            .any(|key| value(key).is_some());
+
+        let boarding_and_seating_info = BoardingAndSeatingInfo {
+            boarding_group: value("boarding_group"),
+            boarding_position: value("boarding_position"),
+            sequence_number: value("sequence_number"),
+            seat_number: value("seat"),
+            seat_class: value("seat_class"),
+            boarding_door: None,
+        };
+        let reservation_info = ReservationInfo {
+            confirmation_code: value("confirmation_code"),
+            eticket_number: value("eticket_number"),
+            frequent_flyer_info: None,
+        };
+
+        let mut rest = pass.clone();
+        rest.fields.retain(|field| {
+            let key = field.key.as_str();
+            key != "passenger"
+                && !BOARDING_FIELD_KEYS.contains(&key)
+                && !RESERVATION_FIELD_KEYS.contains(&key)
+        });
+        let generic = GenericObject::from(&rest);
+
+        FlightObject {
+            id: generic.id,
+            class_id: generic.class_id,
+            state: generic.state,
+            barcode: generic.barcode,
+            rotating_barcode: generic.rotating_barcode,
+            smart_tap_redemption_value: generic.smart_tap_redemption_value,
+            app_link_data: generic.app_link_data,
+            passenger_name: value("passenger"),
+            boarding_and_seating_info: has_boarding.then_some(boarding_and_seating_info),
+            reservation_info: has_reservation.then_some(reservation_info),
+            valid_time_interval: generic.valid_time_interval,
+            text_modules_data: generic.text_modules_data,
+            links_module_data: generic.links_module_data,
+            image_modules_data: generic.image_modules_data,
+            grouping_info: generic.grouping_info,
+            notifications: generic.notifications,
+            messages: generic.messages,
+            notify_preference: None,
+        }
+    }
+}
+
+impl From<&super::EventSeat> for crate::models::Seat {
+    fn from(seat: &super::EventSeat) -> Self {
+        let value = |localized: &Option<LocalizedString>| {
+            Some(localized.as_ref()?.default_value.as_ref()?.value.clone())
+        };
+        crate::models::Seat {
+            section: value(&seat.section),
+            row: value(&seat.row),
+            number: value(&seat.seat),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,32 +667,398 @@ mod tests {
                 logo: None,
                 background_color: Some("#FF0000".to_string()),
                 foreground_color: None,
+                hero_image: None,
             },
             barcode: Some(Barcode {
                 format: BarcodeFormat::QrCode,
                 value: "12345".to_string(),
                 alternate_text: Some("12345".to_string()),
+                rotating: None,
             }),
             fields: vec![],
             linked_objects: vec![],
             state: PassState::Active,
             valid_time_interval: None,
             updated_at: None,
+            translations: vec![],
+            default_locale: None,
+            relevance: Default::default(),
+            smart_tap_redemption_value: None,
+            app_link: None,
+            images: vec![],
+            grouping: None,
+            notifications: Default::default(),
+            apple_assets: Default::default(),
         };
 
         let google_object: GenericObject = pass.into();
         assert_eq!(google_object.id, "test.pass");
         assert_eq!(google_object.class_id, "test.class");
-        assert_eq!(google_object.state, Some("ACTIVE".to_string()));
+        assert_eq!(google_object.state, Some(State::Active));
         assert!(google_object.barcode.is_some());
     }
 
+    #[test]
+    fn test_rotating_barcode_round_trip() {
+        let key = "3132333435363738393031323334353637383930";
+        let pass = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .rotating_barcode(
+                BarcodeFormat::QrCode,
+                "TICKET123",
+                RotatingBarcode::totp(key, 5),
+            )
+            .build();
+
+        let object = GenericObject::from(&pass);
+        let json = serde_json::to_value(&object).unwrap();
+        assert_eq!(json["barcode"]["value"], "TICKET123");
+        assert_eq!(
+            json["rotatingBarcode"],
+            serde_json::json!({
+                "type": "QR_CODE",
+                "valuePattern": "{totp_value_0}",
+                "totpDetails": {
+                    "periodMillis": "5000",
+                    "algorithm": "TOTP_SHA1",
+                    "parameters": [{ "key": key, "valueLength": 8 }]
+                }
+            })
+        );
+
+        let back = Pass::from(&object).barcode.unwrap();
+        assert_eq!(back.value, "TICKET123");
+        assert_eq!(back.rotating, Some(RotatingBarcode::totp(key, 5)));
+    }
+
+    #[test]
+    fn test_image_modules_round_trip() {
+        let pass = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .image("https://example.com/map.png", Some("Venue map".to_string()))
+            .link_field("site", "Website", "Example", "https://example.com")
+            .build();
+
+        let object = GenericObject::from(&pass);
+        let json = serde_json::to_value(&object).unwrap();
+        assert_eq!(
+            json["imageModulesData"],
+            serde_json::json!([{
+                "mainImage": {
+                    "sourceUri": {
+                        "uri": "https://example.com/map.png",
+                        "description": "Venue map"
+                    }
+                }
+            }])
+        );
+        assert_eq!(
+            json["linksModuleData"]["uris"][0]["uri"],
+            "https://example.com"
+        );
+
+        let back = Pass::from(&object);
+        assert_eq!(back.images.len(), 1);
+        assert_eq!(back.images[0].source_uri, "https://example.com/map.png");
+        assert_eq!(back.images[0].alt_text.as_deref(), Some("Venue map"));
+    }
+
+    #[test]
+    fn test_grouping_round_trip() {
+        let pass = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .grouping("order-1", Some(2))
+            .build();
+
+        let json = serde_json::to_value(FlightObject::from(&pass)).unwrap();
+        assert_eq!(
+            json["groupingInfo"],
+            serde_json::json!({ "groupingId": "order-1", "sortIndex": 2 })
+        );
+
+        let object = GenericObject::from(&pass);
+        assert_eq!(Pass::from(&object).grouping, pass.grouping);
+    }
+
+    #[test]
+    fn test_valid_time_interval_round_trip() {
+        let start = "2027-03-01T18:00:00Z".parse().unwrap();
+        let end = "2027-03-01T23:30:00Z".parse().unwrap();
+        let pass = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .valid_from(start)
+            .valid_until(end)
+            .build();
+
+        let json = serde_json::to_value(FlightObject::from(&pass)).unwrap();
+        assert_eq!(
+            json["validTimeInterval"],
+            serde_json::json!({
+                "start": { "date": "2027-03-01T18:00:00Z" },
+                "end": { "date": "2027-03-01T23:30:00Z" }
+            })
+        );
+
+        let interval = Pass::from(&GenericObject::from(&pass))
+            .valid_time_interval
+            .unwrap();
+        assert_eq!((interval.start, interval.end), (start, Some(end)));
+    }
+
+    #[test]
+    fn test_notifications_round_trip() {
+        let pass = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .valid_until(chrono::Utc::now())
+            .notify_before_expiry()
+            .build();
+
+        let object = GenericObject::from(&pass);
+        let json = serde_json::to_value(&object).unwrap();
+        assert_eq!(
+            json["notifications"],
+            serde_json::json!({ "expiryNotification": { "enableNotification": true } })
+        );
+        assert!(json["validTimeInterval"]["end"]["date"].is_string());
+        assert_eq!(Pass::from(&object).notifications, pass.notifications);
+
+        let quiet = crate::builder::PassBuilder::new("test.pass", "test.class").build();
+        assert!(GenericObject::from(&quiet).notifications.is_none());
+    }
+
+    #[test]
+    fn test_app_link_round_trip() {
+        let link = AppLink {
+            title: Some("Open in app".to_string()),
+            android_package: Some("com.example.app".to_string()),
+            ..AppLink::new("https://example.com/tickets/001")
+        };
+        let pass = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .app_link(link.clone())
+            .build();
+
+        let object = GenericObject::from(&pass);
+        let json = serde_json::to_value(&object).unwrap();
+        assert_eq!(
+            json["appLinkData"]["androidAppLinkInfo"]["appTarget"],
+            serde_json::json!({
+                "targetUri": { "uri": "https://example.com/tickets/001" },
+                "packageName": "com.example.app"
+            })
+        );
+        assert!(json["appLinkData"]["iosAppLinkInfo"].is_object());
+        assert!(json["appLinkData"]["webAppLinkInfo"].is_object());
+
+        assert_eq!(Pass::from(&object).app_link, Some(link));
+
+        let custom_scheme = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .app_link(AppLink::new("example://tickets/001"))
+            .build();
+        let json = serde_json::to_value(GenericObject::from(&custom_scheme)).unwrap();
+        assert!(json["appLinkData"].get("webAppLinkInfo").is_none());
+    }
+
+    #[test]
+    fn test_smart_tap_redemption_value_round_trip() {
+        let pass = crate::builder::PassBuilder::new("test.pass", "test.class")
+            .smart_tap_redemption_value("MEMBER-42")
+            .build();
+
+        let object = GenericObject::from(&pass);
+        let json = serde_json::to_value(&object).unwrap();
+        assert_eq!(json["smartTapRedemptionValue"], "MEMBER-42");
+        assert_eq!(
+            FlightObject::from(&pass)
+                .smart_tap_redemption_value
+                .as_deref(),
+            Some("MEMBER-42")
+        );
+
+        let back = Pass::from(&object);
+        assert_eq!(
+            back.smart_tap_redemption_value.as_deref(),
+            Some("MEMBER-42")
+        );
+    }
+
+    #[test]
+    fn test_pass_to_flight_object() {
+        let pass = crate::builder::PassBuilder::new("test.boarding", "test.flight")
+            .pass_type(crate::models::PassType::Flight)
+            .title("LHR to JFK")
+            .barcode(BarcodeFormat::Pdf417, "M1DOE/JOHN")
+            .field("passenger", "Passenger", "DOE/JOHN")
+            .field("seat", "Seat", "14C")
+            .field("boarding_group", "Group", "3")
+            .field("confirmation_code", "Booking", "ABC123")
+            .field("meal", "Meal", "Vegetarian")
+            .build();
+
+        let boarding_pass = FlightObject::from(&pass);
+        assert_eq!(boarding_pass.id, "test.boarding");
+        assert_eq!(boarding_pass.passenger_name.as_deref(), Some("DOE/JOHN"));
+        let seating = boarding_pass.boarding_and_seating_info.unwrap();
+        assert_eq!(seating.seat_number.as_deref(), Some("14C"));
+        assert_eq!(seating.boarding_group.as_deref(), Some("3"));
+        assert_eq!(
+            boarding_pass
+                .reservation_info
+                .unwrap()
+                .confirmation_code
+                .as_deref(),
+            Some("ABC123")
+        );
+        let modules = boarding_pass.text_modules_data.unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].id.as_deref(), Some("meal"));
+        assert_eq!(boarding_pass.barcode.unwrap().barcode_type, "PDF_417");
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary_pass_round_trip() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        // Deterministic xorshift input so failures are reproducible
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let bytes: Vec<u8> = (0..1 << 16)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect();
+
+        let mut input = Unstructured::new(&bytes);
+        while let Ok(pass) = Pass::arbitrary(&mut input) {
+            let back = Pass::from(&GenericObject::from(&pass));
+
+            assert_eq!(back.id, pass.id);
+            assert_eq!(back.class_id, pass.class_id);
+            assert_eq!(back.state, pass.state);
+            assert_eq!(back.header.title, pass.header.title);
+            assert_eq!(back.header.subtitle, pass.header.subtitle);
+            assert_eq!(back.linked_objects, pass.linked_objects);
+            assert_eq!(
+                back.barcode.map(|b| (b.format, b.value, b.alternate_text)),
+                pass.barcode.map(|b| (b.format, b.value, b.alternate_text))
+            );
+            // Fields with links move to the links module, which has no label
+            let (linked, plain): (Vec<_>, Vec<_>) =
+                pass.fields.iter().partition(|f| f.link.is_some());
+            assert_eq!(
+                back.fields
+                    .iter()
+                    .map(|f| (&f.key, &f.label, &f.value))
+                    .collect::<Vec<_>>(),
+                plain
+                    .iter()
+                    .map(|f| (&f.key, &f.label, &f.value))
+                    .chain(linked.iter().map(|f| (&f.key, &f.value, &f.value)))
+                    .collect::<Vec<_>>()
+            );
+            if input.is_empty() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_generic_object_snapshot() {
+        let pass = crate::PassBuilder::new("issuer.pass_001", "issuer.class_001")
+            .title("Concert Ticket")
+            .subtitle("The Rust Band")
+            .background_color("#4285F4")
+            .barcode_with_text(BarcodeFormat::QrCode, "TICKET123", "TICKET123")
+            .field("seat", "Seat", "A23")
+            .link_object("issuer.offer_001")
+            .build();
+
+        crate::snapshot::assert_snapshot(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/snapshots/generic_object.json"
+            ),
+            &GenericObject::from(&pass),
+        );
+    }
+
+    #[test]
+    fn test_translations_round_trip() {
+        let mut pass = crate::PassBuilder::new("test.pass", "test.class")
+            .title("Concert")
+            .field("seat", "Seat", "A23")
+            .build();
+        pass.translations = vec![
+            LocalizedValue {
+                value: "Concert".to_string(),
+                translations: [("de-DE".to_string(), "Konzert".to_string())].into(),
+            },
+            LocalizedValue {
+                value: "Seat".to_string(),
+                translations: [("de-DE".to_string(), "Platz".to_string())].into(),
+            },
+        ];
+
+        let google_object = GenericObject::from(&pass);
+        let title = google_object.card_title.as_ref().unwrap();
+        assert_eq!(
+            title.translated_values.as_ref().unwrap()[0].value,
+            "Konzert"
+        );
+        let modules = google_object.text_modules_data.as_ref().unwrap();
+        assert!(modules[0].localized_header.is_some());
+        assert!(modules[0].localized_body.is_none());
+
+        let back = Pass::from(&google_object);
+        assert_eq!(back.translations, pass.translations);
+    }
+
+    #[test]
+    fn test_link_fields_use_links_module() {
+        let pass = crate::PassBuilder::new("test.pass", "test.class")
+            .field("seat", "Seat", "A23")
+            .link_field("support", "Support", "+1 555 0100", "tel:+15550100")
+            .build();
+
+        let google_object = GenericObject::from(&pass);
+        assert_eq!(google_object.text_modules_data.as_ref().unwrap().len(), 1);
+        let links = google_object.links_module_data.as_ref().unwrap();
+        let uris = links.uris.as_ref().unwrap();
+        assert_eq!(uris[0].uri, "tel:+15550100");
+        assert_eq!(uris[0].description.as_deref(), Some("+1 555 0100"));
+
+        let back = Pass::from(&google_object);
+        let support = back.fields.iter().find(|f| f.key == "support").unwrap();
+        assert_eq!(support.link.as_deref(), Some("tel:+15550100"));
+    }
+
+    #[test]
+    fn test_strict_conversion() {
+        let mut object = GenericObject {
+            id: "test.object".to_string(),
+            state: Some(State::from("expired")),
+            barcode: Some(GoogleBarcode {
+                barcode_type: "pdf417".to_string(),
+                value: "1".to_string(),
+                alternate_text: None,
+            }),
+            ..Default::default()
+        };
+        let pass = Pass::from_google_strict(&object).unwrap();
+        assert_eq!(pass.state, PassState::Expired);
+        assert_eq!(pass.barcode.unwrap().format, BarcodeFormat::Pdf417);
+
+        object.barcode.as_mut().unwrap().barcode_type = "DATA_MATRIX".to_string();
+        assert!(Pass::from_google_strict(&object).is_err());
+        assert_eq!(
+            Pass::from(&object).barcode.unwrap().format,
+            BarcodeFormat::QrCode
+        );
+    }
+
     #[test]
     fn test_google_object_to_pass() {
         let google_object = GenericObject {
             id: "test.object".to_string(),
             class_id: "test.class".to_string(),
-            state: Some("ACTIVE".to_string()),
+            state: Some(State::Active),
             barcode: Some(GoogleBarcode {
                 barcode_type: "QR_CODE".to_string(),
                 value: "54321".to_string(),
@@ -271,6 +1094,7 @@ mod tests {
                 logo: None,
                 background_color: None,
                 foreground_color: None,
+                hero_image: None,
             },
             barcode: None,
             fields: vec![
@@ -279,18 +1103,33 @@ mod tests {
                     label: "Label 1".to_string(),
                     value: "Value 1".to_string(),
                     text_alignment: None,
+                    link: None,
+                    format: None,
+                    change_message: None,
                 },
                 crate::models::PassField {
                     key: "field2".to_string(),
                     label: "Label 2".to_string(),
                     value: "Value 2".to_string(),
                     text_alignment: None,
+                    link: None,
+                    format: None,
+                    change_message: None,
                 },
             ],
             linked_objects: vec![],
             state: PassState::Active,
             valid_time_interval: None,
             updated_at: None,
+            translations: vec![],
+            default_locale: None,
+            relevance: Default::default(),
+            smart_tap_redemption_value: None,
+            app_link: None,
+            images: vec![],
+            grouping: None,
+            notifications: Default::default(),
+            apple_assets: Default::default(),
         };
 
         let google_object: GenericObject = pass.into();
@@ -313,7 +1152,7 @@ mod tests {
         let google_object = GenericObject {
             id: "test.object".to_string(),
             class_id: "test.class".to_string(),
-            state: Some("ACTIVE".to_string()),
+            state: Some(State::Active),
             barcode: None,
             card_title: Some(LocalizedString {
                 default_value: Some(TranslatedString {
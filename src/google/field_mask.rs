@@ -0,0 +1,140 @@
+//! Field masks for partial updates
+//!
+//! A PATCH of a whole struct writes every field that is set, which can
+//! clobber data changed on the server since the struct was read. A
+//! [`FieldMask`] names the fields a PATCH may touch; only those are sent,
+//! and the mask itself goes along as the `updateMask` query.
+
+use crate::error::{PorterError, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Fields written by a masked PATCH, as JSON paths such as `loyaltyPoints.balance`
+///
+/// Masked PATCHes send the mask as their `updateMask` query, so the server
+/// writes exactly these fields. A masked field that is unset on the
+/// resource is sent as `null` and cleared on the server.
+///
+/// # Example
+///
+/// ```
+/// use porter::google::FieldMask;
+///
+/// let mask = FieldMask::new().field("state").field("barcode.value");
+/// assert_eq!(mask.to_string(), "state,barcode.value");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMask {
+    paths: Vec<String>,
+}
+
+impl FieldMask {
+    /// Create an empty mask
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field, by its camelCase JSON name with `.` between nested fields
+    pub fn field(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        if !self.paths.contains(&path) {
+            self.paths.push(path);
+        }
+        self
+    }
+
+    /// The masked paths, in the order they were added
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Whether the mask has no fields
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// The parts of `resource` named by the mask, as a PATCH body
+    pub fn apply(&self, resource: &impl Serialize) -> Result<Value> {
+        if self.is_empty() {
+            return Err(PorterError::ValidationError(
+                "Field mask has no fields".to_string(),
+            ));
+        }
+
+        let resource = serde_json::to_value(resource)?;
+        let mut body = Value::Object(Map::new());
+        for path in &self.paths {
+            let keys: Vec<&str> = path.split('.').collect();
+            if keys.iter().any(|key| key.is_empty()) {
+                return Err(PorterError::ValidationError(format!(
+                    "Invalid field mask path: {:?}",
+                    path
+                )));
+            }
+
+            let value = keys
+                .iter()
+                .try_fold(&resource, |value, key| value.get(key))
+                .cloned()
+                .unwrap_or(Value::Null);
+
+            let (last, parents) = keys.split_last().unwrap_or((&"", &[]));
+            let mut target = &mut body;
+            for key in parents {
+                if !target.get(key).is_some_and(Value::is_object) {
+                    target[key] = Value::Object(Map::new());
+                }
+                target = &mut target[key];
+            }
+            target[last] = value;
+        }
+        Ok(body)
+    }
+}
+
+impl std::fmt::Display for FieldMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.paths.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::google::types::{GenericObject, LocalizedString, State, TranslatedString};
+    use serde_json::json;
+
+    #[test]
+    fn test_apply() {
+        let object = GenericObject {
+            id: "issuer.a".to_string(),
+            class_id: "issuer.class".to_string(),
+            state: Some(State::Active),
+            header: Some(LocalizedString {
+                default_value: Some(TranslatedString {
+                    language: "en-US".to_string(),
+                    value: "Gold member".to_string(),
+                }),
+                translated_values: None,
+            }),
+            hex_background_color: Some("#000000".to_string()),
+            ..Default::default()
+        };
+
+        let mask = FieldMask::new()
+            .field("state")
+            .field("header.defaultValue.value")
+            .field("subheader");
+        assert_eq!(
+            mask.apply(&object).unwrap(),
+            json!({
+                "state": "ACTIVE",
+                "header": { "defaultValue": { "value": "Gold member" } },
+                "subheader": null
+            })
+        );
+
+        assert!(FieldMask::new().apply(&object).is_err());
+        assert!(FieldMask::new().field("header.").apply(&object).is_err());
+    }
+}
@@ -0,0 +1,485 @@
+//! Import of raw Google Wallet REST resources into the unified model
+//!
+//! Existing issuers can migrate by feeding the JSON returned by the Wallet
+//! API (or exported from the console) to [`Pass::from_google_json`] and
+//! [`PassClass::from_google_json`]. Anything the unified model cannot
+//! represent is reported as an [`ImportWarning`] instead of being dropped
+//! silently.
+
+use crate::error::{PorterError, Result};
+use crate::google::types::{GenericObject, Image, State};
+use crate::models::{Pass, PassClass, PassField, PassType, ReviewStatus};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Something in an imported resource that did not carry over exactly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportWarning {
+    /// JSON path of the affected value, such as `$.seatInfo.gate`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Keys platform-managed or otherwise safe to ignore on import
+const IGNORED_KEYS: &[&str] = &[
+    "kind",
+    "version",
+    "hasUsers",
+    "hasLinkedDevice",
+    "classReference",
+];
+
+/// Object keys carried over by the GenericObject conversion
+const OBJECT_KEYS: &[&str] = &[
+    "id",
+    "classId",
+    "state",
+    "barcode",
+    "rotatingBarcode",
+    "smartTapRedemptionValue",
+    "appLinkData",
+    "imageModulesData",
+    "groupingInfo",
+    "notifications",
+    "cardTitle",
+    "header",
+    "heroImage",
+    "logo",
+    "hexBackgroundColor",
+    "linkedOfferIds",
+    "linksModuleData",
+    "textModulesData",
+    "validTimeInterval",
+];
+
+/// Class keys carried over to a PassClass
+const CLASS_KEYS: &[&str] = &[
+    "id",
+    "issuerName",
+    "reviewStatus",
+    "logo",
+    "programLogo",
+    "hexBackgroundColor",
+    "textModulesData",
+];
+
+impl Pass {
+    /// Import a Google Wallet object from its REST JSON representation
+    ///
+    /// Generic, event ticket, loyalty, gift card, offer, flight and transit
+    /// objects are accepted. The pass type is taken from `kind` when present
+    /// and otherwise inferred from type-specific fields, which are added to
+    /// the pass as fields. Returns the pass together with everything that
+    /// could not be represented.
+    pub fn from_google_json(json: &str) -> Result<(Pass, Vec<ImportWarning>)> {
+        let map = parse_resource(json)?;
+        for key in ["id", "classId"] {
+            required_string(&map, key)?;
+        }
+
+        let mut warnings = vec![];
+        let object: GenericObject = serde_json::from_value(Value::Object(map.clone()))?;
+        let mut pass = Pass::from(&object);
+        pass.pass_type = object_type(&map);
+
+        if let Some(barcode) = &object.barcode {
+            if !["QR_CODE", "PDF_417", "AZTEC", "CODE_128"].contains(&barcode.barcode_type.as_str())
+            {
+                warn(
+                    &mut warnings,
+                    "$.barcode.type",
+                    format!(
+                        "unsupported type {}, imported as QR code",
+                        barcode.barcode_type
+                    ),
+                );
+            }
+        }
+        if let Some(state @ State::Unknown(_)) = &object.state {
+            warn(
+                &mut warnings,
+                "$.state",
+                format!("unknown state {}, imported as active", state),
+            );
+        }
+        for bound in ["start", "end"] {
+            let date = map
+                .get("validTimeInterval")
+                .and_then(|interval| interval.get(bound))
+                .and_then(|date| date.get("date"))
+                .and_then(Value::as_str);
+            if let Some(date) =
+                date.filter(|date| chrono::DateTime::parse_from_rfc3339(date).is_err())
+            {
+                warn(
+                    &mut warnings,
+                    &format!("$.validTimeInterval.{}", bound),
+                    format!("{} has no UTC offset and was read as UTC", date),
+                );
+            }
+        }
+
+        let consumed = type_fields(&pass.pass_type, &map, &mut pass.fields);
+        for key in map.keys() {
+            if !OBJECT_KEYS.contains(&key.as_str())
+                && !consumed.contains(&key.as_str())
+                && !IGNORED_KEYS.contains(&key.as_str())
+            {
+                warn(
+                    &mut warnings,
+                    &format!("$.{}", key),
+                    "not represented in the unified model",
+                );
+            }
+        }
+
+        Ok((pass, warnings))
+    }
+}
+
+impl PassClass {
+    /// Import a Google Wallet class from its REST JSON representation
+    ///
+    /// The pass type is taken from `kind` when present and otherwise inferred
+    /// from type-specific fields. Returns the class together with everything
+    /// that could not be represented.
+    pub fn from_google_json(json: &str) -> Result<(PassClass, Vec<ImportWarning>)> {
+        let map = parse_resource(json)?;
+        let id = required_string(&map, "id")?;
+        let pass_type = class_type(&map);
+
+        let mut warnings = vec![];
+        let issuer_name = string(&map, "issuerName");
+        if issuer_name.is_none() && pass_type != PassType::Generic {
+            warn(&mut warnings, "$.issuerName", "missing, imported as empty");
+        }
+
+        let review_status = match string(&map, "reviewStatus").map(|s| s.to_lowercase()) {
+            Some(status) if status == "underreview" || status == "under_review" => {
+                ReviewStatus::UnderReview
+            }
+            Some(status) if status == "approved" => ReviewStatus::Approved,
+            Some(status) if status == "rejected" => ReviewStatus::Rejected,
+            Some(status) if status == "draft" => ReviewStatus::Draft,
+            status => {
+                if let Some(status) = status {
+                    warn(
+                        &mut warnings,
+                        "$.reviewStatus",
+                        format!("unknown status {}, imported as draft", status),
+                    );
+                }
+                ReviewStatus::Draft
+            }
+        };
+
+        let logo = ["logo", "programLogo"]
+            .iter()
+            .filter_map(|key| map.get(*key))
+            .find_map(|logo| serde_json::from_value::<Image>(logo.clone()).ok())
+            .and_then(|logo| (&logo).into());
+
+        let fields = serde_json::from_value::<GenericObject>(Value::Object(map.clone()))
+            .map(|object| Pass::from(&object).fields)
+            .unwrap_or_default();
+
+        for key in map.keys() {
+            if !CLASS_KEYS.contains(&key.as_str()) && !IGNORED_KEYS.contains(&key.as_str()) {
+                warn(
+                    &mut warnings,
+                    &format!("$.{}", key),
+                    "not represented in the unified model",
+                );
+            }
+        }
+
+        let class = PassClass {
+            id,
+            pass_type,
+            issuer_name: issuer_name.unwrap_or_default(),
+            review_status,
+            logo,
+            background_color: string(&map, "hexBackgroundColor"),
+            foreground_color: None,
+            fields,
+        };
+        Ok((class, warnings))
+    }
+}
+
+fn parse_resource(json: &str) -> Result<Map<String, Value>> {
+    match serde_json::from_str(json)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(PorterError::ValidationError(
+            "Google Wallet resource must be a JSON object".to_string(),
+        )),
+    }
+}
+
+fn required_string(map: &Map<String, Value>, key: &str) -> Result<String> {
+    string(map, key)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| PorterError::ValidationError(format!("Missing required field `{}`", key)))
+}
+
+fn string(map: &Map<String, Value>, key: &str) -> Option<String> {
+    map.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// The default value of a LocalizedString
+fn localized(value: Option<&Value>) -> Option<String> {
+    value?
+        .pointer("/defaultValue/value")?
+        .as_str()
+        .map(str::to_string)
+}
+
+fn warn(warnings: &mut Vec<ImportWarning>, path: &str, message: impl Into<String>) {
+    warnings.push(ImportWarning {
+        path: path.to_string(),
+        message: message.into(),
+    });
+}
+
+/// Resource type named by `kind`, e.g. "walletobjects#eventTicketObject"
+fn kind(map: &Map<String, Value>) -> Option<PassType> {
+    let kind = map.get("kind")?.as_str()?.rsplit('#').next()?;
+    let kind = kind.strip_suffix("Object").or(kind.strip_suffix("Class"))?;
+    match kind {
+        "generic" => Some(PassType::Generic),
+        "eventTicket" => Some(PassType::EventTicket),
+        "loyalty" => Some(PassType::Loyalty),
+        "giftCard" => Some(PassType::GiftCard),
+        "offer" => Some(PassType::Offer),
+        "flight" => Some(PassType::Flight),
+        "transit" => Some(PassType::Transit),
+        _ => None,
+    }
+}
+
+/// Pass type of an object, from `kind` or type-specific fields
+fn object_type(map: &Map<String, Value>) -> PassType {
+    let has = |keys: &[&str]| keys.iter().any(|key| map.contains_key(*key));
+    kind(map).unwrap_or(
+        if has(&["seatInfo", "ticketHolderName", "reservationInfo"]) {
+            if has(&["boardingAndSeatingInfo", "passengerName"]) {
+                PassType::Flight
+            } else {
+                PassType::EventTicket
+            }
+        } else if has(&["passengerName", "boardingAndSeatingInfo"]) {
+            PassType::Flight
+        } else if has(&["loyaltyPoints", "accountId", "accountName"]) {
+            PassType::Loyalty
+        } else if has(&["cardNumber", "balance", "pin"]) {
+            PassType::GiftCard
+        } else if has(&["ticketLeg", "ticketLegs", "tripType"]) {
+            PassType::Transit
+        } else {
+            PassType::Generic
+        },
+    )
+}
+
+/// Pass type of a class, from `kind` or type-specific fields
+fn class_type(map: &Map<String, Value>) -> PassType {
+    let has = |keys: &[&str]| keys.iter().any(|key| map.contains_key(*key));
+    kind(map).unwrap_or(if has(&["eventName", "venue", "eventId"]) {
+        PassType::EventTicket
+    } else if has(&["programName", "programLogo"]) {
+        PassType::Loyalty
+    } else if has(&["flightHeader", "origin", "destination"]) {
+        PassType::Flight
+    } else if has(&["transitType", "transitOperatorName"]) {
+        PassType::Transit
+    } else if has(&["redemptionChannel", "provider"]) {
+        PassType::Offer
+    } else if has(&["merchantName", "pinLabel"]) {
+        PassType::GiftCard
+    } else {
+        PassType::Generic
+    })
+}
+
+/// Add type-specific values as fields, returning the keys consumed
+fn type_fields(
+    pass_type: &PassType,
+    map: &Map<String, Value>,
+    fields: &mut Vec<PassField>,
+) -> &'static [&'static str] {
+    let mut add = |key: &str, label: &str, value: Option<String>| {
+        if let Some(value) = value {
+            fields.push(PassField {
+                key: key.to_string(),
+                label: label.to_string(),
+                value,
+                text_alignment: None,
+                link: None,
+                format: None,
+                change_message: None,
+            });
+        }
+    };
+
+    match pass_type {
+        PassType::EventTicket => {
+            let seat = map.get("seatInfo");
+            add(
+                "section",
+                "Section",
+                localized(seat.and_then(|s| s.get("section"))),
+            );
+            add("row", "Row", localized(seat.and_then(|s| s.get("row"))));
+            add("seat", "Seat", localized(seat.and_then(|s| s.get("seat"))));
+            add("gate", "Gate", localized(seat.and_then(|s| s.get("gate"))));
+            add(
+                "ticket_holder",
+                "Ticket holder",
+                string(map, "ticketHolderName"),
+            );
+            add(
+                "ticket_number",
+                "Ticket number",
+                string(map, "ticketNumber"),
+            );
+            &["seatInfo", "ticketHolderName", "ticketNumber"]
+        }
+        PassType::Loyalty => {
+            add("account_name", "Member", string(map, "accountName"));
+            add("account_id", "Member ID", string(map, "accountId"));
+            let points = map.get("loyaltyPoints");
+            let balance = points.and_then(|p| p.get("balance")).and_then(|b| {
+                b.get("int")
+                    .or(b.get("string"))
+                    .or(b.get("double"))
+                    .map(|v| v.as_str().map_or(v.to_string(), str::to_string))
+            });
+            let label = points
+                .and_then(|p| p.get("label"))
+                .and_then(Value::as_str)
+                .unwrap_or("Points");
+            add("points", label, balance);
+            &["accountName", "accountId", "loyaltyPoints"]
+        }
+        PassType::GiftCard => {
+            add("card_number", "Card number", string(map, "cardNumber"));
+            let balance = map.get("balance").and_then(|balance| {
+                let micros = balance.get("micros")?;
+                let micros = match micros {
+                    Value::String(s) => s.parse::<i64>().ok()?,
+                    other => other.as_i64()?,
+                };
+                let currency = balance.get("currencyCode")?.as_str()?;
+                Some(format!("{:.2} {}", micros as f64 / 1_000_000.0, currency))
+            });
+            add("balance", "Balance", balance);
+            &["cardNumber", "balance"]
+        }
+        PassType::Flight => {
+            add("passenger", "Passenger", string(map, "passengerName"));
+            &["passengerName"]
+        }
+        PassType::Transit => {
+            add(
+                "ticket_number",
+                "Ticket number",
+                string(map, "ticketNumber"),
+            );
+            add("passengers", "Passengers", string(map, "passengerNames"));
+            &["ticketNumber", "passengerNames"]
+        }
+        PassType::Generic | PassType::Offer => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_event_ticket_object() {
+        let json = r#"{
+            "kind": "walletobjects#eventTicketObject",
+            "id": "issuer.ticket",
+            "classId": "issuer.concert",
+            "state": "ACTIVE",
+            "barcode": { "type": "QR_CODE", "value": "TICKET123" },
+            "seatInfo": {
+                "seat": { "defaultValue": { "language": "en-US", "value": "23" } },
+                "row": { "defaultValue": { "language": "en-US", "value": "A" } }
+            },
+            "ticketHolderName": "Jane Smith",
+            "validTimeInterval": { "end": { "date": "2027-03-01T23:00:00Z" } },
+            "groupingInfo": { "groupingId": "order-1" },
+            "infoModuleData": { "showLastUpdateTime": true }
+        }"#;
+
+        let (pass, warnings) = Pass::from_google_json(json).unwrap();
+        assert_eq!(pass.pass_type, PassType::EventTicket);
+        assert_eq!(pass.barcode.unwrap().value, "TICKET123");
+        let fields: Vec<_> = pass.fields.iter().map(|f| f.value.as_str()).collect();
+        assert_eq!(fields, ["A", "23", "Jane Smith"]);
+        assert!(pass.valid_time_interval.unwrap().end.is_some());
+        assert_eq!(pass.grouping.unwrap().id, "order-1");
+        assert_eq!(
+            warnings,
+            vec![ImportWarning {
+                path: "$.infoModuleData".to_string(),
+                message: "not represented in the unified model".to_string(),
+            }]
+        );
+
+        assert!(Pass::from_google_json(r#"{ "id": "issuer.ticket" }"#).is_err());
+    }
+
+    #[test]
+    fn test_import_local_date() {
+        let json = r#"{
+            "id": "issuer.pass",
+            "classId": "issuer.class",
+            "validTimeInterval": { "start": { "date": "2027-03-01T18:00:00" } }
+        }"#;
+
+        let (pass, warnings) = Pass::from_google_json(json).unwrap();
+        assert_eq!(
+            pass.valid_time_interval.unwrap().start.to_rfc3339(),
+            "2027-03-01T18:00:00+00:00"
+        );
+        assert_eq!(
+            warnings,
+            vec![ImportWarning {
+                path: "$.validTimeInterval.start".to_string(),
+                message: "2027-03-01T18:00:00 has no UTC offset and was read as UTC".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_import_loyalty_class() {
+        let json = r##"{
+            "id": "issuer.rewards",
+            "issuerName": "Example Coffee",
+            "programName": "Rewards",
+            "reviewStatus": "APPROVED",
+            "programLogo": { "sourceUri": { "uri": "https://example.com/logo.png" } },
+            "hexBackgroundColor": "#6F4E37"
+        }"##;
+
+        let (class, warnings) = PassClass::from_google_json(json).unwrap();
+        assert_eq!(class.pass_type, PassType::Loyalty);
+        assert_eq!(class.issuer_name, "Example Coffee");
+        assert_eq!(class.review_status, ReviewStatus::Approved);
+        assert_eq!(
+            class.logo.unwrap().source_uri,
+            "https://example.com/logo.png"
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "$.programName");
+    }
+}
@@ -0,0 +1,120 @@
+//! RS256 signing with parsed keys
+
+use crate::credentials::rsa_key_pair;
+use crate::error::{PorterError, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ring::rand::SystemRandom;
+use ring::signature::{RsaKeyPair, RSA_PKCS1_SHA256};
+use serde::Serialize;
+
+/// A private key parsed once and reused for every signature
+pub(crate) struct JwtSigner {
+    key: RsaKeyPair,
+    /// Encoded JOSE header, identical for every token
+    header: String,
+    rng: SystemRandom,
+}
+
+/// Buffers reused between signatures
+#[derive(Default)]
+pub(crate) struct SignBuffers {
+    json: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl JwtSigner {
+    /// Parse a PKCS#8 or PKCS#1 PEM key, naming it `kid` in the header
+    pub(crate) fn new(private_key_pem: &str, kid: Option<&str>) -> Result<Self> {
+        let key = rsa_key_pair(private_key_pem)?;
+
+        let header = match kid {
+            Some(kid) => serde_json::json!({"typ": "JWT", "alg": "RS256", "kid": kid}),
+            None => serde_json::json!({"typ": "JWT", "alg": "RS256"}),
+        };
+        Ok(Self {
+            key,
+            header: URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Sign `claims` into a compact JWT
+    pub(crate) fn sign(&self, claims: &impl Serialize) -> Result<String> {
+        self.sign_with(claims, &mut SignBuffers::default())
+    }
+
+    /// Sign `claims`, reusing `buffers` from earlier signatures
+    pub(crate) fn sign_with(
+        &self,
+        claims: &impl Serialize,
+        buffers: &mut SignBuffers,
+    ) -> Result<String> {
+        buffers.json.clear();
+        serde_json::to_writer(&mut buffers.json, claims)?;
+
+        let encoded_len = |len: usize| base64::encoded_len(len, false).unwrap_or(0);
+        let mut token = String::with_capacity(
+            self.header.len()
+                + encoded_len(buffers.json.len())
+                + encoded_len(self.key.public().modulus_len())
+                + 2,
+        );
+        token.push_str(&self.header);
+        token.push('.');
+        URL_SAFE_NO_PAD.encode_string(&buffers.json, &mut token);
+
+        buffers.signature.resize(self.key.public().modulus_len(), 0);
+        self.key
+            .sign(
+                &RSA_PKCS1_SHA256,
+                &self.rng,
+                token.as_bytes(),
+                &mut buffers.signature,
+            )
+            .map_err(|_| PorterError::SignatureError("RS256 signing failed".to_string()))?;
+        token.push('.');
+        URL_SAFE_NO_PAD.encode_string(&buffers.signature, &mut token);
+        Ok(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::google::types::{GenericObject, JwtObjectPayload, JwtPayload};
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_signed_claims_decode() {
+        let signer =
+            JwtSigner::new(include_str!("../apple/testdata/pass_key.pem"), Some("2026")).unwrap();
+        let objects = JwtObjectPayload {
+            generic_objects: Some(vec![GenericObject::reference(
+                "issuer.pass",
+                "issuer.class",
+            )]),
+            ..Default::default()
+        };
+        let claims = JwtPayload {
+            iss: Cow::Borrowed("wallet@example.iam.gserviceaccount.com"),
+            aud: Cow::Borrowed("google"),
+            typ: Cow::Borrowed("savetowallet"),
+            iat: 1_800_000_000,
+            exp: None,
+            origins: None,
+            payload: Cow::Borrowed(&objects),
+        };
+
+        let jwt = signer
+            .sign_with(&claims, &mut SignBuffers::default())
+            .unwrap();
+        let header = jsonwebtoken::decode_header(&jwt).unwrap();
+        assert_eq!(header.kid.as_deref(), Some("2026"));
+
+        let decoded = JwtPayload::from_save_url(&jwt).unwrap();
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&claims).unwrap()
+        );
+    }
+}
@@ -1,6 +1,28 @@
+pub mod batch;
+pub mod builder;
+mod cache;
+pub mod callback;
 pub mod client;
 pub mod convert;
+pub mod field_mask;
+pub mod import;
+mod jwt;
+pub mod save_link;
+#[cfg(feature = "smart-tap")]
+pub mod smart_tap;
+pub mod smart_tap_keys;
+mod strict;
 pub mod types;
 
-pub use client::{GoogleWalletClient, GoogleWalletConfig, PassClient};
+pub use batch::{BatchItemResult, BatchRequest, BatchResource};
+pub use builder::{GenericClassBuilder, OfferClassBuilder};
+pub use callback::{CallbackEventType, CallbackMessage, CallbackVerifier};
+pub use client::{
+    ClassSync, DryRunRequest, GoogleWalletClient, GoogleWalletConfig, HolderUpdate, PassClient,
+    PassTransfer, SigningKey,
+};
+pub use field_mask::FieldMask;
+pub use import::ImportWarning;
+pub use save_link::{SaveLinkBuilder, SaveLinkResource};
+pub use smart_tap_keys::SmartTapKeyRegistry;
 pub use types::*;
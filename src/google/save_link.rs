@@ -0,0 +1,137 @@
+//! Save links that install several passes at once
+//!
+//! A single "Add to Google Wallet" button can save objects of different
+//! types together, and insert the classes they need on the fly.
+//! [`SaveLinkBuilder`] collects them into a [`JwtObjectPayload`] for
+//! [`GoogleWalletClient::generate_save_link`](super::GoogleWalletClient::generate_save_link)
+//! or [`generate_save_url_for`](super::GoogleWalletClient::generate_save_url_for).
+
+use crate::error::{PorterError, Result};
+use crate::google::types::*;
+
+/// A class or object that can be carried by a save link
+pub trait SaveLinkResource {
+    /// Add the resource to the list of its type in `payload`
+    fn add_to(self, payload: &mut JwtObjectPayload);
+}
+
+macro_rules! save_link_resource {
+    ($($resource:ty => $field:ident),* $(,)?) => {
+        $(
+            impl SaveLinkResource for $resource {
+                fn add_to(self, payload: &mut JwtObjectPayload) {
+                    payload.$field.get_or_insert_with(Vec::new).push(self);
+                }
+            }
+        )*
+    };
+}
+
+save_link_resource! {
+    GenericObject => generic_objects,
+    EventTicketObject => event_ticket_objects,
+    LoyaltyObject => loyalty_objects,
+    OfferObject => offer_objects,
+    GiftCardObject => gift_card_objects,
+    TransitObject => transit_objects,
+    FlightObject => flight_objects,
+    GenericClass => generic_classes,
+    LoyaltyClass => loyalty_classes,
+    OfferClass => offer_classes,
+    GiftCardClass => gift_card_classes,
+    TransitClass => transit_classes,
+    FlightClass => flight_classes,
+}
+
+/// Builder for a save link payload mixing object and class types
+///
+/// # Example
+///
+/// ```
+/// use porter::google::{EventTicketObject, LoyaltyClass, LoyaltyObject, SaveLinkBuilder};
+///
+/// let payload = SaveLinkBuilder::new()
+///     .resource(EventTicketObject::reference("issuer_id.ticket_1", "issuer_id.concert"))
+///     .resource(EventTicketObject::reference("issuer_id.ticket_2", "issuer_id.concert"))
+///     .resource(LoyaltyClass {
+///         id: "issuer_id.rewards".to_string(),
+///         ..Default::default()
+///     })
+///     .resource(LoyaltyObject::reference("issuer_id.member_1", "issuer_id.rewards"))
+///     .build()?;
+///
+/// assert_eq!(payload.event_ticket_objects.unwrap().len(), 2);
+/// # Ok::<(), porter::PorterError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SaveLinkBuilder {
+    payload: JwtObjectPayload,
+}
+
+impl SaveLinkBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an object, or a class to insert along with the objects
+    pub fn resource(mut self, resource: impl SaveLinkResource) -> Self {
+        resource.add_to(&mut self.payload);
+        self
+    }
+
+    /// Add several objects or classes of one type
+    pub fn resources<R: SaveLinkResource>(self, resources: impl IntoIterator<Item = R>) -> Self {
+        resources.into_iter().fold(self, Self::resource)
+    }
+
+    /// The payload, which must contain at least one object
+    pub fn build(self) -> Result<JwtObjectPayload> {
+        if self.payload.is_empty() {
+            return Err(PorterError::ValidationError(
+                "Save link payload contains no objects".to_string(),
+            ));
+        }
+        Ok(self.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build() {
+        let payload = SaveLinkBuilder::new()
+            .resource(GenericClass {
+                id: "issuer.class".to_string(),
+                ..Default::default()
+            })
+            .resources([
+                GenericObject::reference("issuer.a", "issuer.class"),
+                GenericObject::reference("issuer.b", "issuer.class"),
+            ])
+            .resource(OfferObject::reference("issuer.offer", "issuer.offers"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({
+                "genericObjects": [
+                    { "id": "issuer.a", "classId": "issuer.class" },
+                    { "id": "issuer.b", "classId": "issuer.class" }
+                ],
+                "offerObjects": [{ "id": "issuer.offer", "classId": "issuer.offers" }],
+                "genericClasses": [{ "id": "issuer.class" }]
+            })
+        );
+
+        let classes_only = SaveLinkBuilder::new().resource(FlightClass {
+            id: "issuer.flight".to_string(),
+            ..Default::default()
+        });
+        assert!(classes_only.build().is_err());
+    }
+}
@@ -0,0 +1,347 @@
+//! Decoding of Smart Tap redemption payloads
+//!
+//! Terminals receive Smart Tap data from the device as NDEF messages. The
+//! service objects inside are encrypted for the collector's long-term key and
+//! can be opened with [`SmartTapDecryptor`].
+
+use crate::error::{PorterError, Result};
+use aes::cipher::{KeyIvInit, StreamCipher};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use p256::pkcs8::DecodePrivateKey;
+use p256::{PublicKey, SecretKey};
+use sha2::Sha256;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+const FLAG_MESSAGE_BEGIN: u8 = 0x80;
+const FLAG_MESSAGE_END: u8 = 0x40;
+const FLAG_CHUNKED: u8 = 0x20;
+const FLAG_SHORT_RECORD: u8 = 0x10;
+const FLAG_ID_LENGTH: u8 = 0x08;
+const TNF_MASK: u8 = 0x07;
+
+const MAC_LENGTH: usize = 32;
+const AES_KEY_LENGTH: usize = 16;
+
+/// Record type carrying the service number (the object's `smartTapRedemptionValue`)
+const SERVICE_NUMBER_TYPE: &[u8] = b"n";
+
+/// Type name format of an NDEF record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeNameFormat {
+    Empty,
+    WellKnown,
+    Media,
+    AbsoluteUri,
+    External,
+    Unknown,
+    Unchanged,
+    Reserved,
+}
+
+impl From<u8> for TypeNameFormat {
+    fn from(tnf: u8) -> Self {
+        match tnf & TNF_MASK {
+            0x00 => TypeNameFormat::Empty,
+            0x01 => TypeNameFormat::WellKnown,
+            0x02 => TypeNameFormat::Media,
+            0x03 => TypeNameFormat::AbsoluteUri,
+            0x04 => TypeNameFormat::External,
+            0x05 => TypeNameFormat::Unknown,
+            0x06 => TypeNameFormat::Unchanged,
+            _ => TypeNameFormat::Reserved,
+        }
+    }
+}
+
+/// A single record of an NDEF message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdefRecord {
+    pub tnf: TypeNameFormat,
+    pub record_type: Vec<u8>,
+    pub id: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+impl NdefRecord {
+    /// Parse a complete NDEF message into its records
+    pub fn parse_message(bytes: &[u8]) -> Result<Vec<NdefRecord>> {
+        let mut records = Vec::new();
+        let mut offset = 0;
+
+        while offset < bytes.len() {
+            let header = bytes[offset];
+            offset += 1;
+
+            if records.is_empty() && header & FLAG_MESSAGE_BEGIN == 0 {
+                return Err(invalid_ndef(
+                    "first record is missing the message begin flag",
+                ));
+            }
+            if header & FLAG_CHUNKED != 0 {
+                return Err(invalid_ndef("chunked records are not supported"));
+            }
+
+            let type_length = take(bytes, &mut offset, 1)?[0] as usize;
+            let payload_length = if header & FLAG_SHORT_RECORD != 0 {
+                take(bytes, &mut offset, 1)?[0] as usize
+            } else {
+                let length = take(bytes, &mut offset, 4)?;
+                u32::from_be_bytes([length[0], length[1], length[2], length[3]]) as usize
+            };
+            let id_length = if header & FLAG_ID_LENGTH != 0 {
+                take(bytes, &mut offset, 1)?[0] as usize
+            } else {
+                0
+            };
+
+            let record_type = take(bytes, &mut offset, type_length)?.to_vec();
+            let id = take(bytes, &mut offset, id_length)?.to_vec();
+            let payload = take(bytes, &mut offset, payload_length)?.to_vec();
+
+            records.push(NdefRecord {
+                tnf: TypeNameFormat::from(header),
+                record_type,
+                id,
+                payload,
+            });
+
+            if header & FLAG_MESSAGE_END != 0 {
+                break;
+            }
+        }
+
+        if records.is_empty() {
+            return Err(invalid_ndef("message contains no records"));
+        }
+
+        Ok(records)
+    }
+
+    /// Parse the payload of this record as a nested NDEF message
+    pub fn nested(&self) -> Result<Vec<NdefRecord>> {
+        Self::parse_message(&self.payload)
+    }
+
+    /// The record type as a string, if it is valid UTF-8
+    pub fn type_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.record_type).ok()
+    }
+}
+
+/// A service object (loyalty, offer, gift card, ...) from a decrypted payload
+#[derive(Debug, Clone)]
+pub struct ServiceObject {
+    /// Record type of the service, e.g. `ly` for loyalty
+    pub service_type: String,
+    pub records: Vec<NdefRecord>,
+}
+
+impl ServiceObject {
+    /// The redemption value the issuer set on the object, if present
+    pub fn redemption_value(&self) -> Option<String> {
+        let record = self
+            .records
+            .iter()
+            .find(|record| record.record_type == SERVICE_NUMBER_TYPE)?;
+
+        // The first byte of the payload describes the value's format
+        let (_format, value) = record.payload.split_first()?;
+        String::from_utf8(value.to_vec()).ok()
+    }
+}
+
+/// Session values exchanged between terminal and device during a tap
+#[derive(Debug, Clone)]
+pub struct SmartTapSession {
+    pub terminal_nonce: Vec<u8>,
+    pub device_nonce: Vec<u8>,
+    /// Compressed SEC1 ephemeral public key sent by the device
+    pub device_ephemeral_public_key: Vec<u8>,
+}
+
+/// Decrypts Smart Tap payloads with a collector's long-term private key
+pub struct SmartTapDecryptor {
+    private_key: SecretKey,
+}
+
+impl SmartTapDecryptor {
+    /// Create a decryptor from a PEM encoded P-256 private key (PKCS#8 or SEC1)
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        let private_key = SecretKey::from_pkcs8_pem(pem)
+            .or_else(|_| SecretKey::from_sec1_pem(pem))
+            .map_err(|e| PorterError::ConfigError(format!("Invalid Smart Tap key: {}", e)))?;
+        Ok(Self { private_key })
+    }
+
+    /// Create a decryptor from an existing private key
+    pub fn new(private_key: SecretKey) -> Self {
+        Self { private_key }
+    }
+
+    /// Decrypt an encrypted payload (ciphertext followed by its HMAC-SHA256 tag)
+    pub fn decrypt(&self, session: &SmartTapSession, encrypted: &[u8]) -> Result<Vec<u8>> {
+        if encrypted.len() < MAC_LENGTH {
+            return Err(invalid_ndef("encrypted payload is shorter than its MAC"));
+        }
+        let (ciphertext, tag) = encrypted.split_at(encrypted.len() - MAC_LENGTH);
+
+        let (aes_key, mac_key) = self.session_keys(session)?;
+
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&mac_key)
+            .map_err(|e| PorterError::SignatureError(e.to_string()))?;
+        mac.update(ciphertext);
+        mac.verify_slice(tag).map_err(|_| {
+            PorterError::SignatureError("Smart Tap payload MAC does not match".to_string())
+        })?;
+
+        let mut plaintext = ciphertext.to_vec();
+        Aes128Ctr::new(&aes_key.into(), &[0u8; 16].into()).apply_keystream(&mut plaintext);
+
+        Ok(plaintext)
+    }
+
+    /// Decrypt a payload and return the service objects it contains
+    pub fn decrypt_services(
+        &self,
+        session: &SmartTapSession,
+        encrypted: &[u8],
+    ) -> Result<Vec<ServiceObject>> {
+        let plaintext = self.decrypt(session, encrypted)?;
+
+        NdefRecord::parse_message(&plaintext)?
+            .into_iter()
+            .map(|record| {
+                Ok(ServiceObject {
+                    service_type: record.type_str().unwrap_or_default().to_string(),
+                    records: record.nested()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Derive the AES and HMAC keys for a session
+    fn session_keys(&self, session: &SmartTapSession) -> Result<([u8; AES_KEY_LENGTH], [u8; 32])> {
+        let device_key = PublicKey::from_sec1_bytes(&session.device_ephemeral_public_key)
+            .map_err(|_| invalid_ndef("invalid device ephemeral public key"))?;
+        let shared_secret = p256::ecdh::diffie_hellman(
+            self.private_key.to_nonzero_scalar(),
+            device_key.as_affine(),
+        );
+
+        let mut ikm = session.device_ephemeral_public_key.clone();
+        ikm.extend_from_slice(shared_secret.raw_secret_bytes());
+
+        let mut info = session.terminal_nonce.clone();
+        info.extend_from_slice(&session.device_nonce);
+
+        let mut okm = [0u8; AES_KEY_LENGTH + 32];
+        Hkdf::<Sha256>::new(None, &ikm)
+            .expand(&info, &mut okm)
+            .map_err(|e| PorterError::SignatureError(e.to_string()))?;
+
+        let mut aes_key = [0u8; AES_KEY_LENGTH];
+        let mut mac_key = [0u8; 32];
+        aes_key.copy_from_slice(&okm[..AES_KEY_LENGTH]);
+        mac_key.copy_from_slice(&okm[AES_KEY_LENGTH..]);
+
+        Ok((aes_key, mac_key))
+    }
+}
+
+fn take<'a>(bytes: &'a [u8], offset: &mut usize, length: usize) -> Result<&'a [u8]> {
+    let end = offset
+        .checked_add(length)
+        .filter(|end| *end <= bytes.len())
+        .ok_or_else(|| invalid_ndef("record is truncated"))?;
+    let slice = &bytes[*offset..end];
+    *offset = end;
+    Ok(slice)
+}
+
+fn invalid_ndef(reason: &str) -> PorterError {
+    PorterError::ValidationError(format!("Invalid Smart Tap payload: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+    fn encode_message(records: &[(&[u8], &[u8])]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (index, (record_type, payload)) in records.iter().enumerate() {
+            let mut header = 0x01 | FLAG_SHORT_RECORD;
+            if index == 0 {
+                header |= FLAG_MESSAGE_BEGIN;
+            }
+            if index == records.len() - 1 {
+                header |= FLAG_MESSAGE_END;
+            }
+            bytes.push(header);
+            bytes.push(record_type.len() as u8);
+            bytes.push(payload.len() as u8);
+            bytes.extend_from_slice(record_type);
+            bytes.extend_from_slice(payload);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse_ndef_message() {
+        let message = encode_message(&[(b"ly", b"abc"), (b"of", b"")]);
+        let records = NdefRecord::parse_message(&message).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tnf, TypeNameFormat::WellKnown);
+        assert_eq!(records[0].type_str(), Some("ly"));
+        assert_eq!(records[0].payload, b"abc");
+        assert_eq!(records[1].type_str(), Some("of"));
+    }
+
+    #[test]
+    fn test_parse_truncated_message() {
+        let mut message = encode_message(&[(b"ly", b"abc")]);
+        message.truncate(message.len() - 1);
+
+        assert!(NdefRecord::parse_message(&message).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_services() {
+        let collector = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let device = SecretKey::from_slice(&[9u8; 32]).unwrap();
+        let device_public = device.public_key().to_encoded_point(true);
+
+        let session = SmartTapSession {
+            terminal_nonce: vec![1; 32],
+            device_nonce: vec![2; 32],
+            device_ephemeral_public_key: device_public.as_bytes().to_vec(),
+        };
+
+        let service = encode_message(&[(SERVICE_NUMBER_TYPE, b"\x01REDEEM-42")]);
+        let plaintext = encode_message(&[(b"ly", &service)]);
+
+        // Encrypt as the device would, using the collector's public key
+        let decryptor = SmartTapDecryptor::new(collector);
+        let (aes_key, mac_key) = decryptor.session_keys(&session).unwrap();
+        let mut encrypted = plaintext.clone();
+        Aes128Ctr::new(&aes_key.into(), &[0u8; 16].into()).apply_keystream(&mut encrypted);
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&mac_key).unwrap();
+        mac.update(&encrypted);
+        encrypted.extend_from_slice(&mac.finalize().into_bytes());
+
+        let services = decryptor.decrypt_services(&session, &encrypted).unwrap();
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].service_type, "ly");
+        assert_eq!(
+            services[0].redemption_value(),
+            Some("REDEEM-42".to_string())
+        );
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0xff;
+        assert!(decryptor.decrypt(&session, &encrypted).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::error::{PorterError, Result};
+use crate::google::types::AuthenticationKey;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// A version of the collector key used for Smart Tap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartTapKeyVersion {
+    pub version: i32,
+    pub public_key_pem: String,
+    pub created_at: DateTime<Utc>,
+    /// When the version stopped being uploaded to the issuer
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+/// Tracks Smart Tap key versions and which version each object was issued with
+///
+/// Terminals need the private key matching the version a pass was issued
+/// under, so old versions stay active until their objects are gone. The
+/// registry is serializable so it can be persisted between runs.
+///
+/// # Example
+///
+/// ```
+/// use porter::google::SmartTapKeyRegistry;
+///
+/// let mut keys = SmartTapKeyRegistry::new();
+/// let v1 = keys.rotate("-----BEGIN PUBLIC KEY-----\n...");
+/// keys.record_issued("issuer.pass_001");
+///
+/// let v2 = keys.rotate("-----BEGIN PUBLIC KEY-----\n...");
+/// assert_eq!(keys.version_for("issuer.pass_001"), Some(v1));
+/// assert_eq!(keys.current_version(), Some(v2));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SmartTapKeyRegistry {
+    versions: BTreeMap<i32, SmartTapKeyVersion>,
+    current: Option<i32>,
+    issued: HashMap<String, i32>,
+}
+
+impl SmartTapKeyRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a new key version and make it current, returning its version number
+    pub fn rotate(&mut self, public_key_pem: impl Into<String>) -> i32 {
+        let version = self.versions.keys().next_back().map_or(1, |v| v + 1);
+        self.versions.insert(
+            version,
+            SmartTapKeyVersion {
+                version,
+                public_key_pem: public_key_pem.into(),
+                created_at: Utc::now(),
+                retired_at: None,
+            },
+        );
+        self.current = Some(version);
+        version
+    }
+
+    /// The version new objects are issued with
+    pub fn current_version(&self) -> Option<i32> {
+        self.current
+    }
+
+    /// Look up a key version
+    pub fn version(&self, version: i32) -> Option<&SmartTapKeyVersion> {
+        self.versions.get(&version)
+    }
+
+    /// Record that an object was issued under the current key version
+    pub fn record_issued(&mut self, object_id: impl Into<String>) -> Option<i32> {
+        let version = self.current?;
+        self.issued.insert(object_id.into(), version);
+        Some(version)
+    }
+
+    /// The key version an object was issued with
+    pub fn version_for(&self, object_id: &str) -> Option<i32> {
+        self.issued.get(object_id).copied()
+    }
+
+    /// Stop tracking an object (for example once it has expired)
+    pub fn forget(&mut self, object_id: &str) {
+        self.issued.remove(object_id);
+    }
+
+    /// Objects still issued under a key version
+    pub fn objects_for_version(&self, version: i32) -> Vec<&str> {
+        self.issued
+            .iter()
+            .filter(|(_, v)| **v == version)
+            .map(|(id, _)| id.as_str())
+            .collect()
+    }
+
+    /// Retire a key version so it is no longer uploaded to the issuer
+    ///
+    /// Fails if it is the current version or objects were issued with it.
+    pub fn retire(&mut self, version: i32) -> Result<()> {
+        if self.current == Some(version) {
+            return Err(PorterError::ValidationError(format!(
+                "Smart Tap key version {} is current and cannot be retired",
+                version
+            )));
+        }
+        let remaining = self.objects_for_version(version).len();
+        if remaining > 0 {
+            return Err(PorterError::ValidationError(format!(
+                "Smart Tap key version {} is still used by {} objects",
+                version, remaining
+            )));
+        }
+
+        let key = self
+            .versions
+            .get_mut(&version)
+            .ok_or_else(|| PorterError::NotFound(format!("Smart Tap key version {}", version)))?;
+        key.retired_at = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Keys that should be registered on the issuer
+    pub fn active_keys(&self) -> Vec<AuthenticationKey> {
+        self.versions
+            .values()
+            .filter(|key| key.retired_at.is_none())
+            .map(|key| AuthenticationKey {
+                id: key.version,
+                public_key_pem: key.public_key_pem.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_keeps_issued_versions() {
+        let mut keys = SmartTapKeyRegistry::new();
+        assert_eq!(keys.record_issued("a"), None);
+
+        let v1 = keys.rotate("pem-1");
+        keys.record_issued("a");
+        let v2 = keys.rotate("pem-2");
+        keys.record_issued("b");
+
+        assert_eq!(keys.version_for("a"), Some(v1));
+        assert_eq!(keys.version_for("b"), Some(v2));
+        assert_eq!(keys.active_keys().len(), 2);
+    }
+
+    #[test]
+    fn test_retire_requires_unused_version() {
+        let mut keys = SmartTapKeyRegistry::new();
+        let v1 = keys.rotate("pem-1");
+        keys.record_issued("a");
+        keys.rotate("pem-2");
+
+        assert!(keys.retire(v1).is_err());
+
+        keys.forget("a");
+        keys.retire(v1).unwrap();
+        assert_eq!(
+            keys.active_keys(),
+            vec![AuthenticationKey {
+                id: 2,
+                public_key_pem: "pem-2".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Detection of enum values the crate does not know about
+
+use serde_json::Value;
+
+/// Values Google documents for the enum-like string fields Porter models
+///
+/// Google also accepts camelCase and lowercase aliases, so values are
+/// compared with [`normalize`].
+const ENUM_VALUES: &[(&str, &[&str])] = &[
+    (
+        "state",
+        &[
+            "STATE_UNSPECIFIED",
+            "ACTIVE",
+            "COMPLETED",
+            "EXPIRED",
+            "INACTIVE",
+        ],
+    ),
+    (
+        "reviewStatus",
+        &[
+            "REVIEW_STATUS_UNSPECIFIED",
+            "UNDER_REVIEW",
+            "APPROVED",
+            "REJECTED",
+            "DRAFT",
+        ],
+    ),
+    (
+        "messageType",
+        &[
+            "MESSAGE_TYPE_UNSPECIFIED",
+            "TEXT",
+            "EXPIRATION_NOTIFICATION",
+            "TEXT_AND_NOTIFY",
+        ],
+    ),
+    (
+        "multipleDevicesAndHoldersAllowedStatus",
+        &[
+            "STATUS_UNSPECIFIED",
+            "MULTIPLE_HOLDERS",
+            "ONE_USER_ALL_DEVICES",
+            "ONE_USER_ONE_DEVICE",
+        ],
+    ),
+    (
+        "viewUnlockRequirement",
+        &[
+            "VIEW_UNLOCK_REQUIREMENT_UNSPECIFIED",
+            "UNLOCK_NOT_REQUIRED",
+            "UNLOCK_REQUIRED_TO_VIEW",
+        ],
+    ),
+];
+
+/// Barcode types Google documents for `barcode.type`
+const BARCODE_TYPES: &[&str] = &[
+    "BARCODE_TYPE_UNSPECIFIED",
+    "AZTEC",
+    "CODE_39",
+    "CODE_128",
+    "CODABAR",
+    "DATA_MATRIX",
+    "EAN_8",
+    "EAN_13",
+    "ITF_14",
+    "PDF_417",
+    "QR_CODE",
+    "UPC_A",
+    "TEXT_ONLY",
+];
+
+/// Fold Google's enum aliases together, so `QR_CODE`, `qrCode` and `qrcode` match
+pub(crate) fn normalize(value: &str) -> String {
+    value.replace('_', "").to_ascii_lowercase()
+}
+
+/// Enum fields in `value` holding values Google does not document
+///
+/// Each entry names the field by its JSON path, such as
+/// `resources[0].barcode.type: "MAXICODE"`.
+pub(crate) fn unknown_enum_values(value: &Value) -> Vec<String> {
+    let mut unknown = vec![];
+    walk(value, "", None, &mut unknown);
+    unknown
+}
+
+fn walk(value: &Value, path: &str, parent: Option<&str>, unknown: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                let known = match (parent, key.as_str()) {
+                    (Some("barcode" | "rotatingBarcode"), "type") => Some(BARCODE_TYPES),
+                    _ => ENUM_VALUES
+                        .iter()
+                        .find(|(field, _)| field == key)
+                        .map(|(_, values)| *values),
+                };
+                match (known, child) {
+                    (Some(known), Value::String(s))
+                        if !known.iter().any(|k| normalize(k) == normalize(s)) =>
+                    {
+                        unknown.push(format!("{}: {:?}", child_path, s));
+                    }
+                    _ => walk(child, &child_path, Some(key), unknown),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                walk(item, &format!("{}[{}]", path, index), parent, unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_enum_values() {
+        let response = serde_json::json!({
+            "resources": [
+                {"state": "active", "barcode": {"type": "qrCode", "value": "1"}},
+                {"state": "ARCHIVED", "barcode": {"type": "MAXICODE", "value": "2"}},
+            ],
+            "type": "anything"
+        });
+        assert_eq!(
+            unknown_enum_values(&response),
+            vec![
+                "resources[1].barcode.type: \"MAXICODE\"",
+                "resources[1].state: \"ARCHIVED\"",
+            ]
+        );
+    }
+}
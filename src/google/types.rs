@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// Google Wallet Generic Object
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -9,242 +10,1498 @@ pub struct GenericObject {
     #[serde(default)]
     pub class_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub state: Option<String>,
+    pub state: Option<State>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<Barcode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotating_barcode: Option<RotatingBarcode>,
+    /// Value sent to Smart Tap terminals when the pass is tapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_redemption_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_link_data: Option<AppLinkData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_info: Option<GroupingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<Notifications>,
+    /// Messages shown on the pass, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_title: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subheader: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex_background_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hero_image: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_time_interval: Option<TimeInterval>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linked_offer_ids: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+}
+
+/// Google Wallet Generic Class
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenericClass {
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_status: Option<ReviewStatus>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub class_template_info: Option<ClassTemplateInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_options: Option<CallbackOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_smart_tap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redemption_issuers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    /// Messages shown on all passes of the class, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_animation: Option<SecurityAnimation>,
+    /// MULTIPLE_HOLDERS, ONE_USER_ALL_DEVICES or ONE_USER_ONE_DEVICE
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiple_devices_and_holders_allowed_status: Option<String>,
+    /// UNLOCK_NOT_REQUIRED or UNLOCK_REQUIRED_TO_VIEW
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_unlock_requirement: Option<String>,
+}
+
+/// Security animation shown on the pass
+///
+/// The animation is set on the class and shown on all of its objects, so
+/// screenshots of a pass are easy to tell apart from the real one.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityAnimation {
+    /// FOIL_SHIMMER
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation_type: Option<String>,
+}
+
+/// Declare an enum-like API string with a catch-all for values Porter does
+/// not know, matching aliases the way Google does
+macro_rules! api_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident => $value:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// A value Porter does not know, sent back unchanged
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The value as the API writes it
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)*
+                    Self::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            /// Parse a value, accepting the lowercase and camelCase aliases
+            /// Google also accepts
+            fn from(value: &str) -> Self {
+                let normalized = crate::google::strict::normalize(value);
+                $(
+                    if normalized == crate::google::strict::normalize($value) {
+                        return Self::$variant;
+                    }
+                )*
+                Self::Unknown(value.to_string())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self::from(value.as_str())
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(Self::from)
+            }
+        }
+    };
+}
+
+api_enum! {
+    /// Lifecycle state of an object
+    State {
+        Active => "ACTIVE",
+        Inactive => "INACTIVE",
+        /// The object has been used, such as a redeemed offer
+        Completed => "COMPLETED",
+        Expired => "EXPIRED",
+    }
+}
+
+api_enum! {
+    /// Review status of a class
+    ///
+    /// Classes are created as `UNDER_REVIEW` (or `DRAFT`) and Google moves
+    /// them to `APPROVED` or `REJECTED`.
+    ReviewStatus {
+        Draft => "DRAFT",
+        UnderReview => "UNDER_REVIEW",
+        Approved => "APPROVED",
+        Rejected => "REJECTED",
+    }
+}
+
+/// Localized string for multi-language support
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedString {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<TranslatedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translated_values: Option<Vec<TranslatedString>>,
+}
+
+impl LocalizedString {
+    /// The translated value for `locale`, falling back to less specific locales
+    ///
+    /// `fr-CA` falls back to `fr`. The default value is only used when its
+    /// language matches.
+    pub fn translation(&self, locale: &str) -> Option<&str> {
+        let candidates = self
+            .translated_values
+            .iter()
+            .flatten()
+            .chain(self.default_value.iter())
+            .map(|t| (t.language.as_str(), t.value.as_str()));
+        crate::i18n::best_match(locale, candidates)
+    }
+
+    /// The best text for `locale`, falling back to the default value
+    pub fn resolve(&self, locale: &str) -> Option<&str> {
+        self.translation(locale)
+            .or_else(|| self.default_value.as_ref().map(|t| t.value.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslatedString {
+    pub language: String,
+    pub value: String,
+}
+
+/// Barcode definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Barcode {
+    #[serde(rename = "type")]
+    pub barcode_type: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternate_text: Option<String>,
+}
+
+/// Barcode whose value is regenerated from TOTP codes on the device
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RotatingBarcode {
+    #[serde(rename = "type")]
+    pub barcode_type: String,
+    /// ISO_8859_1 or UTF_8
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub render_encoding: Option<String>,
+    /// Value with `{totp_value_N}` placeholders, such as "TICKET-{totp_value_0}"
+    pub value_pattern: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totp_details: Option<TotpDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternate_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_code_text: Option<LocalizedString>,
+}
+
+/// How the TOTP codes of a rotating barcode are computed
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpDetails {
+    #[serde(with = "int64_string")]
+    pub period_millis: i64,
+    /// Only TOTP_SHA1 is supported
+    pub algorithm: String,
+    pub parameters: Vec<TotpParameters>,
+}
+
+/// Secret and code length of one TOTP value
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpParameters {
+    /// Shared secret, hex encoded
+    pub key: String,
+    pub value_length: i32,
+}
+
+/// Image definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Image {
+    pub source_uri: ImageUri,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_description: Option<LocalizedString>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUri {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Time interval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeInterval {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<DateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime>,
+}
+
+/// Point in time, written by the API as `{"date": "2027-03-01T23:00:00Z"}`
+///
+/// Dates are sent as ISO 8601 with their offset. Google also accepts dates
+/// without an offset, meaning the local time at the venue; those are read
+/// as UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateTime {
+    #[serde(with = "iso8601")]
+    pub date: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for DateTime {
+    fn from(date: chrono::DateTime<Tz>) -> Self {
+        Self {
+            date: date.fixed_offset(),
+        }
+    }
+}
+
+mod iso8601 {
+    use chrono::{FixedOffset, NaiveDateTime, SecondsFormat};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        date: &chrono::DateTime<FixedOffset>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&date.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<chrono::DateTime<FixedOffset>, D::Error> {
+        let date = String::deserialize(deserializer)?;
+        chrono::DateTime::parse_from_rfc3339(&date)
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S%.f")
+                    .map(|date| date.and_utc().fixed_offset())
+            })
+            .map_err(|_| D::Error::custom(format!("invalid ISO 8601 date-time: {}", date)))
+    }
+}
+
+/// Message to add to a pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddMessageRequest {
+    pub message: Message,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_interval: Option<TimeInterval>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// TEXT or TEXT_AND_NOTIFY
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_type: Option<String>,
+}
+
+/// One page of a list of classes or objects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResponse<T> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Vec<T>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+}
+
+/// List response for objects
+pub type GenericObjectListResponse = ListResponse<GenericObject>;
+
+/// List response for classes
+pub type GenericClassListResponse = ListResponse<GenericClass>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results_per_page: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+/// Event Ticket Object
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTicketObject {
+    pub id: String,
+    pub class_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<State>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<Barcode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotating_barcode: Option<RotatingBarcode>,
+    /// Value sent to Smart Tap terminals when the pass is tapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_redemption_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_link_data: Option<AppLinkData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_info: Option<GroupingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<Notifications>,
+    /// Messages shown on the pass, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_info: Option<EventSeat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_holder_name: Option<String>,
+    /// NOTIFY_ON_UPDATE to notify holders of field updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_preference: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSeat {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<LocalizedString>,
+}
+
+/// Loyalty Object
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyObject {
+    pub id: String,
+    pub class_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<State>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<Barcode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotating_barcode: Option<RotatingBarcode>,
+    /// Value sent to Smart Tap terminals when the pass is tapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_redemption_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_link_data: Option<AppLinkData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_info: Option<GroupingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<Notifications>,
+    /// Messages shown on the pass, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loyalty_points: Option<LoyaltyPoints>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyPoints {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<LoyaltyPointsBalance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyPointsBalance {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub string: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub int: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub double: Option<f64>,
+}
+
+/// Google Wallet Gift Card Class
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GiftCardClass {
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_status: Option<ReviewStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program_logo: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wide_program_logo: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hero_image: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex_background_color: Option<String>,
+    /// Such as "Card Number"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_number_label: Option<String>,
+    /// Such as "PIN"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_number_label: Option<String>,
+    /// Whether the barcode can be scanned to redeem the card
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_barcode_redemption: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_template_info: Option<ClassTemplateInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_options: Option<CallbackOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_smart_tap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redemption_issuers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    /// Messages shown on all passes of the class, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_animation: Option<SecurityAnimation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiple_devices_and_holders_allowed_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_unlock_requirement: Option<String>,
+}
+
+/// Google Wallet Gift Card Object
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GiftCardObject {
+    pub id: String,
+    pub class_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<State>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<Barcode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotating_barcode: Option<RotatingBarcode>,
+    /// Value sent to Smart Tap terminals when the pass is tapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_redemption_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_link_data: Option<AppLinkData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_info: Option<GroupingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<Notifications>,
+    /// Messages shown on the pass, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<Money>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_update_time: Option<DateTime>,
+    /// NOTIFY_ON_UPDATE to notify holders of field updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_preference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_time_interval: Option<TimeInterval>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+}
+
+/// Amount of money in micros (1 USD is 1,000,000 micros)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Money {
+    #[serde(with = "int64_string")]
+    pub micros: i64,
+    pub currency_code: String,
+}
+
+impl Money {
+    /// Create an amount from micros and an ISO 4217 currency code
+    pub fn new(micros: i64, currency_code: impl Into<String>) -> Self {
+        Self {
+            micros,
+            currency_code: currency_code.into(),
+        }
+    }
+}
+
+/// int64 values are sent as JSON strings by the Wallet API
+mod int64_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Int64 {
+            String(String),
+            Number(i64),
+        }
+
+        match Int64::deserialize(deserializer)? {
+            Int64::String(s) => s.parse().map_err(serde::de::Error::custom),
+            Int64::Number(n) => Ok(n),
+        }
+    }
+}
+
+/// Google Wallet Flight Class
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightClass {
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_status: Option<ReviewStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_header: Option<FlightHeader>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<AirportInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<AirportInfo>,
+    /// Local ISO 8601 date-time without offset, e.g. "2027-03-20T09:30:00"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_scheduled_departure_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_estimated_or_actual_departure_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_boarding_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_gate_closing_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_scheduled_arrival_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_estimated_or_actual_arrival_date_time: Option<String>,
+    /// SCHEDULED, ACTIVE, LANDED, CANCELLED, REDIRECTED or DIVERTED
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_status: Option<String>,
+    /// NOTIFY_ON_UPDATE to notify holders of field updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_preference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hero_image: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex_background_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_template_info: Option<ClassTemplateInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_options: Option<CallbackOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_smart_tap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redemption_issuers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    /// Messages shown on all passes of the class, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_animation: Option<SecurityAnimation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiple_devices_and_holders_allowed_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_unlock_requirement: Option<String>,
+}
+
+/// Google Wallet Flight Object, one passenger's boarding pass
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightObject {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub class_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<State>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<Barcode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotating_barcode: Option<RotatingBarcode>,
+    /// Value sent to Smart Tap terminals when the pass is tapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_redemption_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_link_data: Option<AppLinkData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_info: Option<GroupingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<Notifications>,
+    /// Messages shown on the pass, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    /// As printed on the boarding pass, such as "DOE/JOHN"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passenger_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boarding_and_seating_info: Option<BoardingAndSeatingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reservation_info: Option<ReservationInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_time_interval: Option<TimeInterval>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    /// NOTIFY_ON_UPDATE to notify holders of field updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_preference: Option<String>,
+}
+
+/// Boarding group, seat and cabin of a passenger
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardingAndSeatingInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boarding_group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boarding_position: Option<String>,
+    /// Position in the check-in sequence, such as "49"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_number: Option<String>,
+    /// Such as "Economy" or "First"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_class: Option<String>,
+    /// FRONT or BACK
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boarding_door: Option<String>,
+}
+
+/// Booking details of a flight reservation
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservationInfo {
+    /// Record locator, such as "ABC123"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmation_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eticket_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequent_flyer_info: Option<FrequentFlyerInfo>,
+}
+
+/// Frequent flyer membership attached to a reservation
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FrequentFlyerInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequent_flyer_program_name: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequent_flyer_number: Option<String>,
+}
+
+/// Carrier and number of a flight
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightHeader {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carrier: Option<FlightCarrier>,
+    /// Digits only, without the carrier code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_number: Option<String>,
+}
+
+/// Airline operating a flight
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FlightCarrier {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carrier_iata_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airline_name: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airline_logo: Option<Image>,
+}
+
+/// Origin or destination airport
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AirportInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airport_iata_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gate: Option<String>,
+    /// Shown instead of the name Google Wallet looks up from the IATA code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airport_name_override: Option<LocalizedString>,
+}
+
+/// Google Wallet Transit Class
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitClass {
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_status: Option<ReviewStatus>,
+    /// BUS, RAIL, TRAM, FERRY or OTHER
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transit_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transit_operator_name: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wide_logo: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hero_image: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex_background_color: Option<String>,
+    /// Show each leg of a multi-leg ticket as its own card
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_single_leg_itinerary: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_template_info: Option<ClassTemplateInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_options: Option<CallbackOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_smart_tap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redemption_issuers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    /// Messages shown on all passes of the class, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_animation: Option<SecurityAnimation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiple_devices_and_holders_allowed_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_unlock_requirement: Option<String>,
+}
+
+/// Google Wallet Transit Object
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitObject {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub class_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<State>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barcode: Option<Barcode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotating_barcode: Option<RotatingBarcode>,
+    /// Value sent to Smart Tap terminals when the pass is tapped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_redemption_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_link_data: Option<AppLinkData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_info: Option<GroupingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<Notifications>,
+    /// Messages shown on the pass, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    /// ONE_WAY or ROUND_TRIP
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trip_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_number: Option<String>,
+    /// The single leg of the trip; use `ticket_legs` for multi-leg trips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_leg: Option<TicketLeg>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_legs: Option<Vec<TicketLeg>>,
+    /// USED, REFUNDED or EXCHANGED
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_status: Option<String>,
+    /// Custom status shown instead of `ticketStatus`, such as "Cancelled"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_ticket_status: Option<LocalizedString>,
+    /// SINGLE_PASSENGER or MULTIPLE_PASSENGERS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passenger_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passenger_names: Option<String>,
+    /// ADULT, CHILD or SENIOR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concession_category: Option<String>,
+    /// Shown instead of `concessionCategory`, such as "Student"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_concession_category: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase_details: Option<PurchaseDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_time_interval: Option<TimeInterval>,
+    /// NOTIFY_ON_UPDATE to notify holders of field updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_preference: Option<String>,
+}
+
+/// Departure, arrival and boarding details of one leg of a trip
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketLeg {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin_station_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin_name: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_station_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_name: Option<LocalizedString>,
+    /// ISO 8601 date-time, with or without an offset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub departure_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arrival_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carriage: Option<String>,
+    /// Such as "Anytime Return"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fare_name: Option<LocalizedString>,
+    /// Operator of this leg, when it differs from the class
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transit_operator_name: Option<LocalizedString>,
+}
+
+/// Purchase and fare information of a transit ticket
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PurchaseDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase_receipt_number: Option<String>,
+    /// ISO 8601 date-time, with or without an offset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmation_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_cost: Option<TicketCost>,
+}
+
+/// Price paid for a transit ticket
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TicketCost {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub face_value: Option<Money>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase_price: Option<Money>,
+    /// Such as "Railcard discount applied"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount_message: Option<LocalizedString>,
+}
+
+/// Google Wallet Loyalty Class
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyClass {
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_status: Option<ReviewStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program_logo: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wide_program_logo: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hero_image: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hex_background_color: Option<String>,
+    /// Such as "Gold" or "Platinum"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewards_tier: Option<String>,
+    /// Such as "Rewards Tier"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewards_tier_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_rewards_tier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_rewards_tier_label: Option<String>,
+    /// Such as "Member ID"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id_label: Option<String>,
+    /// Such as "Member Name"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_name_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discoverable_program: Option<DiscoverableProgram>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_template_info: Option<ClassTemplateInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_options: Option<CallbackOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_smart_tap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redemption_issuers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    /// Messages shown on all passes of the class, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_animation: Option<SecurityAnimation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiple_devices_and_holders_allowed_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_unlock_requirement: Option<String>,
+}
+
+/// Google Wallet Offer Class
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferClass {
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_status: Option<ReviewStatus>,
+    /// Such as "20% off any t-shirt"
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub barcode: Option<Barcode>,
+    pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub card_title: Option<LocalizedString>,
+    pub localized_title: Option<LocalizedString>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub header: Option<LocalizedString>,
+    pub short_title: Option<String>,
+    /// Aggregator or merchant name
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub subheader: Option<LocalizedString>,
+    pub provider: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub logo: Option<Image>,
+    pub localized_provider: Option<LocalizedString>,
+    /// INSTORE, ONLINE, BOTH or TEMPORARY_PRICE_REDUCTION
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub hex_background_color: Option<String>,
+    pub redemption_channel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fine_print: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub localized_fine_print: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help_uri: Option<Uri>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_image: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wide_title_image: Option<Image>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hero_image: Option<Image>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub valid_time_interval: Option<TimeInterval>,
+    pub hex_background_color: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub linked_offer_ids: Option<Vec<String>>,
+    pub class_template_info: Option<ClassTemplateInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_options: Option<CallbackOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_smart_tap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redemption_issuers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    /// Messages shown on all passes of the class, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security_animation: Option<SecurityAnimation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multiple_devices_and_holders_allowed_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub view_unlock_requirement: Option<String>,
 }
 
-/// Google Wallet Generic Class
+/// Google Wallet Offer Object
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct GenericClass {
+pub struct OfferObject {
     #[serde(default)]
     pub id: String,
+    #[serde(default)]
+    pub class_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub issuer_name: Option<String>,
+    pub state: Option<State>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub review_status: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none", default)]
-    pub class_template_info: Option<ClassTemplateInfo>,
-}
-
-/// Localized string for multi-language support
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LocalizedString {
+    pub barcode: Option<Barcode>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub default_value: Option<TranslatedString>,
+    pub rotating_barcode: Option<RotatingBarcode>,
+    /// Value sent to Smart Tap terminals when the pass is tapped
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub translated_values: Option<Vec<TranslatedString>>,
+    pub smart_tap_redemption_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_link_data: Option<AppLinkData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_modules_data: Option<Vec<ImageModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grouping_info: Option<GroupingInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<Notifications>,
+    /// Messages shown on the pass, in the order they were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<Message>>,
+    /// When the offer can be redeemed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_time_interval: Option<TimeInterval>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_modules_data: Option<Vec<TextModuleData>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links_module_data: Option<LinksModuleData>,
+    /// NOTIFY_ON_UPDATE to notify holders of field updates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_preference: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How a loyalty program can be discovered and joined from Google Wallet
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct TranslatedString {
-    pub language: String,
-    pub value: String,
+pub struct DiscoverableProgram {
+    /// TRUSTED_TESTERS, LIVE or DISABLED
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_signup_info: Option<DiscoverableProgramMerchantSignupInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_signin_info: Option<DiscoverableProgramMerchantSigninInfo>,
 }
 
-/// Barcode definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Merchant site where users can sign up for a discoverable program
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct Barcode {
-    #[serde(rename = "type")]
-    pub barcode_type: String,
-    pub value: String,
+pub struct DiscoverableProgramMerchantSignupInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub alternate_text: Option<String>,
+    pub signup_website: Option<Uri>,
+    /// User data shared with the signup site, such as FIRST_NAME or EMAIL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signup_shared_datas: Option<Vec<String>>,
 }
 
-/// Image definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Merchant site where existing members can sign in
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct Image {
-    pub source_uri: ImageUri,
+pub struct DiscoverableProgramMerchantSigninInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content_description: Option<LocalizedString>,
+    pub signin_website: Option<Uri>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Link with an optional description
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct ImageUri {
+pub struct Uri {
     pub uri: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub localized_description: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
 }
 
-/// Time interval
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// JWT payload for creating save URLs
+///
+/// The fields can borrow, so claims for many links can be signed without
+/// copying the objects into each one. Decoded payloads own their data.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct TimeInterval {
+pub struct JwtPayload<'a> {
+    pub iss: Cow<'a, str>, // Issuer (service account email)
+    pub aud: Cow<'a, str>, // Audience (should be "google")
+    pub typ: Cow<'a, str>, // Type (should be "savetowallet")
+    pub iat: i64,          // Issued at timestamp
+    /// Expiration timestamp, after which the link can no longer be saved
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start: Option<DateTime>,
+    pub exp: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub end: Option<DateTime>,
+    pub origins: Option<Cow<'a, [String]>>,
+    pub payload: Cow<'a, JwtObjectPayload>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct DateTime {
-    pub date: String, // ISO 8601 format
+impl JwtPayload<'_> {
+    /// Decode the payload of a save URL or a raw save JWT
+    ///
+    /// Accepts `https://pay.google.com/gp/v/save/<jwt>` links as well as the
+    /// bare token. The signature is not verified, so this is meant for
+    /// inspecting links, not for trusting them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use porter::google::JwtPayload;
+    ///
+    /// # fn example(link: &str) -> porter::error::Result<()> {
+    /// let payload = JwtPayload::from_save_url(link)?;
+    /// for object in payload.payload.into_owned().generic_objects.unwrap_or_default() {
+    ///     println!("{} ({})", object.id, object.class_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_save_url(link: &str) -> crate::error::Result<JwtPayload<'static>> {
+        use crate::error::PorterError;
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        let segments: Vec<&str> = save_token(link).split('.').collect();
+        if segments.len() != 3 {
+            return Err(PorterError::ValidationError(
+                "Save link does not contain a JWT".to_string(),
+            ));
+        }
+        let payload = URL_SAFE_NO_PAD
+            .decode(segments[1].trim_end_matches('='))
+            .map_err(|e| PorterError::ValidationError(format!("Invalid JWT payload: {}", e)))?;
+        Ok(serde_json::from_slice(&payload)?)
+    }
 }
 
-/// Message to add to a pass
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AddMessageRequest {
-    pub message: Message,
+/// The token in a save URL, or the input itself if it is not a save URL
+pub(crate) fn save_token(link: &str) -> &str {
+    let link = link.trim();
+    let token = match link.rfind("/save/") {
+        Some(index) => &link[index + "/save/".len()..],
+        None => link,
+    };
+    token.split(['?', '#']).next().unwrap_or_default()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Container for objects to be saved
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct Message {
+pub struct JwtObjectPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub header: Option<String>,
+    pub generic_objects: Option<Vec<GenericObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub body: Option<String>,
+    pub event_ticket_objects: Option<Vec<EventTicketObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub display_interval: Option<TimeInterval>,
+    pub loyalty_objects: Option<Vec<LoyaltyObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_objects: Option<Vec<OfferObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gift_card_objects: Option<Vec<GiftCardObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transit_objects: Option<Vec<TransitObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_objects: Option<Vec<FlightObject>>,
+    /// Classes inserted along with the objects, when they do not exist yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generic_classes: Option<Vec<GenericClass>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loyalty_classes: Option<Vec<LoyaltyClass>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_classes: Option<Vec<OfferClass>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gift_card_classes: Option<Vec<GiftCardClass>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transit_classes: Option<Vec<TransitClass>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_classes: Option<Vec<FlightClass>>,
 }
 
-/// List response for objects
+impl JwtObjectPayload {
+    /// Whether the payload contains no objects at all
+    pub fn is_empty(&self) -> bool {
+        self.generic_objects
+            .as_deref()
+            .unwrap_or_default()
+            .is_empty()
+            && self
+                .event_ticket_objects
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+            && self
+                .loyalty_objects
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+            && self.offer_objects.as_deref().unwrap_or_default().is_empty()
+            && self
+                .gift_card_objects
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+            && self
+                .transit_objects
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+            && self
+                .flight_objects
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+    }
+}
+
+macro_rules! object_reference {
+    ($($object:ident),* $(,)?) => {
+        $(
+            impl $object {
+                /// An object carrying only its id and class
+                ///
+                /// Save links built from references stay short, and point at
+                /// an object that has already been inserted.
+                pub fn reference(id: impl Into<String>, class_id: impl Into<String>) -> Self {
+                    Self {
+                        id: id.into(),
+                        class_id: class_id.into(),
+                        ..Default::default()
+                    }
+                }
+            }
+        )*
+    };
+}
+
+object_reference!(
+    GenericObject,
+    EventTicketObject,
+    LoyaltyObject,
+    OfferObject,
+    GiftCardObject,
+    TransitObject,
+    FlightObject,
+);
+
+/// Request body for JWT insert endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GenericObjectListResponse {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub resources: Option<Vec<GenericObject>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pagination: Option<Pagination>,
+pub struct JwtResource {
+    pub jwt: String,
 }
 
+/// Response from JWT insert endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Pagination {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub results_per_page: Option<i32>,
+pub struct JwtInsertResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub next_page_token: Option<String>,
+    pub save_uri: Option<String>,
 }
 
-/// Event Ticket Object
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Links shown on the back of a pass
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct EventTicketObject {
-    pub id: String,
-    pub class_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub state: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub barcode: Option<Barcode>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub seat_info: Option<EventSeat>,
+pub struct LinksModuleData {
+    /// Web, `tel:`, `mailto:` or `geo:` links
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ticket_holder_name: Option<String>,
+    pub uris: Option<Vec<Uri>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stacking of passes in the Google Wallet app
+///
+/// Passes with the same grouping ID are shown together, ordered by sort index.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct EventSeat {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub seat: Option<LocalizedString>,
+pub struct GroupingInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub row: Option<LocalizedString>,
+    pub grouping_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub section: Option<LocalizedString>,
+    pub sort_index: Option<i32>,
 }
 
-/// Loyalty Object
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Reminders Google Wallet sends about a pass
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct LoyaltyObject {
-    pub id: String,
-    pub class_id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub state: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub barcode: Option<Barcode>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub account_id: Option<String>,
+pub struct Notifications {
+    /// Sent shortly before the end of the valid time interval
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub account_name: Option<String>,
+    pub expiry_notification: Option<ExpiryNotification>,
+    /// Sent shortly before the start of the valid time interval
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub loyalty_points: Option<LoyaltyPoints>,
+    pub upcoming_notification: Option<UpcomingNotification>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct LoyaltyPoints {
-    pub label: String,
+pub struct ExpiryNotification {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub balance: Option<LoyaltyPointsBalance>,
+    pub enable_notification: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct LoyaltyPointsBalance {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub string: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub int: Option<i32>,
+pub struct UpcomingNotification {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub double: Option<f64>,
+    pub enable_notification: Option<bool>,
 }
 
-/// JWT payload for creating save URLs
+/// Image shown on the back of a pass
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct JwtPayload {
-    pub iss: String, // Issuer (service account email)
-    pub aud: String, // Audience (should be "google")
-    pub typ: String, // Type (should be "savetowallet")
-    pub iat: i64,    // Issued at timestamp
+pub struct ImageModuleData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_image: Option<Image>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub origins: Option<Vec<String>>,
-    pub payload: JwtObjectPayload,
+    pub id: Option<String>,
 }
 
-/// Container for objects to be saved
+/// Links from a pass to the issuer's app or website, by platform
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct JwtObjectPayload {
+pub struct AppLinkData {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub generic_objects: Option<Vec<GenericObject>>,
+    pub android_app_link_info: Option<AppLinkInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub event_ticket_objects: Option<Vec<EventTicketObject>>,
+    pub ios_app_link_info: Option<AppLinkInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub loyalty_objects: Option<Vec<LoyaltyObject>>,
+    pub web_app_link_info: Option<AppLinkInfo>,
 }
 
-/// Request body for JWT insert endpoint
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JwtResource {
-    pub jwt: String,
+/// App link shown on a pass for one platform
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLinkInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_logo_image: Option<Image>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<LocalizedString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_target: Option<AppTarget>,
 }
 
-/// Response from JWT insert endpoint
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where an app link leads
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct JwtInsertResponse {
+pub struct AppTarget {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub save_uri: Option<String>,
+    pub target_uri: Option<Uri>,
+    /// Android package name, for Android links
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_name: Option<String>,
 }
 
 /// Text module data for displaying custom fields
@@ -412,3 +1669,162 @@ pub struct BarcodeSectionDetail {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub field_selector: Option<FieldSelector>,
 }
+
+/// Issuer account
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Issuer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_info: Option<IssuerContactInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_options: Option<CallbackOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_merchant_data: Option<SmartTapMerchantData>,
+}
+
+/// Issuer contact information
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuerContactInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alerts_emails: Option<Vec<String>>,
+}
+
+/// Callback settings for save/delete notifications
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CallbackOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Smart Tap merchant settings of an issuer
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartTapMerchantData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tap_merchant_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authentication_keys: Option<Vec<AuthenticationKey>>,
+}
+
+/// Collector public key used by Smart Tap terminals
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationKey {
+    /// Key version
+    pub id: i32,
+    pub public_key_pem: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_time_serde() {
+        let interval: TimeInterval = serde_json::from_value(serde_json::json!({
+            "start": { "date": "2027-03-01T18:00:00.5+01:00" },
+            "end": { "date": "2027-03-01T23:00:00" }
+        }))
+        .unwrap();
+
+        let start = interval.start.unwrap().date;
+        assert_eq!(start.offset().local_minus_utc(), 3600);
+        assert_eq!(
+            serde_json::to_value(interval).unwrap(),
+            serde_json::json!({
+                "start": { "date": "2027-03-01T18:00:00.500+01:00" },
+                "end": { "date": "2027-03-01T23:00:00Z" }
+            })
+        );
+
+        let utc: DateTime = "2027-03-01T18:00:00Z"
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .unwrap()
+            .into();
+        assert_eq!(utc.date.to_rfc3339(), "2027-03-01T18:00:00+00:00");
+
+        assert!(serde_json::from_str::<DateTime>(r#"{ "date": "tomorrow" }"#).is_err());
+    }
+
+    #[test]
+    fn test_state_and_review_status_serde() {
+        let object: GenericObject =
+            serde_json::from_value(serde_json::json!({ "state": "expired" })).unwrap();
+        assert_eq!(object.state, Some(State::Expired));
+        assert_eq!(
+            serde_json::to_value(&object).unwrap()["state"],
+            serde_json::json!("EXPIRED")
+        );
+
+        let status: ReviewStatus = serde_json::from_str("\"underReview\"").unwrap();
+        assert_eq!(status, ReviewStatus::UnderReview);
+        assert_eq!(status.to_string(), "UNDER_REVIEW");
+
+        let unknown = State::from("ARCHIVED");
+        assert_eq!(unknown, State::Unknown("ARCHIVED".to_string()));
+        assert_eq!(serde_json::to_string(&unknown).unwrap(), "\"ARCHIVED\"");
+    }
+
+    #[test]
+    fn test_jwt_payload_from_save_url() {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        let payload = serde_json::json!({
+            "iss": "wallet@example.iam.gserviceaccount.com",
+            "aud": "google",
+            "typ": "savetowallet",
+            "iat": 1_700_000_000,
+            "payload": {"genericObjects": [{"id": "issuer.pass", "classId": "issuer.class"}]}
+        });
+        let jwt = format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256"}"#),
+            URL_SAFE_NO_PAD.encode(payload.to_string())
+        );
+
+        for link in [
+            jwt.clone(),
+            format!("https://pay.google.com/gp/v/save/{}?utm_source=email", jwt),
+        ] {
+            let decoded = JwtPayload::from_save_url(&link).unwrap();
+            assert_eq!(decoded.typ, "savetowallet");
+            assert_eq!(
+                decoded.payload.generic_objects.as_ref().unwrap()[0].id,
+                "issuer.pass"
+            );
+        }
+        assert!(JwtPayload::from_save_url("https://pay.google.com/gp/v/save/opaque").is_err());
+    }
+
+    #[test]
+    fn test_money_micros_as_string() {
+        let money = Money::new(12_500_000, "USD");
+        let json = serde_json::to_value(&money).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "micros": "12500000", "currencyCode": "USD" })
+        );
+        assert_eq!(serde_json::from_value::<Money>(json).unwrap(), money);
+        assert_eq!(
+            serde_json::from_str::<Money>(r#"{"micros":5,"currencyCode":"EUR"}"#)
+                .unwrap()
+                .micros,
+            5
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! Translation catalogs
+//!
+//! Loads translations maintained outside Rust code (gettext `.po` files or
+//! Fluent `.ftl` resources) and applies them to a [`Pass`]. Translations are
+//! keyed by the source string shown on the pass, so the pass is written in
+//! the default language and the catalog fills in the rest.
+
+use crate::error::{PorterError, Result};
+use crate::models::{LocalizedValue, Pass};
+use std::collections::{BTreeMap, HashMap};
+
+/// Translations of source strings into one or more locales
+///
+/// # Example
+///
+/// ```
+/// use porter::i18n::TranslationCatalog;
+/// use porter::PassBuilder;
+///
+/// let mut catalog = TranslationCatalog::new();
+/// catalog
+///     .load_po("fr-FR", "msgid \"Seat\"\nmsgstr \"Place\"\n")
+///     .unwrap();
+///
+/// let mut pass = PassBuilder::new("issuer.pass", "issuer.class")
+///     .field("seat", "Seat", "A23")
+///     .build();
+/// catalog.apply(&mut pass);
+///
+/// assert_eq!(pass.localized("Seat").unwrap().translations["fr-FR"], "Place");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TranslationCatalog {
+    locales: BTreeMap<String, HashMap<String, String>>,
+}
+
+impl TranslationCatalog {
+    /// Create an empty catalog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a single translation
+    pub fn insert(
+        &mut self,
+        locale: impl Into<String>,
+        source: impl Into<String>,
+        translation: impl Into<String>,
+    ) {
+        self.locales
+            .entry(locale.into())
+            .or_default()
+            .insert(source.into(), translation.into());
+    }
+
+    /// Look up the translation of a source string
+    pub fn translate(&self, locale: &str, source: &str) -> Option<&str> {
+        self.locales.get(locale)?.get(source).map(String::as_str)
+    }
+
+    /// Locales with at least one translation
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.locales.keys().map(String::as_str)
+    }
+
+    /// Load a gettext `.po` file for a locale
+    ///
+    /// Fuzzy and untranslated entries are skipped. For plural entries the
+    /// first form is used.
+    pub fn load_po(&mut self, locale: &str, po: &str) -> Result<()> {
+        for entry in parse_po(po)? {
+            self.insert(locale, entry.0, entry.1);
+        }
+        Ok(())
+    }
+
+    /// Load a Fluent resource for a locale
+    ///
+    /// Fluent messages are keyed by id rather than source text, so the
+    /// resource for the default language is needed to pair them up.
+    /// Messages missing from either resource are skipped.
+    pub fn load_fluent(
+        &mut self,
+        locale: &str,
+        source_ftl: &str,
+        translated_ftl: &str,
+    ) -> Result<()> {
+        let source = parse_fluent(source_ftl)?;
+        let translated = parse_fluent(translated_ftl)?;
+
+        for (id, text) in source {
+            if let Some(translation) = translated.get(&id) {
+                self.insert(locale, text, translation.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Record translations for every string on the pass found in the catalog
+    ///
+    /// Covers the title, subtitle, field labels and values, and barcode
+    /// alternate text. Existing translations on the pass are kept unless the
+    /// catalog has an entry for the same locale.
+    pub fn apply(&self, pass: &mut Pass) {
+        let mut strings = vec![pass.header.title.clone()];
+        strings.extend(pass.header.subtitle.clone());
+        strings.extend(pass.barcode.as_ref().and_then(|b| b.alternate_text.clone()));
+        for field in &pass.fields {
+            strings.push(field.label.clone());
+            strings.push(field.value.clone());
+        }
+
+        for source in strings {
+            let found: BTreeMap<String, String> = self
+                .locales
+                .iter()
+                .filter_map(|(locale, entries)| {
+                    entries.get(&source).map(|t| (locale.clone(), t.clone()))
+                })
+                .collect();
+            if found.is_empty() {
+                continue;
+            }
+
+            match pass.translations.iter_mut().find(|t| t.value == source) {
+                Some(existing) => existing.translations.extend(found),
+                None => pass.translations.push(LocalizedValue {
+                    value: source,
+                    translations: found,
+                }),
+            }
+        }
+    }
+}
+
+/// Locales to try for `locale`, most specific first
+///
+/// Subtags are dropped one at a time, so `fr-CA` falls back to `fr` and
+/// `zh-Hant-TW` to `zh-Hant` then `zh`. Underscores are read as hyphens.
+///
+/// ```
+/// assert_eq!(porter::i18n::locale_fallbacks("fr_CA"), vec!["fr-CA", "fr"]);
+/// ```
+pub fn locale_fallbacks(locale: &str) -> Vec<String> {
+    let locale = locale.trim().replace('_', "-");
+    let mut fallbacks = vec![];
+    let mut current = locale.as_str();
+    while !current.is_empty() {
+        fallbacks.push(current.to_string());
+        current = match current.rfind('-') {
+            Some(index) => &current[..index],
+            None => "",
+        };
+    }
+    fallbacks
+}
+
+/// The value of the best-matching locale in `candidates`, following [`locale_fallbacks`]
+///
+/// Locales are compared case-insensitively, with underscores read as hyphens.
+pub(crate) fn best_match<'a, T: Copy>(
+    locale: &str,
+    candidates: impl Iterator<Item = (&'a str, T)> + Clone,
+) -> Option<T> {
+    let normalize = |locale: &str| locale.replace('_', "-").to_ascii_lowercase();
+    locale_fallbacks(locale).iter().find_map(|wanted| {
+        let wanted = normalize(wanted);
+        candidates
+            .clone()
+            .find(|(candidate, _)| normalize(candidate) == wanted)
+            .map(|(_, value)| value)
+    })
+}
+
+/// Parse `.po` content into (msgid, msgstr) pairs
+fn parse_po(po: &str) -> Result<Vec<(String, String)>> {
+    #[derive(PartialEq)]
+    enum Field {
+        None,
+        Context,
+        Id,
+        Plural,
+        Str,
+        OtherStr,
+    }
+
+    let mut entries = Vec::new();
+    let mut fuzzy = false;
+    let mut field = Field::None;
+    let mut msgid = String::new();
+    let mut msgstr = String::new();
+
+    let mut finish = |fuzzy: &mut bool, msgid: &mut String, msgstr: &mut String| {
+        if !*fuzzy && !msgid.is_empty() && !msgstr.is_empty() {
+            entries.push((std::mem::take(msgid), std::mem::take(msgstr)));
+        }
+        msgid.clear();
+        msgstr.clear();
+        *fuzzy = false;
+    };
+
+    for (number, line) in po.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(flags) = line.strip_prefix("#,") {
+            if field != Field::None {
+                finish(&mut fuzzy, &mut msgid, &mut msgstr);
+                field = Field::None;
+            }
+            fuzzy = flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let (keyword, rest) = match line.find(|c: char| c.is_whitespace()) {
+            Some(index) if !line.starts_with('"') => (&line[..index], line[index..].trim()),
+            _ => ("", line),
+        };
+        let text = unquote(rest).ok_or_else(|| {
+            PorterError::ValidationError(format!(
+                "Invalid string on line {} of .po file",
+                number + 1
+            ))
+        })?;
+
+        match keyword {
+            "msgctxt" | "msgid" => {
+                if matches!(field, Field::Str | Field::OtherStr) {
+                    finish(&mut fuzzy, &mut msgid, &mut msgstr);
+                }
+                if keyword == "msgctxt" {
+                    field = Field::Context;
+                } else {
+                    field = Field::Id;
+                    msgid = text;
+                }
+            }
+            "msgid_plural" => field = Field::Plural,
+            "msgstr" | "msgstr[0]" => {
+                field = Field::Str;
+                msgstr = text;
+            }
+            _ if keyword.starts_with("msgstr[") => field = Field::OtherStr,
+            "" => match field {
+                Field::Id => msgid.push_str(&text),
+                Field::Str => msgstr.push_str(&text),
+                _ => {}
+            },
+            _ => {
+                return Err(PorterError::ValidationError(format!(
+                    "Unknown keyword `{}` on line {} of .po file",
+                    keyword,
+                    number + 1
+                )))
+            }
+        }
+    }
+    finish(&mut fuzzy, &mut msgid, &mut msgstr);
+
+    Ok(entries)
+}
+
+/// Strip quotes from a `.po` string and resolve escapes
+fn unquote(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => text.push('\n'),
+            't' => text.push('\t'),
+            'r' => text.push('\r'),
+            other => text.push(other),
+        }
+    }
+    Some(text)
+}
+
+/// Parse the messages of a Fluent resource into id -> text
+///
+/// Only message values are read; terms, attributes and comments are skipped
+/// and placeables are kept verbatim.
+fn parse_fluent(ftl: &str) -> Result<HashMap<String, String>> {
+    let mut messages = HashMap::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+
+    let mut finish = |current: &mut Option<(String, Vec<String>)>| {
+        if let Some((id, lines)) = current.take() {
+            let value = lines.join("\n").trim().to_string();
+            if !value.is_empty() {
+                messages.insert(id, value);
+            }
+        }
+    };
+
+    for (number, line) in ftl.lines().enumerate() {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            let trimmed = line.trim();
+            if trimmed.starts_with('.') {
+                // Attribute lines end the message value
+                finish(&mut current);
+            } else if let Some((_, lines)) = current.as_mut() {
+                lines.push(trimmed.to_string());
+            }
+            continue;
+        }
+
+        finish(&mut current);
+        if line.trim().is_empty() || line.starts_with('#') || line.starts_with('-') {
+            continue;
+        }
+
+        let (id, value) = line.split_once('=').ok_or_else(|| {
+            PorterError::ValidationError(format!(
+                "Invalid message on line {} of Fluent resource",
+                number + 1
+            ))
+        })?;
+        current = Some((id.trim().to_string(), vec![value.trim().to_string()]));
+    }
+    finish(&mut current);
+
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_fallbacks() {
+        assert_eq!(
+            locale_fallbacks("zh-Hant-TW"),
+            vec!["zh-Hant-TW", "zh-Hant", "zh"]
+        );
+        assert!(locale_fallbacks("").is_empty());
+
+        let translations = [("fr", "Place"), ("fr-ca", "Siège"), ("de", "Platz")];
+        let lookup = |locale| best_match(locale, translations.iter().copied());
+        assert_eq!(lookup("fr-CA"), Some("Siège"));
+        assert_eq!(lookup("fr-BE"), Some("Place"));
+        assert_eq!(lookup("de_AT"), Some("Platz"));
+        assert_eq!(lookup("es"), None);
+    }
+
+    #[test]
+    fn test_parse_po() {
+        let po = r#"
+msgid ""
+msgstr ""
+"Language: fr\n"
+
+#: pass.rs:10
+msgid "Seat"
+msgstr "Place"
+
+#, fuzzy
+msgid "Row"
+msgstr "Rang"
+
+msgid "Gate"
+msgstr ""
+
+msgid "Doors open"
+msgid_plural "Doors open"
+msgstr[0] "Ouverture "
+"des portes"
+msgstr[1] "Ouverture des portes"
+"#;
+
+        let entries = parse_po(po).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("Seat".to_string(), "Place".to_string()),
+                ("Doors open".to_string(), "Ouverture des portes".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_fluent() {
+        let source = "# Labels\nseat = Seat\nwelcome =\n    Welcome to\n    the show\n    .title = Hi\n-brand = Porter\n";
+        let translated = "seat = Place\nwelcome = Bienvenue\n";
+
+        let mut catalog = TranslationCatalog::new();
+        catalog.load_fluent("fr-FR", source, translated).unwrap();
+
+        assert_eq!(catalog.translate("fr-FR", "Seat"), Some("Place"));
+        assert_eq!(
+            catalog.translate("fr-FR", "Welcome to\nthe show"),
+            Some("Bienvenue")
+        );
+    }
+
+    #[test]
+    fn test_apply_to_pass() {
+        let mut catalog = TranslationCatalog::new();
+        catalog.insert("fr-FR", "Concert", "Concert (FR)");
+        catalog.insert("de-DE", "Concert", "Konzert");
+        catalog.insert("de-DE", "Seat", "Platz");
+
+        let mut pass = crate::PassBuilder::new("issuer.pass", "issuer.class")
+            .title("Concert")
+            .field("seat", "Seat", "A23")
+            .build();
+        catalog.apply(&mut pass);
+
+        assert_eq!(pass.translations.len(), 2);
+        assert_eq!(pass.localized("Concert").unwrap().translations.len(), 2);
+        assert_eq!(
+            pass.localized("Seat").unwrap().translations["de-DE"],
+            "Platz"
+        );
+        assert!(pass.localized("A23").is_none());
+    }
+}
@@ -0,0 +1,134 @@
+//! Hosting of pass imagery
+//!
+//! Google Wallet only accepts images by public URL, while Apple pass bundles
+//! carry the image bytes themselves. An [`ImageHost`] uploads image bytes
+//! and returns the URL to put in Google classes and objects; builder methods
+//! such as [`PassBuilder::logo_bytes`](crate::PassBuilder::logo_bytes) use it
+//! so one set of bytes serves both platforms.
+
+use crate::error::{PorterError, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+
+/// Stores images and serves them from a public URL
+#[async_trait]
+pub trait ImageHost: Send + Sync {
+    /// Store `bytes` under `name` and return its public URL
+    async fn upload(&self, name: &str, content_type: &str, bytes: &[u8]) -> Result<String>;
+}
+
+/// Image host backed by HTTP PUT, such as a bucket or CDN origin
+///
+/// Images are PUT to `<upload_base>/<name>` and served from
+/// `<public_base>/<name>`. Names are derived from the image content, so
+/// uploading the same image twice yields the same URL.
+///
+/// # Example
+///
+/// ```
+/// use porter::image_host::HttpImageHost;
+///
+/// let host = HttpImageHost::new(
+///     "https://storage.example.com/wallet-assets",
+///     "https://cdn.example.com/wallet-assets",
+/// )
+/// .bearer_token("token");
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpImageHost {
+    client: Client,
+    upload_base: String,
+    public_base: String,
+    bearer_token: Option<String>,
+}
+
+impl HttpImageHost {
+    /// Create a host uploading under `upload_base` and serving from `public_base`
+    pub fn new(upload_base: impl Into<String>, public_base: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            upload_base: upload_base.into().trim_end_matches('/').to_string(),
+            public_base: public_base.into().trim_end_matches('/').to_string(),
+            bearer_token: None,
+        }
+    }
+
+    /// Authenticate uploads with a bearer token
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+}
+
+#[async_trait]
+impl ImageHost for HttpImageHost {
+    async fn upload(&self, name: &str, content_type: &str, bytes: &[u8]) -> Result<String> {
+        let mut request = self
+            .client
+            .put(format!("{}/{}", self.upload_base, name))
+            .header("Content-Type", content_type)
+            .body(bytes.to_vec());
+        if let Some(token) = &self.bearer_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PorterError::ApiError {
+                status: status.as_u16(),
+                message: response.text().await?,
+            });
+        }
+
+        Ok(format!("{}/{}", self.public_base, name))
+    }
+}
+
+/// Upload an image under a content-derived name, returning its public URL
+pub async fn host_image(host: &dyn ImageHost, bytes: &[u8]) -> Result<String> {
+    let (extension, content_type) = image_type(bytes)?;
+    host.upload(&image_name(bytes, extension), content_type, bytes)
+        .await
+}
+
+/// File extension and content type of PNG and JPEG images
+pub(crate) fn image_type(bytes: &[u8]) -> Result<(&'static str, &'static str)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Ok(("png", "image/png"))
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Ok(("jpg", "image/jpeg"))
+    } else {
+        Err(PorterError::ValidationError(
+            "Pass images must be PNG or JPEG".to_string(),
+        ))
+    }
+}
+
+/// Name an image after the SHA-256 of its content
+fn image_name(bytes: &[u8], extension: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, bytes);
+    let hex: String = digest
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("{}.{}", hex, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_name_and_type() {
+        let png = b"\x89PNG\r\n\x1a\nrest";
+        assert_eq!(image_type(png).unwrap(), ("png", "image/png"));
+        assert!(image_type(b"GIF89a").is_err());
+
+        let name = image_name(png, "png");
+        assert_eq!(name.len(), 64 + 4);
+        assert_eq!(name, image_name(png, "png"));
+        assert_ne!(name, image_name(b"other", "png"));
+    }
+}
@@ -4,7 +4,8 @@
 //! - A unified, platform-agnostic data model for wallet passes
 //! - A fluent builder API (`PassBuilder`) for creating passes
 //! - Automatic conversion between unified and platform-specific types
-//! - Platform-specific clients (Google Wallet implemented, Apple Wallet coming soon)
+//! - A Google Wallet API client
+//! - Apple Wallet `.pkpass` generation, signing, push updates and web service types
 //! - Authentication handling for Google Wallet API
 //! - CRUD operations for passes
 //!
@@ -69,16 +70,37 @@
 //! ```
 
 pub mod apple;
+pub mod audit;
 pub mod builder;
+pub mod credentials;
+pub mod debug;
 pub mod error;
+#[cfg(feature = "uniffi")]
+pub mod ffi;
 pub mod google;
+pub mod i18n;
+pub mod image_host;
+#[cfg(test)]
+mod mock_server;
 pub mod models;
+pub mod purge;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod shortener;
+pub mod snapshot;
+pub mod usage;
+pub mod webhook;
 
 // Re-export commonly used types
 pub use builder::PassBuilder;
 pub use error::{PorterError, Result};
 pub use models::*;
 
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!("porter");
+
 #[cfg(test)]
 mod tests {
     use super::*;
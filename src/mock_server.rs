@@ -0,0 +1,94 @@
+//! Local HTTP server answering canned JSON responses, for client tests
+
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub(crate) struct MockRequest {
+    pub method: String,
+    /// Path and query, such as `/genericObject?classId=issuer.class`
+    pub path: String,
+    pub body: Option<Value>,
+}
+
+/// Requests received so far, in order
+pub(crate) type Received = Arc<Mutex<Vec<MockRequest>>>;
+
+/// Serve `(method, path, status, body)` routes on a local port
+///
+/// A request with several routes gets them in order, then the last one
+/// again. Requests without a route get a 404 with an empty object. Returns
+/// the server's base URL, such as `http://127.0.0.1:51234`.
+pub(crate) async fn serve(routes: Vec<(&str, &str, u16, Value)>) -> (String, Received) {
+    let routes: Vec<(String, String, u16, Value)> = routes
+        .into_iter()
+        .map(|(method, path, status, body)| (method.into(), path.into(), status, body))
+        .collect();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let received = Received::default();
+
+    let log = received.clone();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = vec![];
+            let mut chunk = [0u8; 4096];
+            let (head, body) = loop {
+                let n = socket.read(&mut chunk).await.unwrap();
+                request.extend_from_slice(&chunk[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                    continue;
+                };
+                let length = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length: ")?
+                            .parse()
+                            .ok()
+                    })
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    break (head.to_string(), body.to_string());
+                }
+            };
+
+            let mut request_line = head.split(' ');
+            let method = request_line.next().unwrap_or_default().to_string();
+            let path = request_line.next().unwrap_or_default().to_string();
+            log.lock().unwrap().push(MockRequest {
+                method: method.clone(),
+                path: path.clone(),
+                body: serde_json::from_str(&body).ok(),
+            });
+
+            let matching: Vec<_> = routes
+                .iter()
+                .filter(|route| route.0 == method && route.1 == path)
+                .collect();
+            let hits = log
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|request| request.method == method && request.path == path)
+                .count();
+            let (status, body) = matching
+                .get(hits.saturating_sub(1).min(matching.len().saturating_sub(1)))
+                .map(|route| (route.2, route.3.to_string()))
+                .unwrap_or((404, "{}".to_string()));
+            let response = format!(
+                "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    (format!("http://{}", address), received)
+}
@@ -1,7 +1,10 @@
+use crate::apple::AssetSet;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Platform-agnostic pass data structure
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pass {
     /// Unique identifier for the pass
@@ -33,9 +36,147 @@ pub struct Pass {
 
     /// Last updated timestamp
     pub updated_at: Option<DateTime<Utc>>,
+
+    /// Translations of the strings shown on the pass
+    #[serde(default)]
+    pub translations: Vec<LocalizedValue>,
+
+    /// Language of the strings on the pass, `en-US` when not set
+    #[serde(default)]
+    pub default_locale: Option<String>,
+
+    /// When and where the pass is surfaced on the lock screen
+    #[serde(default)]
+    pub relevance: Relevance,
+
+    /// Value sent to Smart Tap terminals when the pass is tapped
+    #[serde(default)]
+    pub smart_tap_redemption_value: Option<String>,
+
+    /// Link from the pass to the issuer's app
+    #[serde(default)]
+    pub app_link: Option<AppLink>,
+
+    /// Extra images shown on the back of the pass
+    #[serde(default)]
+    pub images: Vec<Image>,
+
+    /// Group the pass is shown with, such as the other tickets of an order
+    #[serde(default)]
+    pub grouping: Option<Grouping>,
+
+    /// Reminders sent around the validity period
+    #[serde(default)]
+    pub notifications: PassNotifications,
+
+    /// Images to embed in the Apple bundle, such as a logo added with
+    /// [`PassBuilder::logo_bytes`](crate::PassBuilder::logo_bytes)
+    ///
+    /// Not serialized; write them into a bundle with
+    /// [`PkPassBundle::assets`](crate::apple::PkPassBundle::assets).
+    #[serde(skip)]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    pub apple_assets: AssetSet,
+}
+
+/// Key of the "Valid for N more days" field
+pub const EXPIRY_COUNTDOWN_KEY: &str = "expiry_countdown";
+
+impl Pass {
+    /// Look up the translations of a string shown on the pass
+    pub fn localized(&self, value: &str) -> Option<&LocalizedValue> {
+        self.translations.iter().find(|l| l.value == value)
+    }
+
+    /// The seat assignment recorded in the pass fields, if any
+    pub fn seat(&self) -> Option<Seat> {
+        let value = |key: &str| {
+            self.fields
+                .iter()
+                .find(|f| f.key == key)
+                .map(|f| f.value.clone())
+        };
+        let seat = Seat {
+            section: value(SEAT_SECTION_KEY),
+            row: value(SEAT_ROW_KEY),
+            number: value(SEAT_NUMBER_KEY),
+        };
+        (seat != Seat::default()).then_some(seat)
+    }
+
+    /// Recompute the expiry countdown field as of `now`
+    ///
+    /// Only passes that have an [`EXPIRY_COUNTDOWN_KEY`] field are changed.
+    /// The countdown is derived from the end of `valid_time_interval`, and
+    /// the field is removed when the pass has no end date. Returns whether
+    /// the pass changed.
+    pub fn refresh_expiry_countdown(&mut self, now: DateTime<Utc>) -> bool {
+        let Some(index) = self
+            .fields
+            .iter()
+            .position(|f| f.key == EXPIRY_COUNTDOWN_KEY)
+        else {
+            return false;
+        };
+
+        let Some(end) = self.valid_time_interval.as_ref().and_then(|i| i.end) else {
+            self.fields.remove(index);
+            return true;
+        };
+
+        let value = expiry_countdown(end - now);
+        let field = &mut self.fields[index];
+        if field.value == value {
+            return false;
+        }
+        field.value = value;
+        true
+    }
+}
+
+/// Text shown for the time left until a pass expires
+fn expiry_countdown(remaining: chrono::Duration) -> String {
+    if remaining <= chrono::Duration::zero() {
+        return "Expired".to_string();
+    }
+    // Round partial days up, so a pass expiring tomorrow morning is valid for 1 more day
+    let days = (remaining.num_seconds() + 86_399) / 86_400;
+    match days {
+        1 => "Valid for 1 more day".to_string(),
+        days => format!("Valid for {} more days", days),
+    }
+}
+
+/// A string with its translations keyed by locale (e.g. "fr-FR")
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LocalizedValue {
+    pub value: String,
+    pub translations: BTreeMap<String, String>,
+}
+
+impl LocalizedValue {
+    /// The translation for `locale`, falling back to less specific locales
+    ///
+    /// `fr-CA` falls back to `fr`. Returns `None` when no translation matches.
+    pub fn translation(&self, locale: &str) -> Option<&str> {
+        crate::i18n::best_match(
+            locale,
+            self.translations
+                .iter()
+                .map(|(locale, value)| (locale.as_str(), value.as_str())),
+        )
+    }
+
+    /// The best text for `locale`, falling back to the untranslated value
+    pub fn resolve(&self, locale: &str) -> &str {
+        self.translation(locale).unwrap_or(&self.value)
+    }
 }
 
 /// Types of passes supported
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PassType {
     EventTicket,
@@ -48,6 +189,7 @@ pub enum PassType {
 }
 
 /// Pass header information
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassHeader {
     pub title: String,
@@ -55,9 +197,50 @@ pub struct PassHeader {
     pub logo: Option<Image>,
     pub background_color: Option<String>,
     pub foreground_color: Option<String>,
+    /// Wide banner image shown on the front of the pass
+    #[serde(default)]
+    pub hero_image: Option<Image>,
+}
+
+/// Visual identity shared by a brand's passes
+///
+/// Applied with [`PassBuilder::theme`](crate::PassBuilder::theme); only the
+/// values that are set are applied.
+///
+/// # Example
+///
+/// ```
+/// use porter::models::{Image, PassTheme, PassType};
+/// use porter::PassBuilder;
+///
+/// let theme = PassTheme {
+///     background_color: Some("#1A1A2E".to_string()),
+///     foreground_color: Some("#FFFFFF".to_string()),
+///     logo: Some(Image {
+///         source_uri: "https://example.com/logo.png".to_string(),
+///         alt_text: Some("Example".to_string()),
+///     }),
+///     ..Default::default()
+/// };
+///
+/// let ticket = PassBuilder::new("issuer.ticket", "issuer.event")
+///     .pass_type(PassType::EventTicket)
+///     .theme(&theme)
+///     .build();
+/// assert_eq!(ticket.header.background_color.as_deref(), Some("#1A1A2E"));
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PassTheme {
+    pub background_color: Option<String>,
+    pub foreground_color: Option<String>,
+    pub logo: Option<Image>,
+    pub hero_image: Option<Image>,
+    /// Language of the strings on the pass, such as `fr-FR`
+    pub default_locale: Option<String>,
 }
 
 /// Image resource
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Image {
     pub source_uri: String,
@@ -65,13 +248,110 @@ pub struct Image {
 }
 
 /// Barcode representation
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Barcode {
     pub format: BarcodeFormat,
+    /// Static value, shown where rotating barcodes are unsupported
     pub value: String,
     pub alternate_text: Option<String>,
+    /// Time-based value that replaces `value` on Google Wallet
+    #[serde(default)]
+    pub rotating: Option<RotatingBarcode>,
+}
+
+/// Barcode whose value changes over time, so screenshots stop working
+///
+/// Each `{totp_value_N}` placeholder in `value_pattern` is replaced by the
+/// TOTP code of `parameters[N]`, recomputed every `period_seconds`.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RotatingBarcode {
+    pub value_pattern: String,
+    pub period_seconds: u32,
+    pub parameters: Vec<TotpParameter>,
+}
+
+/// Secret and code length of one TOTP value in a rotating barcode
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TotpParameter {
+    /// Shared secret, hex encoded
+    pub key: String,
+    /// Number of digits in the code
+    pub value_length: u8,
+}
+
+impl RotatingBarcode {
+    /// A barcode that is just one 8 digit TOTP code from `key`
+    pub fn totp(key: impl Into<String>, period_seconds: u32) -> Self {
+        Self {
+            value_pattern: "{totp_value_0}".to_string(),
+            period_seconds,
+            parameters: vec![TotpParameter {
+                key: key.into(),
+                value_length: 8,
+            }],
+        }
+    }
+}
+
+/// Automatic reminders about a pass
+///
+/// Only Google Wallet sends them. The expiry reminder needs the end of the
+/// validity period, and the upcoming one its start.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PassNotifications {
+    /// Remind the holder shortly before the pass expires
+    pub expiry: bool,
+    /// Remind the holder shortly before the pass becomes valid
+    pub upcoming: bool,
 }
 
+/// Passes shown together in the wallet, such as the tickets of one order
+///
+/// Maps to Google's `groupingInfo` and Apple's `groupingIdentifier`. Apple
+/// only groups event tickets and boarding passes, and has no sort order.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Grouping {
+    pub id: String,
+    /// Position of the pass within the group, lowest first
+    pub sort_index: Option<i32>,
+}
+
+/// Link from a pass to the issuer's app
+///
+/// Apple Wallet passes `uri` to the app as its `appLaunchURL`; Google
+/// Wallet shows it as a button that opens the app, or the page if `uri` is
+/// a web address.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppLink {
+    pub uri: String,
+    /// Button text on Google Wallet
+    pub title: Option<String>,
+    /// Package name of the Android app
+    pub android_package: Option<String>,
+    /// App Store id of the iOS app
+    pub app_store_id: Option<u64>,
+}
+
+impl AppLink {
+    /// A link to `uri` with no app details
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            title: None,
+            android_package: None,
+            app_store_id: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum BarcodeFormat {
     QrCode,
@@ -81,14 +361,78 @@ pub enum BarcodeFormat {
 }
 
 /// Dynamic field on a pass
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassField {
     pub key: String,
     pub label: String,
     pub value: String,
     pub text_alignment: Option<TextAlignment>,
+    /// Link opened from the field, such as `https://`, `tel:` or `mailto:`
+    #[serde(default)]
+    pub link: Option<String>,
+    /// How the value is formatted, for values that are dates or numbers
+    #[serde(default)]
+    pub format: Option<FieldFormat>,
+    /// Notification shown when the value changes; `%@` is replaced by the new value
+    #[serde(default)]
+    pub change_message: Option<String>,
+}
+
+/// Formatting of a field value, applied in the holder's locale
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FieldFormat {
+    /// The value is an RFC 3339 date and time
+    DateTime {
+        date_style: DateTimeStyle,
+        time_style: DateTimeStyle,
+    },
+    /// The value is a number
+    Number(NumberFormat),
+    /// The value is an amount of money in this ISO 4217 currency, such as `EUR`
+    Currency(String),
+}
+
+/// Length of the date or time part of a formatted date
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DateTimeStyle {
+    /// Omitted
+    None,
+    Short,
+    Medium,
+    Long,
+    Full,
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NumberFormat {
+    Decimal,
+    Percent,
+    Scientific,
+    /// Written out in words
+    SpellOut,
 }
 
+/// Keys of the fields holding a ticket's seat assignment
+pub const SEAT_SECTION_KEY: &str = "section";
+pub const SEAT_ROW_KEY: &str = "row";
+pub const SEAT_NUMBER_KEY: &str = "seat";
+
+/// Seat assignment for event and travel tickets
+///
+/// Stored on a pass as the `section`, `row` and `seat` fields, and rendered
+/// as Google `EventSeat` and Apple semantic seat tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Seat {
+    pub section: Option<String>,
+    pub row: Option<String>,
+    pub number: Option<String>,
+}
+
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TextAlignment {
     Left,
@@ -98,6 +442,8 @@ pub enum TextAlignment {
 }
 
 /// Pass state
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PassState {
     Active,
@@ -107,13 +453,60 @@ pub enum PassState {
 }
 
 /// Time interval for pass validity
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeInterval {
     pub start: DateTime<Utc>,
     pub end: Option<DateTime<Utc>>,
 }
 
+/// When and where a pass is relevant to its holder
+///
+/// Apple Wallet shows a relevant pass on the lock screen around its relevant
+/// date, or near one of its locations or beacons.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Relevance {
+    pub relevant_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub locations: Vec<RelevantLocation>,
+    #[serde(default)]
+    pub beacons: Vec<RelevantBeacon>,
+    /// Meters from a location within which the pass is relevant
+    pub max_distance: Option<f64>,
+}
+
+impl Relevance {
+    /// Whether the pass has no relevance information
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A place where a pass is relevant
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RelevantLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+    /// Lock screen text shown near the location
+    pub relevant_text: Option<String>,
+}
+
+/// A Bluetooth beacon near which a pass is relevant
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RelevantBeacon {
+    pub proximity_uuid: String,
+    pub major: Option<u16>,
+    pub minor: Option<u16>,
+    /// Lock screen text shown near the beacon
+    pub relevant_text: Option<String>,
+}
+
 /// Message that can be sent to pass holders
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassMessage {
     pub header: Option<String>,
@@ -123,14 +516,26 @@ pub struct PassMessage {
 }
 
 /// Class definition (template for passes)
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassClass {
     pub id: String,
     pub pass_type: PassType,
     pub issuer_name: String,
     pub review_status: ReviewStatus,
+
+    /// Defaults for passes of this class, see `PassBuilder::from_class`
+    #[serde(default)]
+    pub logo: Option<Image>,
+    #[serde(default)]
+    pub background_color: Option<String>,
+    #[serde(default)]
+    pub foreground_color: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<PassField>,
 }
 
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ReviewStatus {
     Draft,
@@ -138,3 +543,32 @@ pub enum ReviewStatus {
     Approved,
     Rejected,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_refresh_expiry_countdown() {
+        let now = Utc.with_ymd_and_hms(2027, 3, 1, 12, 0, 0).unwrap();
+        let mut pass = crate::PassBuilder::new("issuer.pass", "issuer.class")
+            .valid_until(now + chrono::Duration::hours(60))
+            .expiry_countdown()
+            .build();
+
+        assert!(pass.refresh_expiry_countdown(now));
+        assert_eq!(pass.fields[0].value, "Valid for 3 more days");
+        assert!(!pass.refresh_expiry_countdown(now));
+
+        assert!(pass.refresh_expiry_countdown(now + chrono::Duration::hours(40)));
+        assert_eq!(pass.fields[0].value, "Valid for 1 more day");
+
+        assert!(pass.refresh_expiry_countdown(now + chrono::Duration::days(3)));
+        assert_eq!(pass.fields[0].value, "Expired");
+
+        pass.valid_time_interval = None;
+        assert!(pass.refresh_expiry_countdown(now));
+        assert!(pass.fields.is_empty());
+    }
+}
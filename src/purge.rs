@@ -0,0 +1,323 @@
+//! Holder data purge and bulk expiry reports
+//!
+//! Produced by [`GoogleWalletClient::purge_holder`](crate::google::GoogleWalletClient::purge_holder)
+//! when a pass holder asks for their data to be deleted, with
+//! [`ApplePurge`] covering their Apple passes and devices, and by
+//! [`GoogleWalletClient::expire_all`](crate::google::GoogleWalletClient::expire_all)
+//! when every pass of a class has to be expired.
+
+use crate::apple::{ApnsClient, PassRegistration, PkPassBundle, PushReport, RegistrationStore};
+use crate::error::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Outcome of purging a holder's passes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeReport {
+    /// Google Wallet objects
+    pub google: GooglePurgeReport,
+    /// Apple passes and their device registrations
+    pub apple: ApplePurgeReport,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl PurgeReport {
+    /// Whether every requested pass was purged or did not exist
+    pub fn is_complete(&self) -> bool {
+        self.google.failed.is_empty() && self.apple.failed.is_empty()
+    }
+}
+
+/// Outcome of purging a holder's Google Wallet objects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GooglePurgeReport {
+    /// Objects that were expired and stripped of holder data
+    pub purged: Vec<String>,
+    /// Objects that did not exist
+    pub not_found: Vec<String>,
+    /// Objects that could not be purged
+    pub failed: Vec<PurgeFailure>,
+}
+
+/// Outcome of purging a holder's Apple passes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApplePurgeReport {
+    /// Serial numbers of the passes that were voided
+    pub voided: Vec<String>,
+    /// Serial numbers with no stored pass
+    pub not_found: Vec<String>,
+    /// Device registrations removed from the store
+    pub unregistered: Vec<PassRegistration>,
+    /// Notifications sent to the devices of the voided passes, if an
+    /// APNs client was given
+    pub push: Option<PushReport>,
+    /// Passes that could not be voided, notified or unregistered
+    pub failed: Vec<PurgeFailure>,
+}
+
+/// Outcome of expiring the passes of a class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpireReport {
+    /// Passes that were expired
+    pub expired: Vec<String>,
+    /// Passes that were already expired or did not match the filter
+    pub skipped: Vec<String>,
+    /// Passes that could not be expired
+    pub failed: Vec<PurgeFailure>,
+    pub completed_at: DateTime<Utc>,
+}
+
+impl ExpireReport {
+    /// Whether every matching pass was expired
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// A pass that could not be purged or expired
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeFailure {
+    pub pass_id: String,
+    pub error: String,
+}
+
+/// Storage of the signed Apple passes served by the web service
+#[async_trait]
+pub trait PassStore: Send + Sync {
+    /// The bundle currently served for a pass, if there is one
+    async fn load_pass(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Option<PkPassBundle>>;
+
+    /// Replace the bundle served for a pass
+    ///
+    /// Bundles saved by [`ApplePurge`] have been voided and are no longer
+    /// signed; sign them before serving them.
+    async fn save_pass(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+        bundle: PkPassBundle,
+    ) -> Result<()>;
+}
+
+/// Apple passes of a holder to purge alongside their Google objects
+pub struct ApplePurge<'a> {
+    pub pass_type_identifier: &'a str,
+    pub serial_numbers: &'a [&'a str],
+    pub passes: &'a dyn PassStore,
+    pub registrations: &'a dyn RegistrationStore,
+    /// Notifies the devices of voided passes; without it they are only
+    /// marked updated
+    pub apns: Option<&'a ApnsClient>,
+}
+
+impl ApplePurge<'_> {
+    /// Void each pass, notify its devices, then unregister them
+    ///
+    /// Registrations are removed for every serial number, including those
+    /// with no stored pass. Failures are collected in the report rather
+    /// than aborting the purge.
+    pub async fn run(&self) -> ApplePurgeReport {
+        let mut report = ApplePurgeReport::default();
+        let pass_type = self.pass_type_identifier;
+
+        for serial_number in self.serial_numbers {
+            let voided = match self.passes.load_pass(pass_type, serial_number).await {
+                Ok(Some(bundle)) => match bundle.voided() {
+                    Ok(bundle) => {
+                        self.passes
+                            .save_pass(pass_type, serial_number, bundle)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                },
+                Ok(None) => {
+                    report.not_found.push(serial_number.to_string());
+                    continue;
+                }
+                Err(e) => Err(e),
+            };
+            match voided {
+                Ok(()) => report.voided.push(serial_number.to_string()),
+                Err(e) => report.failed.push(failure(serial_number, e)),
+            }
+        }
+
+        let voided = report.voided.clone();
+        let voided: Vec<&str> = voided.iter().map(String::as_str).collect();
+        let notified = match self.apns {
+            Some(apns) => apns
+                .push_updates(self.registrations, pass_type, &voided)
+                .await
+                .map(|push| report.push = Some(push)),
+            None => {
+                async {
+                    for serial_number in &voided {
+                        self.registrations
+                            .mark_updated(pass_type, serial_number)
+                            .await?;
+                    }
+                    Ok(())
+                }
+                .await
+            }
+        };
+        if let Err(e) = notified {
+            let error = e.to_string();
+            report
+                .failed
+                .extend(voided.iter().map(|serial_number| PurgeFailure {
+                    pass_id: serial_number.to_string(),
+                    error: error.clone(),
+                }));
+        }
+
+        for serial_number in self.serial_numbers {
+            let registrations = match self
+                .registrations
+                .registrations(pass_type, serial_number)
+                .await
+            {
+                Ok(registrations) => registrations,
+                Err(e) => {
+                    report.failed.push(failure(serial_number, e));
+                    continue;
+                }
+            };
+            for registration in registrations {
+                match self.registrations.unregister(&registration).await {
+                    Ok(()) => report.unregistered.push(registration),
+                    Err(e) => report.failed.push(failure(serial_number, e)),
+                }
+            }
+        }
+        report
+    }
+}
+
+fn failure(pass_id: &str, error: crate::error::PorterError) -> PurgeFailure {
+    PurgeFailure {
+        pass_id: pass_id.to_string(),
+        error: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apple::{ApnsConfig, MemoryRegistrationStore};
+    use crate::mock_server;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    const PASS_TYPE: &str = "pass.com.example.ticket";
+
+    #[derive(Default)]
+    struct MemoryPassStore {
+        passes: Mutex<HashMap<String, PkPassBundle>>,
+    }
+
+    #[async_trait]
+    impl PassStore for MemoryPassStore {
+        async fn load_pass(&self, _: &str, serial_number: &str) -> Result<Option<PkPassBundle>> {
+            Ok(self.passes.lock().unwrap().get(serial_number).cloned())
+        }
+
+        async fn save_pass(
+            &self,
+            _: &str,
+            serial_number: &str,
+            bundle: PkPassBundle,
+        ) -> Result<()> {
+            self.passes
+                .lock()
+                .unwrap()
+                .insert(serial_number.to_string(), bundle);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apple_purge() {
+        let (server, received) = mock_server::serve(vec![(
+            "POST",
+            "/3/device/token-1",
+            200,
+            serde_json::json!({}),
+        )])
+        .await;
+        let apns = ApnsClient::new(ApnsConfig {
+            key_id: "ABC123DEFG".to_string(),
+            team_id: "ABCDE12345".to_string(),
+            private_key: include_str!("apple/testdata/apns_key.p8").to_string(),
+            topic: PASS_TYPE.to_string(),
+        })
+        .with_server(server);
+
+        let passes = MemoryPassStore::default();
+        let bundle = PkPassBundle::new(&serde_json::json!({ "formatVersion": 1 }))
+            .unwrap()
+            .signature(vec![1, 2, 3]);
+        passes.save_pass(PASS_TYPE, "A", bundle).await.unwrap();
+
+        let registrations = MemoryRegistrationStore::new();
+        for (device, serial_number) in [("phone", "A"), ("watch", "B"), ("phone", "C")] {
+            let registration = PassRegistration {
+                device_library_identifier: device.to_string(),
+                pass_type_identifier: PASS_TYPE.to_string(),
+                serial_number: serial_number.to_string(),
+            };
+            registrations
+                .register(&registration, "token-1")
+                .await
+                .unwrap();
+        }
+
+        let report = ApplePurge {
+            pass_type_identifier: PASS_TYPE,
+            serial_numbers: &["A", "B"],
+            passes: &passes,
+            registrations: &registrations,
+            apns: Some(&apns),
+        }
+        .run()
+        .await;
+
+        assert_eq!(report.voided, vec!["A"]);
+        assert_eq!(report.not_found, vec!["B"]);
+        assert!(report.failed.is_empty());
+        assert_eq!(report.push.unwrap().notified, vec!["token-1"]);
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        let voided = passes.load_pass(PASS_TYPE, "A").await.unwrap().unwrap();
+        let pass_json: serde_json::Value =
+            serde_json::from_slice(voided.get("pass.json").unwrap()).unwrap();
+        assert_eq!(pass_json["voided"], true);
+        assert!(voided.signature_der().is_none());
+
+        let unregistered: Vec<_> = report
+            .unregistered
+            .iter()
+            .map(|r| {
+                (
+                    r.device_library_identifier.as_str(),
+                    r.serial_number.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(unregistered, vec![("phone", "A"), ("watch", "B")]);
+        assert!(registrations
+            .push_tokens(PASS_TYPE, "A")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            registrations.push_tokens(PASS_TYPE, "C").await.unwrap(),
+            vec!["token-1"]
+        );
+    }
+}
@@ -0,0 +1,334 @@
+//! Python bindings
+//!
+//! Built with maturin (see `pyproject.toml`) into a `porter` extension
+//! module. Passes and objects are exchanged as plain dicts in the same shape
+//! as their serde JSON, and enum values use their Rust names (for example
+//! `"EventTicket"` or `"QrCode"`).
+//!
+//! ```python
+//! import porter
+//!
+//! pass_ = (porter.PassBuilder("issuer.pass_001", "issuer.class_001")
+//!     .title("Concert Ticket")
+//!     .barcode("QrCode", "TICKET123")
+//!     .build())
+//!
+//! client = porter.GoogleWalletClient(issuer_id, email, private_key)
+//! url = client.save_url(pass_)
+//!
+//! pkpass = porter.sign_pkpass(pass_json, {"icon.png": icon}, cert_pem, key_pem, wwdr_pem)
+//! ```
+
+use crate::apple::signing;
+use crate::builder::PassBuilder;
+use crate::error::PorterError;
+use crate::google::{GenericObject, GoogleWalletClient, GoogleWalletConfig};
+use crate::models::Pass;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+create_exception!(porter, PorterException, PyException);
+
+impl From<PorterError> for PyErr {
+    fn from(error: PorterError) -> Self {
+        PorterException::new_err(error.to_string())
+    }
+}
+
+/// Convert a Python object to a Rust value through JSON
+fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    Ok(serde_json::from_str(&json).map_err(PorterError::from)?)
+}
+
+/// Convert a Rust value to a Python object through JSON
+fn to_py<'py, T: Serialize>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(PorterError::from)?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// Parse an enum from its Rust variant name
+fn variant<T: DeserializeOwned>(name: &str) -> PyResult<T> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| PorterException::new_err(format!("Unknown value `{}`", name)))
+}
+
+/// Fluent pass builder
+#[pyclass(name = "PassBuilder")]
+struct PyPassBuilder {
+    inner: Option<PassBuilder>,
+}
+
+impl PyPassBuilder {
+    fn update(
+        mut slf: PyRefMut<'_, Self>,
+        f: impl FnOnce(PassBuilder) -> PassBuilder,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        let builder = slf
+            .inner
+            .take()
+            .ok_or_else(|| PorterException::new_err("Pass builder was already built"))?;
+        slf.inner = Some(f(builder));
+        Ok(slf)
+    }
+}
+
+#[pymethods]
+impl PyPassBuilder {
+    #[new]
+    fn new(id: String, class_id: String) -> Self {
+        Self {
+            inner: Some(PassBuilder::new(id, class_id)),
+        }
+    }
+
+    fn pass_type<'py>(slf: PyRefMut<'py, Self>, pass_type: &str) -> PyResult<PyRefMut<'py, Self>> {
+        let pass_type = variant(pass_type)?;
+        Self::update(slf, |b| b.pass_type(pass_type))
+    }
+
+    fn title(slf: PyRefMut<'_, Self>, title: String) -> PyResult<PyRefMut<'_, Self>> {
+        Self::update(slf, |b| b.title(title))
+    }
+
+    fn subtitle(slf: PyRefMut<'_, Self>, subtitle: String) -> PyResult<PyRefMut<'_, Self>> {
+        Self::update(slf, |b| b.subtitle(subtitle))
+    }
+
+    fn background_color(slf: PyRefMut<'_, Self>, color: String) -> PyResult<PyRefMut<'_, Self>> {
+        Self::update(slf, |b| b.background_color(color))
+    }
+
+    fn foreground_color(slf: PyRefMut<'_, Self>, color: String) -> PyResult<PyRefMut<'_, Self>> {
+        Self::update(slf, |b| b.foreground_color(color))
+    }
+
+    #[pyo3(signature = (format, value, alternate_text=None))]
+    fn barcode<'py>(
+        slf: PyRefMut<'py, Self>,
+        format: &str,
+        value: String,
+        alternate_text: Option<String>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let format = variant(format)?;
+        Self::update(slf, |b| match alternate_text {
+            Some(text) => b.barcode_with_text(format, value, text),
+            None => b.barcode(format, value),
+        })
+    }
+
+    fn field(
+        slf: PyRefMut<'_, Self>,
+        key: String,
+        label: String,
+        value: String,
+    ) -> PyResult<PyRefMut<'_, Self>> {
+        Self::update(slf, |b| b.field(key, label, value))
+    }
+
+    fn link_object(slf: PyRefMut<'_, Self>, object_id: String) -> PyResult<PyRefMut<'_, Self>> {
+        Self::update(slf, |b| b.link_object(object_id))
+    }
+
+    fn state<'py>(slf: PyRefMut<'py, Self>, state: &str) -> PyResult<PyRefMut<'py, Self>> {
+        let state = variant(state)?;
+        Self::update(slf, |b| b.state(state))
+    }
+
+    /// Build the pass as a dict
+    fn build<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let builder = self
+            .inner
+            .take()
+            .ok_or_else(|| PorterException::new_err("Pass builder was already built"))?;
+        to_py(py, &builder.build())
+    }
+}
+
+/// Convert a pass dict to a Google Wallet GenericObject dict
+#[pyfunction]
+fn to_google_object<'py>(pass: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let object = GenericObject::from(&from_py::<Pass>(pass)?);
+    to_py(pass.py(), &object)
+}
+
+/// Build and sign a `.pkpass`, returning the archive as bytes
+///
+/// `pass_json` is an Apple `pass.json` dict; `assets` maps file names such
+/// as `icon.png` or `en.lproj/pass.strings` to their bytes. The certificate,
+/// its private key and the WWDR intermediate are PEM strings.
+#[pyfunction]
+fn sign_pkpass<'py>(
+    py: Python<'py>,
+    pass_json: &Bound<'py, PyAny>,
+    assets: HashMap<String, Vec<u8>>,
+    certificate_pem: &str,
+    private_key_pem: &str,
+    wwdr_pem: &str,
+) -> PyResult<Bound<'py, PyBytes>> {
+    let pass_json = serde_json::to_string(&from_py::<serde_json::Value>(pass_json)?)
+        .map_err(PorterError::from)?;
+    let pkpass = signing::generate_pkpass(
+        &pass_json,
+        assets,
+        certificate_pem,
+        private_key_pem,
+        wwdr_pem,
+    )?;
+    Ok(PyBytes::new(py, &pkpass))
+}
+
+/// Blocking Google Wallet client
+///
+/// Calls release the GIL while waiting on the network.
+#[pyclass(name = "GoogleWalletClient")]
+struct PyGoogleWalletClient {
+    inner: GoogleWalletClient,
+    runtime: tokio::runtime::Runtime,
+}
+
+#[pymethods]
+impl PyGoogleWalletClient {
+    #[new]
+    fn new(
+        issuer_id: String,
+        service_account_email: String,
+        private_key: String,
+    ) -> PyResult<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(PorterError::from)?;
+        Ok(Self {
+            inner: GoogleWalletClient::new(GoogleWalletConfig {
+                issuer_id,
+                service_account_email,
+                private_key,
+            }),
+            runtime,
+        })
+    }
+
+    /// Create a pass as a generic object, returning the created object
+    fn create_pass<'py>(
+        &mut self,
+        py: Python<'py>,
+        pass: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let object = GenericObject::from(&from_py::<Pass>(pass)?);
+        let Self { inner, runtime } = self;
+        let created =
+            py.allow_threads(|| runtime.block_on(inner.create_generic_object(&object)))?;
+        to_py(py, &created)
+    }
+
+    /// Get a generic object by id
+    fn get_object<'py>(&mut self, py: Python<'py>, object_id: &str) -> PyResult<Bound<'py, PyAny>> {
+        let Self { inner, runtime } = self;
+        let object = py.allow_threads(|| runtime.block_on(inner.get_generic_object(object_id)))?;
+        to_py(py, &object)
+    }
+
+    /// Generate a signed save URL for a pass
+    fn save_url(&mut self, py: Python<'_>, pass: &Bound<'_, PyAny>) -> PyResult<String> {
+        let object = GenericObject::from(&from_py::<Pass>(pass)?);
+        let Self { inner, runtime } = self;
+        Ok(py.allow_threads(|| runtime.block_on(inner.generate_save_url(&object)))?)
+    }
+}
+
+#[pymodule]
+fn porter(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("PorterException", m.py().get_type::<PorterException>())?;
+    m.add_class::<PyPassBuilder>()?;
+    m.add_class::<PyGoogleWalletClient>()?;
+    m.add_function(wrap_pyfunction!(to_google_object, m)?)?;
+    m.add_function(wrap_pyfunction!(sign_pkpass, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_to_google_object() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let builder = Bound::new(
+                py,
+                PyPassBuilder::new("issuer.pass".to_string(), "issuer.class".to_string()),
+            )
+            .unwrap();
+            let pass = builder
+                .call_method1("title", ("Concert",))
+                .unwrap()
+                .call_method1("barcode", ("QrCode", "TICKET123"))
+                .unwrap()
+                .call_method0("build")
+                .unwrap();
+            assert_eq!(
+                pass.get_item("header")
+                    .unwrap()
+                    .get_item("title")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "Concert"
+            );
+
+            let object = to_google_object(&pass).unwrap();
+            assert_eq!(
+                object
+                    .get_item("barcode")
+                    .unwrap()
+                    .get_item("type")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "QR_CODE"
+            );
+
+            assert!(builder.call_method0("build").is_err());
+        });
+    }
+
+    #[test]
+    fn test_sign_pkpass() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let pass = crate::apple::ApplePass::new(
+                "pass.com.example.ticket",
+                "001",
+                "ABCDE12345",
+                "Example Events",
+                "Concert ticket",
+                crate::apple::PassStyle::EventTicket(Default::default()),
+            );
+            let pkpass = sign_pkpass(
+                py,
+                &to_py(py, &pass).unwrap(),
+                HashMap::from([("icon.png".to_string(), vec![1, 2, 3])]),
+                include_str!("apple/testdata/pass_cert.pem"),
+                include_str!("apple/testdata/pass_key.pem"),
+                include_str!("apple/testdata/wwdr.pem"),
+            )
+            .unwrap();
+
+            let bundle = crate::apple::PkPassBundle::read(pkpass.as_bytes()).unwrap();
+            assert_eq!(bundle.get("icon.png"), Some(&[1, 2, 3][..]));
+            assert!(bundle
+                .verify_signature(include_str!("apple/testdata/wwdr.pem"))
+                .is_ok());
+        });
+    }
+}
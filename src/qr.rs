@@ -0,0 +1,99 @@
+//! QR codes for save links
+//!
+//! Renders a save URL (or any landing link) as a scannable QR code for kiosks
+//! and printed confirmations. Full save URLs embed a JWT and can exceed QR
+//! capacity; shorten them first with a [`LinkShortener`](crate::shortener::LinkShortener).
+
+use crate::error::{PorterError, Result};
+use qrcode::render::svg;
+use qrcode::{Color, EcLevel, QrCode};
+
+/// Rendering options for QR codes
+#[derive(Debug, Clone)]
+pub struct QrOptions {
+    /// Minimum width and height of the image in pixels
+    pub min_size: u32,
+    pub dark_color: String,
+    pub light_color: String,
+    /// Whether to include the blank margin scanners rely on
+    pub quiet_zone: bool,
+}
+
+impl Default for QrOptions {
+    fn default() -> Self {
+        Self {
+            min_size: 256,
+            dark_color: "#000000".to_string(),
+            light_color: "#ffffff".to_string(),
+            quiet_zone: true,
+        }
+    }
+}
+
+/// Render a link as an SVG QR code
+///
+/// # Example
+///
+/// ```
+/// use porter::qr::{save_link_svg, QrOptions};
+///
+/// let svg = save_link_svg("https://pay.google.com/gp/v/save/abc", &QrOptions::default()).unwrap();
+/// assert!(svg.contains("<svg"));
+/// ```
+pub fn save_link_svg(url: &str, options: &QrOptions) -> Result<String> {
+    let code = encode(url)?;
+
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(options.min_size, options.min_size)
+        .dark_color(svg::Color(&options.dark_color))
+        .light_color(svg::Color(&options.light_color))
+        .quiet_zone(options.quiet_zone)
+        .build())
+}
+
+/// Encode a link and return its width and row-major modules (`true` is dark)
+///
+/// Useful for drawing the code with a custom renderer.
+pub fn save_link_modules(url: &str) -> Result<(usize, Vec<bool>)> {
+    let code = encode(url)?;
+    let modules = code
+        .to_colors()
+        .into_iter()
+        .map(|color| color == Color::Dark)
+        .collect();
+    Ok((code.width(), modules))
+}
+
+/// Encode with medium error correction, falling back to low for long links
+fn encode(url: &str) -> Result<QrCode> {
+    QrCode::with_error_correction_level(url, EcLevel::M)
+        .or_else(|_| QrCode::with_error_correction_level(url, EcLevel::L))
+        .map_err(|e| {
+            PorterError::ValidationError(format!(
+                "Link of {} bytes cannot be encoded as a QR code ({}); shorten it first",
+                url.len(),
+                e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_link_modules() {
+        let (width, modules) = save_link_modules("https://example.com/s/abc").unwrap();
+
+        assert_eq!(modules.len(), width * width);
+        assert!(modules.iter().any(|dark| *dark));
+    }
+
+    #[test]
+    fn test_oversized_link_is_rejected() {
+        let url = format!("https://pay.google.com/gp/v/save/{}", "a".repeat(5000));
+
+        assert!(save_link_svg(&url, &QrOptions::default()).is_err());
+    }
+}
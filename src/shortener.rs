@@ -0,0 +1,128 @@
+//! Shortening of save links
+//!
+//! Save URLs embed a signed JWT and can run to thousands of characters, which
+//! breaks SMS and some email clients. A [`LinkShortener`] attached to a client
+//! is applied to every generated save URL.
+
+use crate::error::{PorterError, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+/// Turns long save URLs into short links
+#[async_trait]
+pub trait LinkShortener: Send + Sync {
+    /// Return a short link that redirects to `url`
+    async fn shorten(&self, url: &str) -> Result<String>;
+}
+
+/// Shortener backed by a JSON HTTP API
+///
+/// The long URL is POSTed as `{"<request_field>": url}` and the short link is
+/// read from `response_field` of the JSON response, which may be a dotted
+/// path such as `data.link`.
+///
+/// # Example
+///
+/// ```
+/// use porter::shortener::HttpLinkShortener;
+///
+/// let shortener = HttpLinkShortener::new("https://api-ssl.bitly.com/v4/shorten")
+///     .bearer_token("token")
+///     .request_field("long_url")
+///     .response_field("link");
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpLinkShortener {
+    client: Client,
+    endpoint: String,
+    bearer_token: Option<String>,
+    request_field: String,
+    response_field: String,
+}
+
+impl HttpLinkShortener {
+    /// Create a shortener posting to `endpoint`
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            endpoint: endpoint.into(),
+            bearer_token: None,
+            request_field: "url".to_string(),
+            response_field: "short_url".to_string(),
+        }
+    }
+
+    /// Authenticate requests with a bearer token
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Set the request field holding the long URL (default `url`)
+    pub fn request_field(mut self, field: impl Into<String>) -> Self {
+        self.request_field = field.into();
+        self
+    }
+
+    /// Set the response field holding the short link (default `short_url`)
+    pub fn response_field(mut self, field: impl Into<String>) -> Self {
+        self.response_field = field.into();
+        self
+    }
+}
+
+#[async_trait]
+impl LinkShortener for HttpLinkShortener {
+    async fn shorten(&self, url: &str) -> Result<String> {
+        let mut body = serde_json::Map::new();
+        body.insert(self.request_field.clone(), Value::String(url.to_string()));
+
+        let mut request = self.client.post(&self.endpoint).json(&body);
+        if let Some(token) = &self.bearer_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PorterError::ApiError {
+                status: status.as_u16(),
+                message: response.text().await?,
+            });
+        }
+
+        let value: Value = response.json().await?;
+        field_at_path(&value, &self.response_field)
+            .map(str::to_string)
+            .ok_or_else(|| PorterError::ApiError {
+                status: status.as_u16(),
+                message: format!("Shortener response has no `{}` field", self.response_field),
+            })
+    }
+}
+
+/// Look up a string at a dotted path in a JSON value
+fn field_at_path<'a>(value: &'a Value, path: &str) -> Option<&'a str> {
+    path.split('.')
+        .try_fold(value, |value, key| value.get(key))?
+        .as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_field_at_path() {
+        let response = json!({ "data": { "link": "https://sho.rt/abc" }, "id": 1 });
+
+        assert_eq!(
+            field_at_path(&response, "data.link"),
+            Some("https://sho.rt/abc")
+        );
+        assert_eq!(field_at_path(&response, "data.missing"), None);
+        assert_eq!(field_at_path(&response, "id"), None);
+    }
+}
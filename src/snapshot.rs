@@ -0,0 +1,215 @@
+//! Golden-file snapshot helpers
+//!
+//! Serialize converted objects to canonical JSON (sorted keys, two-space
+//! indentation, trailing newline) and compare them with checked-in files, so
+//! serialization changes show up as test failures instead of API errors.
+//! Apple pass.json goes through [`canonical_pass_json`] first, which hides
+//! the per-pass authentication token.
+//!
+//! Set `PORTER_UPDATE_SNAPSHOTS=1` to rewrite snapshots instead of comparing.
+
+use crate::error::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Environment variable that switches snapshot checks to update mode
+pub const UPDATE_ENV: &str = "PORTER_UPDATE_SNAPSHOTS";
+
+/// Stands in for the `authenticationToken` of a pass.json snapshot
+pub const REDACTED: &str = "<redacted>";
+
+/// Result of checking a value against a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// The value matches the snapshot
+    Matched,
+    /// The snapshot was created or rewritten
+    Written,
+    /// The value differs from the snapshot
+    Mismatch { expected: String, actual: String },
+}
+
+/// Serialize a value to canonical JSON
+///
+/// # Example
+///
+/// ```
+/// use porter::snapshot::canonical_json;
+/// use serde_json::json;
+///
+/// let json = canonical_json(&json!({ "b": 1, "a": [true] })).unwrap();
+/// assert_eq!(json, "{\n  \"a\": [\n    true\n  ],\n  \"b\": 1\n}\n");
+/// ```
+pub fn canonical_json<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    // Going through Value sorts object keys
+    let value = serde_json::to_value(value)?;
+    let mut json = serde_json::to_string_pretty(&value)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Serialize an Apple pass.json to canonical JSON
+///
+/// Takes an [`ApplePass`](crate::apple::ApplePass) or the parsed `pass.json`
+/// of a bundle. The `authenticationToken` is replaced with [`REDACTED`], as
+/// it is random per pass.
+///
+/// # Example
+///
+/// ```
+/// use porter::snapshot::canonical_pass_json;
+/// use serde_json::json;
+///
+/// let json = canonical_pass_json(&json!({
+///     "serialNumber": "TICKET123",
+///     "authenticationToken": "3f0c4e1b9a7d2c65",
+/// }))
+/// .unwrap();
+/// assert_eq!(
+///     json,
+///     "{\n  \"authenticationToken\": \"<redacted>\",\n  \"serialNumber\": \"TICKET123\"\n}\n"
+/// );
+/// ```
+pub fn canonical_pass_json<T: Serialize + ?Sized>(pass: &T) -> Result<String> {
+    let mut value = serde_json::to_value(pass)?;
+    if let Some(token) = value.get_mut("authenticationToken") {
+        *token = Value::String(REDACTED.to_string());
+    }
+    canonical_json(&value)
+}
+
+/// Compare a value with the snapshot at `path`
+///
+/// Missing snapshots are written. When [`UPDATE_ENV`] is set, existing
+/// snapshots are rewritten too.
+pub fn check_snapshot<T: Serialize + ?Sized>(
+    path: impl AsRef<Path>,
+    value: &T,
+) -> Result<SnapshotOutcome> {
+    check_canonical(path.as_ref(), canonical_json(value)?)
+}
+
+/// Compare an Apple pass.json with the snapshot at `path`
+///
+/// Like [`check_snapshot`], after [`canonical_pass_json`].
+pub fn check_pass_snapshot<T: Serialize + ?Sized>(
+    path: impl AsRef<Path>,
+    pass: &T,
+) -> Result<SnapshotOutcome> {
+    check_canonical(path.as_ref(), canonical_pass_json(pass)?)
+}
+
+fn check_canonical(path: &Path, actual: String) -> Result<SnapshotOutcome> {
+    let update = std::env::var_os(UPDATE_ENV).is_some_and(|v| v != "0");
+
+    if !update {
+        match std::fs::read_to_string(path) {
+            Ok(expected) if expected.replace("\r\n", "\n") == actual => {
+                return Ok(SnapshotOutcome::Matched)
+            }
+            Ok(expected) => return Ok(SnapshotOutcome::Mismatch { expected, actual }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, actual)?;
+    Ok(SnapshotOutcome::Written)
+}
+
+/// Assert that a value matches the snapshot at `path`
+///
+/// # Panics
+///
+/// Panics with a line diff if the value differs from the snapshot.
+pub fn assert_snapshot<T: Serialize + ?Sized>(path: impl AsRef<Path>, value: &T) {
+    let path = path.as_ref();
+    report(path, check_snapshot(path, value));
+}
+
+/// Assert that an Apple pass.json matches the snapshot at `path`
+///
+/// # Panics
+///
+/// Panics with a line diff if the pass differs from the snapshot.
+pub fn assert_pass_snapshot<T: Serialize + ?Sized>(path: impl AsRef<Path>, pass: &T) {
+    let path = path.as_ref();
+    report(path, check_pass_snapshot(path, pass));
+}
+
+fn report(path: &Path, outcome: Result<SnapshotOutcome>) {
+    match outcome {
+        Ok(SnapshotOutcome::Matched | SnapshotOutcome::Written) => {}
+        Ok(SnapshotOutcome::Mismatch { expected, actual }) => panic!(
+            "snapshot {} does not match (set {}=1 to update):\n{}",
+            path.display(),
+            UPDATE_ENV,
+            line_diff(&expected, &actual)
+        ),
+        Err(e) => panic!("snapshot {} could not be checked: {}", path.display(), e),
+    }
+}
+
+/// Show the lines that differ between two snapshots
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut diff = String::new();
+
+    for line in 0..expected.len().max(actual.len()) {
+        match (expected.get(line), actual.get(line)) {
+            (Some(e), Some(a)) if e == a => {}
+            (e, a) => {
+                if let Some(e) = e {
+                    diff.push_str(&format!("{:>4} - {}\n", line + 1, e));
+                }
+                if let Some(a) = a {
+                    diff.push_str(&format!("{:>4} + {}\n", line + 1, a));
+                }
+            }
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_check_snapshot() {
+        let path = std::env::temp_dir()
+            .join(format!("porter-snapshot-{}", std::process::id()))
+            .join("value.json");
+        let _ = std::fs::remove_file(&path);
+
+        if std::env::var_os(UPDATE_ENV).is_none() {
+            assert_eq!(
+                check_snapshot(&path, &json!({ "a": 1 })).unwrap(),
+                SnapshotOutcome::Written
+            );
+            assert_eq!(
+                check_snapshot(&path, &json!({ "a": 1 })).unwrap(),
+                SnapshotOutcome::Matched
+            );
+            assert!(matches!(
+                check_snapshot(&path, &json!({ "a": 2 })).unwrap(),
+                SnapshotOutcome::Mismatch { .. }
+            ));
+        }
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(
+            line_diff("a\nb\n", "a\nc\nd\n"),
+            "   2 - b\n   2 + c\n   3 + d\n"
+        );
+    }
+}
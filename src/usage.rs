@@ -0,0 +1,223 @@
+//! Wallet API usage tracking
+//!
+//! A [`UsageTracker`] attached to a client with
+//! [`GoogleWalletClient::with_usage_tracker`](crate::google::GoogleWalletClient::with_usage_tracker)
+//! counts requests per endpoint and keeps the latest rate-limit headers, so
+//! bulk jobs can throttle themselves and dashboards can show consumption.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Request counts for one endpoint, such as `POST genericObject`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointUsage {
+    pub requests: u64,
+    /// Requests answered with an error status
+    pub errors: u64,
+    /// Requests rejected with 429 Too Many Requests
+    pub rate_limited: u64,
+}
+
+/// Rate-limit headers from the most recent response that carried them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitHeaders {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Seconds until the limit resets
+    pub reset: Option<u64>,
+    /// Seconds to wait before retrying, from `Retry-After`
+    pub retry_after: Option<u64>,
+}
+
+/// Point-in-time view of API consumption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub endpoints: BTreeMap<String, EndpointUsage>,
+    pub total_requests: u64,
+    /// Requests sent in the current quota window
+    pub window_requests: u64,
+    /// Requests left in the current quota window, if a quota is configured
+    pub remaining_estimate: Option<u64>,
+    pub rate_limit: Option<RateLimitHeaders>,
+}
+
+/// Counts requests made through one or more clients
+pub struct UsageTracker {
+    quota: Option<(u64, Duration)>,
+    state: Mutex<UsageState>,
+}
+
+#[derive(Default)]
+struct UsageState {
+    endpoints: BTreeMap<String, EndpointUsage>,
+    window: VecDeque<Instant>,
+    rate_limit: Option<RateLimitHeaders>,
+}
+
+impl UsageTracker {
+    /// Create a tracker without a quota
+    pub fn new() -> Self {
+        Self {
+            quota: None,
+            state: Mutex::new(UsageState::default()),
+        }
+    }
+
+    /// Estimate remaining quota against `limit` requests per `window`
+    ///
+    /// Google Wallet quotas are per project and set in the Cloud console,
+    /// so the limit has to be supplied here.
+    pub fn with_quota(mut self, limit: u64, window: Duration) -> Self {
+        self.quota = Some((limit, window));
+        self
+    }
+
+    /// Record a response from the Wallet API
+    pub fn record(&self, method: &reqwest::Method, path: &str, response: &reqwest::Response) {
+        let headers = response.headers();
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        let rate_limit = RateLimitHeaders {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining"),
+            reset: header("x-ratelimit-reset"),
+            retry_after: header("retry-after"),
+        };
+        self.record_status(
+            endpoint(method, path),
+            response.status().as_u16(),
+            rate_limit,
+            Instant::now(),
+        );
+    }
+
+    fn record_status(
+        &self,
+        endpoint: String,
+        status: u16,
+        rate_limit: RateLimitHeaders,
+        now: Instant,
+    ) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let usage = state.endpoints.entry(endpoint).or_default();
+        usage.requests += 1;
+        if status >= 400 {
+            usage.errors += 1;
+        }
+        if status == 429 {
+            usage.rate_limited += 1;
+        }
+
+        if rate_limit != RateLimitHeaders::default() {
+            state.rate_limit = Some(rate_limit);
+        }
+        if let Some((_, window)) = self.quota {
+            state.window.push_back(now);
+            prune(&mut state.window, window, now);
+        }
+    }
+
+    /// Current usage
+    pub fn report(&self) -> UsageReport {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let window_requests = match self.quota {
+            Some((_, window)) => {
+                prune(&mut state.window, window, Instant::now());
+                state.window.len() as u64
+            }
+            None => 0,
+        };
+
+        UsageReport {
+            total_requests: state.endpoints.values().map(|u| u.requests).sum(),
+            endpoints: state.endpoints.clone(),
+            window_requests,
+            remaining_estimate: self
+                .quota
+                .map(|(limit, _)| limit.saturating_sub(window_requests)),
+            rate_limit: state.rate_limit.clone(),
+        }
+    }
+
+    /// Clear all counts
+    pub fn reset(&self) {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = UsageState::default();
+    }
+}
+
+impl Default for UsageTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drop requests older than `window`
+fn prune(requests: &mut VecDeque<Instant>, window: Duration, now: Instant) {
+    while requests
+        .front()
+        .is_some_and(|sent| now.duration_since(*sent) >= window)
+    {
+        requests.pop_front();
+    }
+}
+
+/// Endpoint name for a request, without ids or query parameters
+///
+/// `/genericObject/issuer.pass/addMessage` becomes `POST genericObject/addMessage`.
+fn endpoint(method: &reqwest::Method, path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    let mut segments = path.trim_start_matches('/').split('/');
+    let resource = segments.next().unwrap_or_default();
+    match segments.nth(1) {
+        Some(action) => format!("{} {}/{}", method, resource, action),
+        None => format!("{} {}", method, resource),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_counts_and_quota() {
+        let tracker = UsageTracker::new().with_quota(3, Duration::from_secs(60));
+        let start = Instant::now();
+        let get = endpoint(&reqwest::Method::GET, "/genericObject/issuer.a");
+        assert_eq!(get, "GET genericObject");
+        assert_eq!(
+            endpoint(&reqwest::Method::POST, "/genericObject/issuer.a/addMessage"),
+            "POST genericObject/addMessage"
+        );
+
+        tracker.record_status(get.clone(), 200, RateLimitHeaders::default(), start);
+        tracker.record_status(
+            get.clone(),
+            429,
+            RateLimitHeaders {
+                retry_after: Some(30),
+                ..Default::default()
+            },
+            start,
+        );
+
+        let report = tracker.report();
+        assert_eq!(report.total_requests, 2);
+        assert_eq!(
+            report.endpoints[&get],
+            EndpointUsage {
+                requests: 2,
+                errors: 1,
+                rate_limited: 1
+            }
+        );
+        assert_eq!(report.remaining_estimate, Some(1));
+        assert_eq!(report.rate_limit.unwrap().retry_after, Some(30));
+    }
+}
@@ -0,0 +1,420 @@
+//! Outbound delivery of pass events to user endpoints
+//!
+//! Events (for example verified [`CallbackMessage`](crate::google::CallbackMessage)s)
+//! are queued and posted as JSON to every configured endpoint. Failed
+//! deliveries are retried with exponential backoff; deliveries that exhaust
+//! their attempts are reported as they fail, through the channel from
+//! [`WebhookQueue::with_failure_channel`], or when the queue is shut down.
+//!
+//! The queue lives in memory only. Events still queued or being retried are
+//! lost if the process exits, so persist events that must survive a restart
+//! before enqueueing them.
+
+use crate::error::{PorterError, Result};
+use reqwest::Client;
+use ring::hmac;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Header carrying the payload signature
+pub const SIGNATURE_HEADER: &str = "X-Porter-Signature";
+
+/// Endpoint that receives webhook deliveries
+#[derive(Debug, Clone)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    /// Shared secret used to sign payloads, if the receiver verifies them
+    pub secret: Option<String>,
+}
+
+impl WebhookEndpoint {
+    /// Create an endpoint without payload signing
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: None,
+        }
+    }
+
+    /// Sign payloads sent to this endpoint with a shared secret
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+}
+
+/// How failed deliveries are retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts per delivery, including the first
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Time allowed to connect to an endpoint
+    pub connect_timeout: Duration,
+    /// Time allowed for a whole attempt, from connecting to the response;
+    /// an attempt that runs out is retried
+    pub request_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the given retry (1 for the first retry)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// A delivery that could not be completed
+#[derive(Debug, Clone)]
+pub struct DeliveryFailure {
+    pub url: String,
+    pub payload: String,
+    pub attempts: u32,
+    pub error: String,
+}
+
+/// Queue that delivers events to webhook endpoints in the background
+///
+/// Must be created inside a Tokio runtime. Pending events are held in
+/// memory and do not survive a restart.
+///
+/// # Example
+///
+/// ```no_run
+/// use porter::webhook::{RetryPolicy, WebhookEndpoint, WebhookQueue};
+///
+/// # async fn example(event: porter::google::CallbackMessage) -> porter::error::Result<()> {
+/// let queue = WebhookQueue::new(
+///     vec![WebhookEndpoint::new("https://example.com/hooks/wallet").with_secret("s3cret")],
+///     RetryPolicy::default(),
+/// );
+///
+/// queue.enqueue(&event)?;
+///
+/// let failures = queue.shutdown().await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct WebhookQueue {
+    sender: mpsc::UnboundedSender<String>,
+    worker: JoinHandle<Vec<DeliveryFailure>>,
+}
+
+impl WebhookQueue {
+    /// Create a queue delivering to the given endpoints
+    pub fn new(endpoints: Vec<WebhookEndpoint>, policy: RetryPolicy) -> Self {
+        Self::spawn(endpoints, policy, None)
+    }
+
+    /// Create a queue that reports failed deliveries as they happen
+    ///
+    /// Each delivery that exhausts its attempts is sent to the returned
+    /// receiver, so a long-running service can log or persist it instead of
+    /// waiting for [`shutdown`](Self::shutdown). Failures are returned from
+    /// `shutdown` only if the receiver has been dropped.
+    ///
+    /// ```no_run
+    /// use porter::webhook::{RetryPolicy, WebhookEndpoint, WebhookQueue};
+    ///
+    /// # async fn example() {
+    /// let (queue, mut failures) = WebhookQueue::with_failure_channel(
+    ///     vec![WebhookEndpoint::new("https://example.com/hooks/wallet")],
+    ///     RetryPolicy::default(),
+    /// );
+    /// tokio::spawn(async move {
+    ///     while let Some(failure) = failures.recv().await {
+    ///         eprintln!("{} undeliverable: {}", failure.url, failure.error);
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn with_failure_channel(
+        endpoints: Vec<WebhookEndpoint>,
+        policy: RetryPolicy,
+    ) -> (Self, mpsc::UnboundedReceiver<DeliveryFailure>) {
+        let (failure_sender, failures) = mpsc::unbounded_channel();
+        (
+            Self::spawn(endpoints, policy, Some(failure_sender)),
+            failures,
+        )
+    }
+
+    fn spawn(
+        endpoints: Vec<WebhookEndpoint>,
+        policy: RetryPolicy,
+        failure_sender: Option<mpsc::UnboundedSender<DeliveryFailure>>,
+    ) -> Self {
+        // Fails only where Client::new() would panic too: no TLS backend or
+        // resolver configuration
+        let client = Client::builder()
+            .connect_timeout(policy.connect_timeout)
+            .timeout(policy.request_timeout)
+            .build()
+            .expect("HTTP client could not be initialized");
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let worker = tokio::spawn(run_worker(
+            client,
+            Arc::new(endpoints),
+            Arc::new(policy),
+            receiver,
+            failure_sender,
+        ));
+
+        Self { sender, worker }
+    }
+
+    /// Queue an event for delivery to every endpoint
+    pub fn enqueue<T: Serialize>(&self, event: &T) -> Result<()> {
+        let payload = serde_json::to_string(event)?;
+        self.sender
+            .send(payload)
+            .map_err(|_| PorterError::ConfigError("Webhook queue has shut down".to_string()))
+    }
+
+    /// Stop accepting events, wait for pending deliveries and return the
+    /// failures not already sent to a failure channel
+    pub async fn shutdown(self) -> Vec<DeliveryFailure> {
+        drop(self.sender);
+        self.worker.await.unwrap_or_default()
+    }
+}
+
+async fn run_worker(
+    client: Client,
+    endpoints: Arc<Vec<WebhookEndpoint>>,
+    policy: Arc<RetryPolicy>,
+    mut receiver: mpsc::UnboundedReceiver<String>,
+    failure_sender: Option<mpsc::UnboundedSender<DeliveryFailure>>,
+) -> Vec<DeliveryFailure> {
+    let mut deliveries = tokio::task::JoinSet::new();
+    let mut failures = Vec::new();
+    let mut report = |failure: DeliveryFailure| {
+        // Keep the failure for shutdown if nobody is listening any more
+        let unsent = match &failure_sender {
+            Some(sender) => sender.send(failure).err().map(|e| e.0),
+            None => Some(failure),
+        };
+        failures.extend(unsent);
+    };
+
+    loop {
+        tokio::select! {
+            payload = receiver.recv() => {
+                let Some(payload) = payload else { break };
+                for endpoint in endpoints.iter() {
+                    deliveries.spawn(deliver(
+                        client.clone(),
+                        endpoint.clone(),
+                        policy.clone(),
+                        payload.clone(),
+                    ));
+                }
+            }
+            Some(result) = deliveries.join_next(), if !deliveries.is_empty() => {
+                if let Ok(Err(failure)) = result {
+                    report(failure);
+                }
+            }
+        }
+    }
+
+    while let Some(result) = deliveries.join_next().await {
+        if let Ok(Err(failure)) = result {
+            report(failure);
+        }
+    }
+
+    failures
+}
+
+/// Deliver a payload to one endpoint, retrying transient failures
+async fn deliver(
+    client: Client,
+    endpoint: WebhookEndpoint,
+    policy: Arc<RetryPolicy>,
+    payload: String,
+) -> std::result::Result<(), DeliveryFailure> {
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let mut request = client
+            .post(&endpoint.url)
+            .header("Content-Type", "application/json")
+            .body(payload.clone());
+        if let Some(secret) = &endpoint.secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret, &payload, unix_now()));
+        }
+
+        let (error, retryable) = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                let retryable = status.is_server_error() || status.as_u16() == 429;
+                (format!("Endpoint returned {}", status), retryable)
+            }
+            Err(e) if e.is_timeout() => (
+                format!(
+                    "Endpoint did not respond within {:?}",
+                    policy.request_timeout
+                ),
+                true,
+            ),
+            Err(e) => (e.to_string(), true),
+        };
+
+        if !retryable || attempts >= policy.max_attempts {
+            return Err(DeliveryFailure {
+                url: endpoint.url,
+                payload,
+                attempts,
+                error,
+            });
+        }
+
+        tokio::time::sleep(policy.backoff(attempts)).await;
+    }
+}
+
+/// Build the signature header value for a payload
+///
+/// The signature is an HMAC-SHA256 over `"{timestamp}.{payload}"`, formatted as
+/// `t={timestamp},v1={hex signature}` so receivers can reject replays.
+pub fn sign_payload(secret: &str, payload: &str, timestamp: u64) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, format!("{}.{}", timestamp, payload).as_bytes());
+    let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+
+    format!("t={},v1={}", timestamp, hex)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            ..Default::default()
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(4), Duration::from_secs(8));
+        assert_eq!(policy.backoff(5), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        let signature = sign_payload("secret", "{}", 1700000000);
+
+        assert!(signature.starts_with("t=1700000000,v1="));
+        assert_eq!(signature.len(), "t=1700000000,v1=".len() + 64);
+        assert_ne!(signature, sign_payload("other", "{}", 1700000000));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_is_reported() {
+        let queue = WebhookQueue::new(
+            vec![WebhookEndpoint::new("http://127.0.0.1:9/hook")],
+            RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                ..Default::default()
+            },
+        );
+
+        queue
+            .enqueue(&serde_json::json!({ "event": "save" }))
+            .unwrap();
+        let failures = queue.shutdown().await;
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_sent_while_running() {
+        let (queue, mut failures) = WebhookQueue::with_failure_channel(
+            vec![WebhookEndpoint::new("http://127.0.0.1:9/hook")],
+            RetryPolicy {
+                max_attempts: 1,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                ..Default::default()
+            },
+        );
+
+        queue
+            .enqueue(&serde_json::json!({ "event": "save" }))
+            .unwrap();
+        let failure = failures.recv().await.unwrap();
+        assert_eq!(failure.url, "http://127.0.0.1:9/hook");
+        assert_eq!(failure.attempts, 1);
+
+        assert!(queue.shutdown().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_timeouts_are_retried() {
+        // Accepts connections and never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut sockets = vec![];
+            while let Ok((socket, _)) = listener.accept().await {
+                sockets.push(socket);
+            }
+        });
+
+        let queue = WebhookQueue::new(
+            vec![WebhookEndpoint::new(url)],
+            RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                request_timeout: Duration::from_millis(50),
+                ..Default::default()
+            },
+        );
+
+        queue
+            .enqueue(&serde_json::json!({ "event": "save" }))
+            .unwrap();
+        let failures = queue.shutdown().await;
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].attempts, 2);
+        assert!(failures[0].error.contains("did not respond"));
+    }
+}